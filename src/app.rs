@@ -1,12 +1,20 @@
 use std::error;
 use std::sync::Arc;
+use std::time::SystemTime;
 
 use crate::{
+    article::{self, highlight::Highlighter, Link},
+    config::{load_config, Config},
+    external::Viewer,
     feed::{
-        entries::Entry,
+        entries::{Entry, FeedDocument},
         feeds::{load_feeds, Feed},
+        health::HealthStore,
+        reading::ReadingStore,
+        store::EntryStore,
     },
-    screen::Screen,
+    images::ImageStore,
+    screen::{Panes, Screen},
     tts::{NarrationUiState, TTS},
 };
 use ratatui::widgets::ListState;
@@ -36,6 +44,7 @@ pub struct EntryList {
 pub enum ConfirmationKind {
     DeleteFeed,
     DownloadTtsModel,
+    RunCommand,
 }
 
 #[derive(Debug)]
@@ -60,6 +69,21 @@ impl ConfirmationChoice {
     }
 }
 
+/// An article left by following a link, to return to with `Esc`.
+#[derive(Debug)]
+pub struct HistoryEntry {
+    pub feed: Option<Feed>,
+    pub entry: Entry,
+    pub scroll_offset: u16,
+}
+
+/// The links and images of the open article, to pick one to open.
+#[derive(Debug)]
+pub struct LinkPicker {
+    pub links: Vec<Link>,
+    pub state: ListState,
+}
+
 #[derive(Debug)]
 pub enum Notice {
     Error(String),
@@ -69,24 +93,53 @@ pub enum Notice {
 #[derive(Debug)]
 pub struct App {
     pub running: bool,
+    pub config: Config,
     pub screen: Screen,
+    pub panes: Panes,
     pub input: Input,
     pub input_mode: InputMode,
     pub feed_list: FeedList,
+    pub health: HealthStore,
+    /// `entries.json`, reloaded before each use as the daemon writes it too.
+    pub entries: EntryStore,
     pub entry_list: EntryList,
+    /// The feed whose entries are listed; `None` for a document opened from
+    /// the command line.
+    pub current_feed: Option<Feed>,
     pub current_entry: Entry,
+    /// Articles left by following links, the latest last.
+    pub history: Vec<HistoryEntry>,
+    pub reading: ReadingStore,
+    pub offline_since: Option<SystemTime>,
     pub scroll_offset: u16,
     pub max_scroll: u16,
     pub confirmation_popup: Option<ConfirmationPopup>,
+    pub show_feed_details: bool,
+    pub link_picker: Option<LinkPicker>,
     pub notice: Option<Notice>,
     pub tts: Option<Arc<TTS>>,
     pub tts_downloading: bool,
     pub narration: NarrationUiState,
+    /// Narration runs in the daemon and keeps playing after fead quits.
+    pub daemon_attached: bool,
+    /// Set to hand the open article to a pager or editor; the main loop
+    /// suspends the TUI while it runs.
+    pub external_viewer: Option<Viewer>,
+    pub images: ImageStore,
+    pub highlighter: Highlighter,
+    /// Wrap long code lines instead of scrolling them sideways.
+    pub code_wrap: bool,
+    /// Columns code blocks are scrolled sideways by when not wrapped.
+    pub code_scroll: u16,
 }
 
 impl App {
     pub fn new() -> AppResult<Self> {
+        let config = load_config()?;
         let feeds = load_feeds()?;
+        let health = HealthStore::load()?;
+        let entries = EntryStore::load()?;
+        let reading = ReadingStore::load()?;
         let mut feed_state = ListState::default();
         if !feeds.is_empty() {
             feed_state.select_first();
@@ -95,24 +148,40 @@ impl App {
         Ok(Self {
             running: true,
             screen: Screen::Home,
+            panes: Panes::Single,
             input: Input::default(),
             input_mode: InputMode::Normal,
             feed_list: FeedList {
                 items: feeds,
                 state: feed_state,
             },
+            health,
+            entries,
             entry_list: EntryList {
                 items: vec![],
                 state: ListState::default(),
             },
+            current_feed: None,
             current_entry: Entry::default(),
+            history: Vec::new(),
+            reading,
+            offline_since: None,
             scroll_offset: 0,
             max_scroll: 0,
             confirmation_popup: None,
+            show_feed_details: false,
+            link_picker: None,
             notice: None,
             tts: None,
             tts_downloading: false,
             narration: NarrationUiState::Idle,
+            daemon_attached: false,
+            external_viewer: None,
+            images: ImageStore::new(&config.images),
+            highlighter: Highlighter::default(),
+            code_wrap: false,
+            code_scroll: 0,
+            config,
         })
     }
 
@@ -128,6 +197,169 @@ impl App {
         self.notice = Some(Notice::Info(message.into()));
     }
 
+    pub fn open_document(&mut self, document: FeedDocument) {
+        self.current_feed = None;
+        self.entry_list.items = document.entries;
+        self.entry_list.state = ListState::default();
+        if !self.entry_list.items.is_empty() {
+            self.entry_list.state.select_first();
+        }
+        if document.recovered {
+            self.show_error("The feed is malformed; showing the entries that could be recovered.");
+        } else if !document.title.is_empty() {
+            self.show_info(format!("Reading {}.", document.title));
+        }
+        self.screen = Screen::Feed;
+        self.preview_selection();
+    }
+
+    /// Lists the recently read articles as if they were a feed.
+    pub fn open_recent(&mut self) {
+        if self.reading.recent().is_empty() {
+            self.show_info("Nothing read yet.");
+            return;
+        }
+        self.current_feed = None;
+        self.offline_since = None;
+        self.entry_list.items = self
+            .reading
+            .recent()
+            .iter()
+            .map(|recent| recent.entry.clone())
+            .collect();
+        self.entry_list.state = ListState::default();
+        self.entry_list.state.select_first();
+        self.show_info("Recently read articles.");
+        self.screen = Screen::Feed;
+        self.preview_selection();
+    }
+
+    /// Picks the layout for a terminal `width` columns wide.
+    pub fn resize(&mut self, width: u16) {
+        let panes = Panes::for_width(width);
+        if panes != self.panes {
+            self.panes = panes;
+            self.preview_selection();
+        }
+    }
+
+    /// Shows the selected entry in the preview pane while the article list
+    /// has focus. It is not marked read until it is opened.
+    pub fn preview_selection(&mut self) {
+        if !self.panes.is_split() || self.screen != Screen::Feed {
+            return;
+        }
+        // The selection may run past the end until the list is drawn.
+        let last = self.entry_list.items.len().checked_sub(1);
+        let Some(entry) = self
+            .entry_list
+            .state
+            .selected()
+            .zip(last)
+            .map(|(selected, last)| &self.entry_list.items[selected.min(last)])
+        else {
+            return;
+        };
+        if entry.key() == self.current_entry.key() {
+            return;
+        }
+        self.current_entry = entry.clone();
+        self.restore_position();
+    }
+
+    /// Shows the article where it was last left and puts it at the top of
+    /// the recently read list.
+    pub fn open_article(&mut self, entry: Entry) -> AppResult<()> {
+        self.current_entry = entry;
+        self.restore_position();
+        self.screen = Screen::Article;
+        self.mark_read()?;
+        self.remember_position()
+    }
+
+    /// Scrolls the current entry to where it was last left.
+    fn restore_position(&mut self) {
+        let feed_url = self.article_feed().map(|feed| feed.url.as_str());
+        self.scroll_offset = self.reading.position(feed_url, &self.current_entry);
+        self.max_scroll = 0;
+        self.code_scroll = 0;
+    }
+
+    /// Marks the open article read in `entries.json`, first storing the
+    /// listed entries when its feed has none stored yet.
+    fn mark_read(&mut self) -> AppResult<()> {
+        let Some(feed) = self.article_feed().cloned() else {
+            return Ok(());
+        };
+        let key = self.current_entry.key();
+        self.entries.reload()?;
+        if self.entries.mark_read(&feed.url, key, true)? {
+            return Ok(());
+        }
+        let listed = self
+            .list_feed()
+            .is_some_and(|listed| listed.url == feed.url);
+        let entries = if listed {
+            self.entry_list.items.clone()
+        } else {
+            vec![self.current_entry.clone()]
+        };
+        self.entries.merge(
+            &feed.url,
+            &FeedDocument {
+                title: feed.title.clone(),
+                entries,
+                recovered: false,
+            },
+        )?;
+        self.entries.mark_read(&feed.url, key, true)?;
+        Ok(())
+    }
+
+    /// Saves how far the open article has been read.
+    pub fn remember_position(&mut self) -> AppResult<()> {
+        if self.screen != Screen::Article {
+            return Ok(());
+        }
+        let feed_url = self.article_feed().map(|feed| feed.url.clone());
+        self.reading
+            .record(feed_url.as_deref(), &self.current_entry, self.scroll_offset)
+    }
+
+    /// The feed whose entries are listed. Following a link to an article of
+    /// another feed changes the current feed but not the list.
+    pub fn list_feed(&self) -> Option<&Feed> {
+        match self.history.first() {
+            Some(root) => root.feed.as_ref(),
+            None => self.current_feed.as_ref(),
+        }
+    }
+
+    /// The feed the open article belongs to, also when it was opened from
+    /// the recently read list.
+    pub fn article_feed(&self) -> Option<&Feed> {
+        self.current_feed.as_ref().or_else(|| {
+            let key = self.current_entry.key();
+            let url = self
+                .reading
+                .recent()
+                .iter()
+                .find(|recent| recent.entry.key() == key)?
+                .feed_url
+                .as_deref()?;
+            self.feed_list.items.iter().find(|feed| feed.url == url)
+        })
+    }
+
+    pub fn open_link_picker(&mut self) {
+        let links = article::parse(&self.current_entry).links;
+        let mut state = ListState::default();
+        if !links.is_empty() {
+            state.select_first();
+        }
+        self.link_picker = Some(LinkPicker { links, state });
+    }
+
     pub fn update_article_viewport(&mut self, line_count: usize, viewport_height: u16) {
         self.max_scroll = line_count.saturating_sub(viewport_height as usize) as u16;
         self.scroll_offset = self.scroll_offset.min(self.max_scroll);
@@ -0,0 +1,138 @@
+use std::{collections::HashMap, fmt, sync::Arc};
+
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+};
+use syntect::{
+    easy::HighlightLines,
+    highlighting::{FontStyle, Theme, ThemeSet},
+    parsing::SyntaxSet,
+    util::LinesWithEndings,
+};
+
+use crate::feed::cache::fnv1a;
+
+use super::CodeBlock;
+
+const THEME: &str = "base16-ocean.dark";
+const TAB: &str = "    ";
+/// Highlighted blocks kept before the cache starts over.
+const CACHE_LIMIT: usize = 256;
+
+/// Colours code blocks by their language hint. The syntax definitions are
+/// loaded the first time a block is highlighted, and each block's lines are
+/// kept so redraws stay cheap.
+#[derive(Default)]
+pub struct Highlighter {
+    syntect: Option<(SyntaxSet, Theme)>,
+    cache: HashMap<u64, Arc<Vec<Line<'static>>>>,
+}
+
+impl fmt::Debug for Highlighter {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("Highlighter")
+            .field("loaded", &self.syntect.is_some())
+            .field("cached", &self.cache.len())
+            .finish()
+    }
+}
+
+impl Highlighter {
+    /// The block's lines, coloured when its language is known and as plain
+    /// text otherwise. Tabs are expanded to four spaces.
+    pub fn highlight(&mut self, block: &CodeBlock) -> Arc<Vec<Line<'static>>> {
+        let language = block.language.as_deref().unwrap_or_default();
+        let key = fnv1a(format!("{language}\0{}", block.code).as_bytes());
+        if let Some(lines) = self.cache.get(&key) {
+            return lines.clone();
+        }
+
+        let code = block.code.replace('\t', TAB);
+        let lines = Arc::new(if language.is_empty() {
+            plain(&code)
+        } else {
+            let (syntaxes, theme) = self.syntect.get_or_insert_with(|| {
+                let mut themes = ThemeSet::load_defaults();
+                let theme = themes.themes.remove(THEME).unwrap_or_default();
+                (SyntaxSet::load_defaults_newlines(), theme)
+            });
+            match syntaxes.find_syntax_by_token(language) {
+                Some(syntax) => {
+                    let mut highlighter = HighlightLines::new(syntax, theme);
+                    LinesWithEndings::from(&code)
+                        .map(|line| match highlighter.highlight_line(line, syntaxes) {
+                            Ok(ranges) => Line::from(
+                                ranges
+                                    .into_iter()
+                                    .map(|(style, text)| {
+                                        Span::styled(
+                                            text.trim_end_matches(['\r', '\n']).to_string(),
+                                            span_style(style),
+                                        )
+                                    })
+                                    .collect::<Vec<_>>(),
+                            ),
+                            Err(_) => Line::raw(line.trim_end_matches(['\r', '\n']).to_string()),
+                        })
+                        .collect()
+                }
+                None => plain(&code),
+            }
+        });
+
+        if self.cache.len() >= CACHE_LIMIT {
+            self.cache.clear();
+        }
+        self.cache.insert(key, lines.clone());
+        lines
+    }
+}
+
+fn plain(code: &str) -> Vec<Line<'static>> {
+    code.lines()
+        .map(|line| Line::raw(line.to_string()))
+        .collect()
+}
+
+fn span_style(style: syntect::highlighting::Style) -> Style {
+    let color = style.foreground;
+    let mut span = Style::new().fg(Color::Rgb(color.r, color.g, color.b));
+    if style.font_style.contains(FontStyle::BOLD) {
+        span = span.add_modifier(Modifier::BOLD);
+    }
+    if style.font_style.contains(FontStyle::ITALIC) {
+        span = span.add_modifier(Modifier::ITALIC);
+    }
+    span
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::style::Color;
+
+    use super::{CodeBlock, Highlighter};
+
+    #[test]
+    fn colours_known_languages_and_leaves_others_plain() {
+        let mut highlighter = Highlighter::default();
+        let rust = highlighter.highlight(&CodeBlock {
+            language: Some("rust".to_string()),
+            code: "fn main() {\n\tlet x = 1;\n}".to_string(),
+        });
+        assert_eq!(rust.len(), 3);
+        assert_eq!(rust[1].to_string(), "    let x = 1;");
+        assert!(rust[0]
+            .spans
+            .iter()
+            .any(|span| matches!(span.style.fg, Some(Color::Rgb(..)))));
+
+        let unknown = highlighter.highlight(&CodeBlock {
+            language: Some("no-such-language".to_string()),
+            code: "plain text".to_string(),
+        });
+        assert_eq!(unknown[0].to_string(), "plain text");
+        assert_eq!(unknown[0].spans[0].style.fg, None);
+    }
+}
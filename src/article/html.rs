@@ -0,0 +1,242 @@
+use std::rc::Rc;
+
+use htmd::{
+    element_handler::{HandlerResult, Handlers},
+    Element, HtmlToMarkdown, Node,
+};
+use markup5ever_rcdom::NodeData;
+
+/// Converts an article's HTML to Markdown, keeping the language of code
+/// blocks and turning every table into a pipe table, with or without a
+/// header row.
+pub(super) fn to_markdown(html: &str) -> String {
+    HtmlToMarkdown::builder()
+        .skip_tags(vec!["script", "style", "noscript"])
+        .add_handler(vec!["pre"], pre)
+        .add_handler(vec!["table"], table)
+        .build()
+        .convert(html)
+        .unwrap_or_else(|_| html.to_string())
+}
+
+/// Words in the document's text, treating every tag as a word break.
+pub(super) fn word_count(html: &str) -> usize {
+    let Ok(document) = HtmlToMarkdown::new().html_to_tree(html) else {
+        return html.split_whitespace().count();
+    };
+    let mut text = String::new();
+    collect_words(&document, &mut text);
+    text.split_whitespace().count()
+}
+
+fn collect_words(node: &Rc<Node>, output: &mut String) {
+    match &node.data {
+        NodeData::Text { contents } => output.push_str(&contents.borrow()),
+        NodeData::Element { name, .. }
+            if matches!(
+                name.local.as_ref(),
+                "script" | "style" | "noscript" | "template"
+            ) =>
+        {
+            return;
+        }
+        NodeData::Element { .. } => output.push(' '),
+        _ => {}
+    }
+    for child in node.children.borrow().iter() {
+        collect_words(child, output);
+    }
+}
+
+fn pre(_: &dyn Handlers, element: Element) -> Option<HandlerResult> {
+    let mut code = String::new();
+    collect_code(element.node, &mut code);
+    let code = code.trim_matches('\n');
+    let language = language(
+        element
+            .attrs
+            .iter()
+            .map(|attr| (attr.name.local.as_ref(), attr.value.as_ref())),
+    )
+    .or_else(|| {
+        element
+            .node
+            .children
+            .borrow()
+            .iter()
+            .find_map(|child| match &child.data {
+                NodeData::Element { name, attrs, .. } if name.local.as_ref() == "code" => language(
+                    attrs
+                        .borrow()
+                        .iter()
+                        .map(|attr| (attr.name.local.as_ref(), attr.value.as_ref())),
+                ),
+                _ => None,
+            })
+    })
+    .unwrap_or_default();
+
+    // The fence must be longer than any run of backticks inside.
+    let longest_run = code
+        .split(|character| character != '`')
+        .map(str::len)
+        .max()
+        .unwrap_or(0);
+    let fence = "`".repeat(longest_run.max(2) + 1);
+    Some(format!("\n\n{fence}{language}\n{code}\n{fence}\n\n").into())
+}
+
+/// The language named by `language-*`, `lang-*` or `highlight-*` classes,
+/// or a `data-lang` attribute.
+fn language<'a>(mut attrs: impl Iterator<Item = (&'a str, &'a str)>) -> Option<String> {
+    attrs
+        .find_map(|(name, value)| match name {
+            "data-lang" | "data-language" => Some(value.trim().to_string()),
+            "class" => value.split_whitespace().find_map(|class| {
+                ["language-", "lang-", "highlight-"]
+                    .iter()
+                    .find_map(|prefix| class.strip_prefix(prefix))
+                    .map(str::to_string)
+            }),
+            _ => None,
+        })
+        .filter(|language| !language.is_empty() && !language.contains('`'))
+}
+
+fn collect_code(node: &Rc<Node>, output: &mut String) {
+    match &node.data {
+        NodeData::Text { contents } => output.push_str(&contents.borrow()),
+        NodeData::Element { name, .. } if name.local.as_ref() == "br" => output.push('\n'),
+        _ => {}
+    }
+    for child in node.children.borrow().iter() {
+        collect_code(child, output);
+    }
+}
+
+fn table(handlers: &dyn Handlers, element: Element) -> Option<HandlerResult> {
+    let mut caption = String::new();
+    let mut rows: Vec<(bool, Vec<Cell>)> = Vec::new();
+    for child in element.node.children.borrow().iter() {
+        match tag(child) {
+            Some("caption") => caption = inline(handlers, child),
+            Some("thead" | "tbody" | "tfoot") => {
+                let head = tag(child) == Some("thead");
+                for row in child.children.borrow().iter() {
+                    if tag(row) == Some("tr") {
+                        rows.push((head, cells(handlers, row)));
+                    }
+                }
+            }
+            Some("tr") => rows.push((false, cells(handlers, child))),
+            _ => {}
+        }
+    }
+    rows.retain(|(_, cells)| !cells.is_empty());
+    let columns = rows.iter().map(|(_, cells)| cells.len()).max()?;
+
+    // The first row is the header when it is in `<thead>` or made of `<th>`.
+    let has_header = rows
+        .first()
+        .is_some_and(|(head, cells)| *head || cells.iter().all(|cell| cell.heading));
+    let header = if has_header {
+        rows.remove(0).1
+    } else {
+        Vec::new()
+    };
+    let alignments = (0..columns).map(|column| {
+        header
+            .get(column)
+            .or_else(|| rows.first().and_then(|(_, cells)| cells.get(column)))
+            .map_or("---", |cell| cell.alignment)
+    });
+
+    let mut markdown = String::from("\n\n");
+    if !caption.is_empty() {
+        markdown.push_str(&caption);
+        markdown.push_str("\n\n");
+    }
+    push_row(&mut markdown, &header, columns);
+    markdown.push('|');
+    for alignment in alignments {
+        markdown.push_str(&format!(" {alignment} |"));
+    }
+    markdown.push('\n');
+    for (_, cells) in &rows {
+        push_row(&mut markdown, cells, columns);
+    }
+    markdown.push('\n');
+    Some(markdown.into())
+}
+
+struct Cell {
+    text: String,
+    heading: bool,
+    /// The delimiter row marker for the cell's alignment.
+    alignment: &'static str,
+}
+
+fn cells(handlers: &dyn Handlers, row: &Rc<Node>) -> Vec<Cell> {
+    row.children
+        .borrow()
+        .iter()
+        .filter_map(|cell| {
+            let NodeData::Element { name, attrs, .. } = &cell.data else {
+                return None;
+            };
+            let heading = match name.local.as_ref() {
+                "th" => true,
+                "td" => false,
+                _ => return None,
+            };
+            let attrs = attrs.borrow();
+            let alignment = attrs
+                .iter()
+                .find_map(|attr| match attr.name.local.as_ref() {
+                    "align" => Some(attr.value.to_ascii_lowercase()),
+                    "style" => attr.value.to_ascii_lowercase().split(';').find_map(|rule| {
+                        let (property, value) = rule.split_once(':')?;
+                        (property.trim() == "text-align").then(|| value.trim().to_string())
+                    }),
+                    _ => None,
+                })
+                .map_or("---", |alignment| match alignment.as_str() {
+                    "center" => ":---:",
+                    "right" | "end" => "---:",
+                    _ => "---",
+                });
+            Some(Cell {
+                text: inline(handlers, cell),
+                heading,
+                alignment,
+            })
+        })
+        .collect()
+}
+
+/// A cell's Markdown squeezed onto one line, as pipe tables need.
+fn inline(handlers: &dyn Handlers, node: &Rc<Node>) -> String {
+    handlers
+        .walk_children(node)
+        .content
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace('|', "\\|")
+}
+
+fn push_row(markdown: &mut String, cells: &[Cell], columns: usize) {
+    markdown.push('|');
+    for column in 0..columns {
+        let text = cells.get(column).map_or("", |cell| cell.text.as_str());
+        markdown.push_str(&format!(" {text} |"));
+    }
+    markdown.push('\n');
+}
+
+fn tag(node: &Node) -> Option<&str> {
+    match &node.data {
+        NodeData::Element { name, .. } => Some(name.local.as_ref()),
+        _ => None,
+    }
+}
@@ -0,0 +1,429 @@
+pub mod highlight;
+mod html;
+pub mod table;
+
+use std::ops::Range;
+
+use pulldown_cmark::{CodeBlockKind, Event, Options, Parser, Tag, TagEnd};
+use reqwest::Url;
+
+use crate::feed::entries::Entry;
+
+pub use table::{Align, Table};
+
+/// Words per minute assumed when estimating reading time.
+const WORDS_PER_MINUTE: usize = 230;
+
+/// A run of the article body: Markdown text, or a block laid out on its
+/// own — an image that stands in a paragraph of its own, a code block or a
+/// table.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Segment {
+    Text(String),
+    Image(Image),
+    Code(CodeBlock),
+    Table(Table),
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CodeBlock {
+    /// The language hint of the fence or the HTML class, if any.
+    pub language: Option<String>,
+    pub code: String,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Image {
+    pub url: String,
+    pub alt: String,
+}
+
+impl Image {
+    /// What is shown when the picture itself cannot be.
+    pub fn placeholder(&self) -> String {
+        if self.alt.trim().is_empty() {
+            "[image]".to_string()
+        } else {
+            format!("[image: {}]", self.alt.trim())
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LinkKind {
+    Link,
+    Image,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Link {
+    pub url: String,
+    pub label: String,
+    pub kind: LinkKind,
+}
+
+/// An entry's body ready to lay out, with every link and image it mentions.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ArticleContent {
+    /// Whether text segments are Markdown rather than plain text.
+    pub markdown: bool,
+    pub segments: Vec<Segment>,
+    pub links: Vec<Link>,
+}
+
+impl ArticleContent {
+    pub fn images(&self) -> impl Iterator<Item = &Image> {
+        self.segments.iter().filter_map(|segment| match segment {
+            Segment::Image(image) => Some(image),
+            _ => None,
+        })
+    }
+}
+
+/// Converts the entry's body to Markdown and pulls out its images, code
+/// blocks and tables. Images alone in a paragraph become their own segments;
+/// those mixed with text are replaced by `[image: alt]`. Blocks nested in
+/// lists or quotes stay in the text. Relative URLs are resolved against the
+/// entry's link.
+pub fn parse(entry: &Entry) -> ArticleContent {
+    let Some(content) = entry.body() else {
+        return ArticleContent::default();
+    };
+    if !content.kind.is_markup() {
+        return ArticleContent {
+            markdown: false,
+            segments: vec![Segment::Text(content.value.clone())],
+            links: Vec::new(),
+        };
+    }
+    let markdown = html::to_markdown(&content.value);
+    let base = entry.link.as_deref().and_then(|link| Url::parse(link).ok());
+    split(&markdown, base.as_ref())
+}
+
+/// Words in the entry's body, counted in its text rather than its markup.
+pub fn word_count(entry: &Entry) -> usize {
+    match entry.body() {
+        Some(content) if content.kind.is_markup() => html::word_count(&content.value),
+        Some(content) => content.value.split_whitespace().count(),
+        None => 0,
+    }
+}
+
+/// Minutes it takes to read `words`, rounded up.
+pub fn reading_minutes(words: usize) -> usize {
+    words.div_ceil(WORDS_PER_MINUTE).max(1)
+}
+
+enum Edit {
+    /// Replaces a paragraph that holds nothing but images, a code block or
+    /// a table.
+    Segments(Vec<Segment>),
+    Text(String),
+}
+
+fn split(markdown: &str, base: Option<&Url>) -> ArticleContent {
+    let mut edits: Vec<(Range<usize>, Edit)> = Vec::new();
+    let mut links = Vec::new();
+
+    let mut paragraph: Option<Range<usize>> = None;
+    // Images in the current paragraph, and whether anything else is in it.
+    let mut images: Vec<(Range<usize>, Image)> = Vec::new();
+    let mut has_text = false;
+    let mut image: Option<(Range<usize>, String, String)> = None;
+    let mut link: Option<(String, String)> = None;
+    // Lists, quotes and footnotes around the current event.
+    let mut containers = 0_usize;
+    let mut code: Option<(Range<usize>, CodeBlock)> = None;
+    let mut table: Option<(Range<usize>, Table)> = None;
+
+    for (event, range) in Parser::new_ext(markdown, Options::ENABLE_TABLES).into_offset_iter() {
+        match event {
+            Event::Start(Tag::List(_) | Tag::BlockQuote(_) | Tag::FootnoteDefinition(_)) => {
+                containers += 1;
+            }
+            Event::End(TagEnd::List(_) | TagEnd::BlockQuote(_) | TagEnd::FootnoteDefinition) => {
+                containers = containers.saturating_sub(1);
+            }
+            Event::Start(Tag::CodeBlock(kind)) if containers == 0 => {
+                let language = match kind {
+                    CodeBlockKind::Fenced(info) => info
+                        .split([' ', ',', '{'])
+                        .next()
+                        .filter(|language| !language.is_empty())
+                        .map(str::to_string),
+                    CodeBlockKind::Indented => None,
+                };
+                code = Some((
+                    range,
+                    CodeBlock {
+                        language,
+                        code: String::new(),
+                    },
+                ));
+            }
+            Event::End(TagEnd::CodeBlock) => {
+                if let Some((range, mut block)) = code.take() {
+                    block.code.truncate(block.code.trim_end_matches('\n').len());
+                    edits.push((range, Edit::Segments(vec![Segment::Code(block)])));
+                }
+            }
+            Event::Start(Tag::Table(alignments)) if containers == 0 => {
+                let alignments = alignments.into_iter().map(Align::from).collect();
+                table = Some((range, Table::new(alignments)));
+            }
+            Event::End(TagEnd::Table) => {
+                if let Some((range, table)) = table.take() {
+                    edits.push((range, Edit::Segments(vec![Segment::Table(table)])));
+                }
+            }
+            Event::Start(Tag::TableRow | Tag::TableHead) => {
+                if let Some((_, table)) = table.as_mut() {
+                    table.push_row();
+                }
+            }
+            Event::Start(Tag::TableCell) => {
+                if let Some((_, table)) = table.as_mut() {
+                    table.push_cell();
+                }
+            }
+            Event::SoftBreak | Event::HardBreak => {
+                if let Some((_, table)) = table.as_mut() {
+                    table.push_text(" ");
+                }
+            }
+            Event::Start(Tag::Paragraph) => {
+                paragraph = Some(range);
+                images.clear();
+                has_text = false;
+            }
+            Event::End(TagEnd::Paragraph) => {
+                let Some(paragraph) = paragraph.take() else {
+                    continue;
+                };
+                if !has_text && !images.is_empty() {
+                    let found = images
+                        .drain(..)
+                        .map(|(_, image)| Segment::Image(image))
+                        .collect();
+                    edits.push((paragraph, Edit::Segments(found)));
+                } else {
+                    for (range, image) in images.drain(..) {
+                        let placeholder =
+                            image.placeholder().replace('[', "\\[").replace(']', "\\]");
+                        edits.push((range, Edit::Text(placeholder)));
+                    }
+                }
+            }
+            Event::Start(Tag::Image { dest_url, .. }) => {
+                image = Some((range, resolve(&dest_url, base), String::new()));
+            }
+            Event::End(TagEnd::Image) => {
+                let Some((range, url, alt)) = image.take() else {
+                    continue;
+                };
+                links.push(Link {
+                    url: url.clone(),
+                    label: alt.clone(),
+                    kind: LinkKind::Image,
+                });
+                let image = Image { url, alt };
+                if let Some((_, table)) = table.as_mut() {
+                    table.push_text(&image.placeholder());
+                } else if paragraph.is_some() {
+                    images.push((range, image));
+                } else {
+                    edits.push((range, Edit::Text(image.placeholder())));
+                }
+            }
+            Event::Start(Tag::Link { dest_url, .. }) => {
+                link = Some((resolve(&dest_url, base), String::new()));
+            }
+            Event::End(TagEnd::Link) => {
+                if let Some((url, label)) = link.take() {
+                    links.push(Link {
+                        url,
+                        label,
+                        kind: LinkKind::Link,
+                    });
+                }
+            }
+            Event::Text(text) if code.is_some() => {
+                if let Some((_, block)) = code.as_mut() {
+                    block.code.push_str(&text);
+                }
+            }
+            Event::Text(text) | Event::Code(text) => {
+                if let Some((_, _, alt)) = image.as_mut() {
+                    alt.push_str(&text);
+                } else {
+                    if let Some((_, table)) = table.as_mut() {
+                        table.push_text(&text);
+                    }
+                    has_text |= !text.trim().is_empty();
+                }
+                if let Some((_, label)) = link.as_mut() {
+                    label.push_str(&text);
+                }
+            }
+            Event::InlineHtml(_) | Event::InlineMath(_) | Event::FootnoteReference(_) => {
+                has_text = true;
+            }
+            _ => {}
+        }
+    }
+
+    ArticleContent {
+        markdown: true,
+        segments: apply(markdown, edits),
+        links: dedup(links),
+    }
+}
+
+fn apply(markdown: &str, mut edits: Vec<(Range<usize>, Edit)>) -> Vec<Segment> {
+    edits.sort_by_key(|(range, _)| range.start);
+    let mut segments = Vec::new();
+    let mut text = String::new();
+    let mut position = 0;
+    for (range, edit) in edits {
+        if range.start < position {
+            continue;
+        }
+        text.push_str(&markdown[position..range.start]);
+        position = range.end;
+        match edit {
+            Edit::Text(replacement) => text.push_str(&replacement),
+            Edit::Segments(blocks) => {
+                push_text(&mut segments, &mut text);
+                segments.extend(blocks);
+            }
+        }
+    }
+    text.push_str(&markdown[position..]);
+    push_text(&mut segments, &mut text);
+    segments
+}
+
+fn push_text(segments: &mut Vec<Segment>, text: &mut String) {
+    let trimmed = text.trim_matches('\n');
+    if !trimmed.trim().is_empty() {
+        segments.push(Segment::Text(trimmed.to_string()));
+    }
+    text.clear();
+}
+
+fn resolve(url: &str, base: Option<&Url>) -> String {
+    match base.and_then(|base| base.join(url).ok()) {
+        Some(resolved) => resolved.to_string(),
+        None => url.to_string(),
+    }
+}
+
+fn dedup(links: Vec<Link>) -> Vec<Link> {
+    let mut unique: Vec<Link> = Vec::with_capacity(links.len());
+    for link in links {
+        if !link.url.is_empty() && !unique.iter().any(|seen| seen.url == link.url) {
+            unique.push(link);
+        }
+    }
+    unique
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::feed::entries::{ContentKind, Entry, EntryContent};
+
+    use super::{
+        parse, reading_minutes, word_count, Align, CodeBlock, Image, LinkKind, Segment, Table,
+    };
+
+    #[test]
+    fn separates_standalone_images_and_collects_links() {
+        let entry = Entry {
+            link: Some("https://example.com/posts/1".to_string()),
+            content: Some(EntryContent {
+                value: r#"<p>Intro with <a href="/about">a link</a>.</p>
+                    <p><a href="/full.png"><img src="/thumb.png" alt="A chart"></a></p>
+                    <p>Inline <img src="icon.png" alt="icon"> here.</p>"#
+                    .to_string(),
+                kind: ContentKind::Html,
+            }),
+            ..Entry::default()
+        };
+
+        let content = parse(&entry);
+        assert_eq!(
+            content.segments,
+            [
+                Segment::Text("Intro with [a link](/about).".to_string()),
+                Segment::Image(Image {
+                    url: "https://example.com/thumb.png".to_string(),
+                    alt: "A chart".to_string(),
+                }),
+                Segment::Text("Inline \\[image: icon\\] here.".to_string()),
+            ]
+        );
+        let links: Vec<_> = content
+            .links
+            .iter()
+            .map(|link| (link.url.as_str(), link.kind))
+            .collect();
+        assert_eq!(
+            links,
+            [
+                ("https://example.com/about", LinkKind::Link),
+                ("https://example.com/thumb.png", LinkKind::Image),
+                ("https://example.com/full.png", LinkKind::Link),
+                ("https://example.com/posts/icon.png", LinkKind::Image),
+            ]
+        );
+    }
+
+    #[test]
+    fn counts_words_in_the_text_and_estimates_reading_time() {
+        let entry = Entry {
+            content: Some(EntryContent {
+                value: "<p>One <em>two</em></p><script>not counted</script><p>three</p>"
+                    .to_string(),
+                kind: ContentKind::Html,
+            }),
+            ..Entry::default()
+        };
+        assert_eq!(word_count(&entry), 3);
+        assert_eq!(reading_minutes(3), 1);
+        assert_eq!(reading_minutes(1_000), 5);
+    }
+
+    #[test]
+    fn pulls_out_code_blocks_and_tables() {
+        let entry = Entry {
+            content: Some(EntryContent {
+                value: r#"<pre class="highlight"><code class="language-rust">fn main() {}
+</code></pre>
+                    <table><tr><td>Apples</td><td align="right">3</td></tr>
+                    <tr><td>Pears | quinces</td><td align="right">12</td></tr></table>"#
+                    .to_string(),
+                kind: ContentKind::Html,
+            }),
+            ..Entry::default()
+        };
+
+        assert_eq!(
+            parse(&entry).segments,
+            [
+                Segment::Code(CodeBlock {
+                    language: Some("rust".to_string()),
+                    code: "fn main() {}".to_string(),
+                }),
+                Segment::Table(Table {
+                    alignments: vec![Align::Left, Align::Right],
+                    rows: vec![
+                        vec![String::new(), String::new()],
+                        vec!["Apples".to_string(), "3".to_string()],
+                        vec!["Pears | quinces".to_string(), "12".to_string()],
+                    ],
+                }),
+            ]
+        );
+    }
+}
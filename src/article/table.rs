@@ -0,0 +1,238 @@
+use pulldown_cmark::Alignment;
+use ratatui::{
+    style::Style,
+    text::{Line, Span},
+};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Columns are not squeezed narrower than this while others are wider.
+const MIN_COLUMN_WIDTH: usize = 3;
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Align {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+impl From<Alignment> for Align {
+    fn from(alignment: Alignment) -> Self {
+        match alignment {
+            Alignment::Center => Self::Center,
+            Alignment::Right => Self::Right,
+            Alignment::None | Alignment::Left => Self::Left,
+        }
+    }
+}
+
+/// A table's cells as plain text. The first row is the header, which tables
+/// converted from HTML without one leave blank.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Table {
+    pub alignments: Vec<Align>,
+    pub rows: Vec<Vec<String>>,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TableStyle {
+    pub border: Style,
+    pub header: Style,
+    pub cell: Style,
+}
+
+impl Table {
+    pub fn new(alignments: Vec<Align>) -> Self {
+        Self {
+            alignments,
+            rows: Vec::new(),
+        }
+    }
+
+    pub(super) fn push_row(&mut self) {
+        self.rows.push(Vec::new());
+    }
+
+    pub(super) fn push_cell(&mut self) {
+        if let Some(row) = self.rows.last_mut() {
+            row.push(String::new());
+        }
+    }
+
+    pub(super) fn push_text(&mut self, text: &str) {
+        if let Some(cell) = self.rows.last_mut().and_then(|row| row.last_mut()) {
+            cell.push_str(text);
+        }
+    }
+
+    fn has_header(&self) -> bool {
+        self.rows
+            .first()
+            .is_some_and(|row| row.iter().any(|cell| !cell.trim().is_empty()))
+    }
+
+    /// Draws the table with box-drawing borders in at most `width` columns,
+    /// wrapping cell text when the natural widths do not fit.
+    pub fn layout(&self, width: u16, style: TableStyle) -> Vec<Line<'static>> {
+        let columns = self
+            .rows
+            .iter()
+            .map(Vec::len)
+            .max()
+            .unwrap_or(0)
+            .max(self.alignments.len());
+        if columns == 0 {
+            return Vec::new();
+        }
+        let widths = self.column_widths(columns, width);
+        let rule = |left: &str, middle: &str, right: &str| {
+            let segments: Vec<String> = widths.iter().map(|width| "─".repeat(width + 2)).collect();
+            Line::styled(
+                format!("{left}{}{right}", segments.join(middle)),
+                style.border,
+            )
+        };
+
+        let body = if self.has_header() {
+            &self.rows[..]
+        } else {
+            self.rows.get(1..).unwrap_or_default()
+        };
+        let mut lines = vec![rule("┌", "┬", "┐")];
+        for (index, row) in body.iter().enumerate() {
+            let header = index == 0 && self.has_header();
+            let cell_style = if header { style.header } else { style.cell };
+            let wrapped: Vec<Vec<String>> = (0..columns)
+                .map(|column| {
+                    let text = row.get(column).map_or("", |cell| cell.trim());
+                    wrap(text, widths[column])
+                })
+                .collect();
+            let height = wrapped.iter().map(Vec::len).max().unwrap_or(1);
+            for line in 0..height {
+                let mut spans = vec![Span::styled("│", style.border)];
+                for (column, cell) in wrapped.iter().enumerate() {
+                    let text = cell.get(line).map_or("", String::as_str);
+                    let align = self.alignments.get(column).copied().unwrap_or_default();
+                    spans.push(Span::styled(
+                        format!(" {} ", pad(text, widths[column], align)),
+                        cell_style,
+                    ));
+                    spans.push(Span::styled("│", style.border));
+                }
+                lines.push(Line::from(spans));
+            }
+            if header && body.len() > 1 {
+                lines.push(rule("├", "┼", "┤"));
+            }
+        }
+        lines.push(rule("└", "┴", "┘"));
+        lines
+    }
+
+    /// Natural column widths, with the widest columns narrowed in turn until
+    /// the table fits.
+    fn column_widths(&self, columns: usize, width: u16) -> Vec<usize> {
+        let mut widths = vec![1; columns];
+        for row in &self.rows {
+            for (column, cell) in row.iter().enumerate() {
+                widths[column] = widths[column].max(cell.trim().width());
+            }
+        }
+        // Each column takes its text, a space either side and a border.
+        let available = usize::from(width).saturating_sub(3 * columns + 1);
+        while widths.iter().sum::<usize>() > available {
+            let Some(widest) = widths
+                .iter_mut()
+                .filter(|width| **width > MIN_COLUMN_WIDTH)
+                .max()
+            else {
+                break;
+            };
+            *widest -= 1;
+        }
+        widths
+    }
+}
+
+/// Breaks text into lines of at most `width` columns, at spaces where it
+/// can and inside words where it must.
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        let needed = if line.is_empty() { 0 } else { line.width() + 1 };
+        if needed > 0 && needed + word.width() > width {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        for character in word.chars() {
+            if line.width() + character.width().unwrap_or(0) > width && !line.is_empty() {
+                lines.push(std::mem::take(&mut line));
+            }
+            line.push(character);
+        }
+    }
+    if !line.is_empty() || lines.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+fn pad(text: &str, width: usize, align: Align) -> String {
+    let space = width.saturating_sub(text.width());
+    let (left, right) = match align {
+        Align::Left => (0, space),
+        Align::Center => (space / 2, space - space / 2),
+        Align::Right => (space, 0),
+    };
+    format!("{}{text}{}", " ".repeat(left), " ".repeat(right))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Align, Table, TableStyle};
+
+    #[test]
+    fn draws_box_tables_and_wraps_cells_to_fit() {
+        let table = Table {
+            alignments: vec![Align::Left, Align::Right],
+            rows: vec![
+                vec!["Fruit".to_string(), "Count".to_string()],
+                vec!["Apples and pears".to_string(), "3".to_string()],
+            ],
+        };
+        let render = |width| -> Vec<String> {
+            table
+                .layout(width, TableStyle::default())
+                .iter()
+                .map(ToString::to_string)
+                .collect()
+        };
+
+        assert_eq!(
+            render(40),
+            [
+                "┌──────────────────┬───────┐",
+                "│ Fruit            │ Count │",
+                "├──────────────────┼───────┤",
+                "│ Apples and pears │     3 │",
+                "└──────────────────┴───────┘",
+            ]
+        );
+        assert_eq!(
+            render(20),
+            [
+                "┌──────────┬───────┐",
+                "│ Fruit    │ Count │",
+                "├──────────┼───────┤",
+                "│ Apples   │     3 │",
+                "│ and      │       │",
+                "│ pears    │       │",
+                "└──────────┴───────┘",
+            ]
+        );
+    }
+}
@@ -0,0 +1,122 @@
+use std::fmt;
+
+use serde_json::{json, Value};
+
+use crate::daemon::protocol::{LIST_ENTRIES, LIST_FEEDS, MARK_READ, PAUSE, PLAY, STOP, SYNC};
+
+const USAGE: &str = "usage: fead [--read <file|url|->]
+       fead daemon
+       fead sync
+       fead ctl feeds | entries <feed> | read <feed> <entry> | unread <feed> <entry>
+       fead ctl play <feed> <entry> | pause | stop | sync";
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Command {
+    Browse,
+    Read(String),
+    Daemon,
+    /// One sync with the configured server, without a daemon.
+    Sync,
+    /// A call to the running daemon's control socket.
+    Control {
+        method: &'static str,
+        params: Value,
+    },
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UsageError(String);
+
+impl fmt::Display for UsageError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "{}\n{USAGE}", self.0)
+    }
+}
+
+impl std::error::Error for UsageError {}
+
+pub fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Command, UsageError> {
+    let mut args = args.into_iter();
+    let command = match args.next().as_deref() {
+        None => Command::Browse,
+        Some("--read" | "-r") => match args.next() {
+            Some(source) => Command::Read(source),
+            None => return Err(UsageError("--read needs a source".into())),
+        },
+        Some("daemon") => Command::Daemon,
+        Some("sync") => Command::Sync,
+        Some("ctl") => parse_control(&mut args)?,
+        Some(other) => return Err(UsageError(format!("unexpected argument `{other}`"))),
+    };
+
+    match args.next() {
+        Some(extra) => Err(UsageError(format!("unexpected argument `{extra}`"))),
+        None => Ok(command),
+    }
+}
+
+fn parse_control(args: &mut impl Iterator<Item = String>) -> Result<Command, UsageError> {
+    let mut operand = |name: &str| {
+        args.next()
+            .ok_or_else(|| UsageError(format!("ctl needs a {name}")))
+    };
+    let (method, params) = match operand("command")?.as_str() {
+        "feeds" => (LIST_FEEDS, Value::Null),
+        "entries" => (LIST_ENTRIES, json!({ "feed": operand("feed")? })),
+        action @ ("read" | "unread") => (
+            MARK_READ,
+            json!({
+                "feed": operand("feed")?,
+                "entry": operand("entry")?,
+                "read": action == "read",
+            }),
+        ),
+        "play" => (
+            PLAY,
+            json!({ "feed": operand("feed")?, "entry": operand("entry")? }),
+        ),
+        "pause" => (PAUSE, Value::Null),
+        "stop" => (STOP, Value::Null),
+        "sync" => (SYNC, Value::Null),
+        other => return Err(UsageError(format!("unknown ctl command `{other}`"))),
+    };
+    Ok(Command::Control { method, params })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{parse_args, Command};
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_browse_and_read_commands() {
+        assert_eq!(parse_args(args(&[])), Ok(Command::Browse));
+        assert_eq!(
+            parse_args(args(&["--read", "-"])),
+            Ok(Command::Read("-".into()))
+        );
+        assert!(parse_args(args(&["--read"])).is_err());
+        assert!(parse_args(args(&["--read", "a", "b"])).is_err());
+    }
+
+    #[test]
+    fn parses_daemon_control_commands() {
+        assert_eq!(parse_args(args(&["daemon"])), Ok(Command::Daemon));
+        assert_eq!(parse_args(args(&["sync"])), Ok(Command::Sync));
+        assert_eq!(
+            parse_args(args(&["ctl", "unread", "https://example.com/feed", "42"])),
+            Ok(Command::Control {
+                method: "mark_read",
+                params: json!({ "feed": "https://example.com/feed", "entry": "42", "read": false }),
+            })
+        );
+        assert!(parse_args(args(&["ctl", "entries"])).is_err());
+        assert!(parse_args(args(&["ctl", "rewind"])).is_err());
+        assert!(parse_args(args(&["ctl", "pause", "now"])).is_err());
+    }
+}
@@ -0,0 +1,395 @@
+use std::{collections::BTreeMap, fs, io, path::PathBuf};
+
+use serde::Deserialize;
+
+use crate::app::AppResult;
+use crate::feed::command::{run_command, COMMAND_TIMEOUT};
+
+const CONFIG_FILE: &str = "config.json";
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub network: NetworkSettings,
+    pub refresh: RefreshSettings,
+    pub websub: WebSubSettings,
+    pub daemon: DaemonSettings,
+    pub notifications: NotificationSettings,
+    pub sync: Option<SyncSettings>,
+    pub export: ExportSettings,
+    pub read_later: ReadLaterSettings,
+    pub images: ImageSettings,
+    pub keys: KeySettings,
+    pub feeds: BTreeMap<String, FeedSettings>,
+}
+
+impl Config {
+    pub fn feed(&self, url: &str) -> FeedSettings {
+        self.feeds.get(url).cloned().unwrap_or_default()
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NetworkSettings {
+    pub proxy: Option<String>,
+    pub ca_certificates: Vec<PathBuf>,
+    pub insecure_hosts: Vec<String>,
+    pub max_retries: u32,
+    /// Largest feed body accepted, in bytes, after decompression.
+    pub max_feed_size: u64,
+}
+
+impl Default for NetworkSettings {
+    fn default() -> Self {
+        Self {
+            proxy: None,
+            ca_certificates: Vec::new(),
+            insecure_hosts: Vec::new(),
+            max_retries: 2,
+            max_feed_size: 10 * 1024 * 1024,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RefreshSettings {
+    /// Used when neither the feed nor the server suggests an interval.
+    pub interval_minutes: u64,
+    /// Floor for intervals suggested by feeds and servers.
+    pub min_interval_minutes: u64,
+}
+
+impl Default for RefreshSettings {
+    fn default() -> Self {
+        Self {
+            interval_minutes: 60,
+            min_interval_minutes: 5,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DaemonSettings {
+    /// Unix socket the daemon listens on and the TUI and CLI connect to.
+    pub socket: PathBuf,
+}
+
+impl Default for DaemonSettings {
+    fn default() -> Self {
+        Self {
+            socket: PathBuf::from("fead.sock"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NotificationSettings {
+    /// Show a desktop notification when a refresh finds new entries.
+    pub desktop: bool,
+    /// Shell commands given each feed's new entries as JSON on stdin.
+    pub hooks: Vec<String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ExportSettings {
+    /// Where exported articles and EPUBs are written.
+    pub directory: PathBuf,
+    /// Format used when saving a single article.
+    pub format: ExportFormat,
+}
+
+impl Default for ExportSettings {
+    fn default() -> Self {
+        Self {
+            directory: PathBuf::from("exports"),
+            format: ExportFormat::default(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    #[default]
+    Markdown,
+    /// A standalone HTML page with its own styles.
+    Html,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ImageSettings {
+    /// Download images in articles and draw them inline.
+    pub enabled: bool,
+    /// How images are drawn; detected from the terminal when unset.
+    pub protocol: Option<ImageProtocol>,
+    /// Tallest an image is drawn, in terminal rows.
+    pub max_rows: u16,
+    /// Largest image downloaded, in bytes.
+    pub max_size: u64,
+}
+
+impl Default for ImageSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            protocol: None,
+            max_rows: 20,
+            max_size: 5 * 1024 * 1024,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageProtocol {
+    /// Kitty's graphics protocol, also spoken by Ghostty and WezTerm.
+    Kitty,
+    Iterm2,
+    Sixel,
+    /// Two pixels per cell drawn with `▀` and colors; works almost anywhere.
+    Halfblocks,
+    /// Shows `[image: alt]` instead of the picture.
+    Placeholder,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct KeySettings {
+    /// Opens the next article in the list from the article view.
+    pub next_article: char,
+    /// Opens the previous article in the list from the article view.
+    pub previous_article: char,
+    /// Opens the next unread article, moving on to later feeds.
+    pub next_unread: char,
+}
+
+impl Default for KeySettings {
+    fn default() -> Self {
+        Self {
+            next_article: 'n',
+            previous_article: 'p',
+            next_unread: 'N',
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ReadLaterSettings {
+    /// The service `b` sends to; may be left out when only one is configured.
+    pub default: Option<String>,
+    pub services: BTreeMap<String, ReadLaterService>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum ReadLaterService {
+    /// Signs in with OAuth's password grant using an API client created in
+    /// Wallabag's developer settings.
+    Wallabag {
+        url: String,
+        client_id: String,
+        client_secret: Secret,
+        username: String,
+        password: Secret,
+    },
+    Linkding {
+        url: String,
+        token: Secret,
+    },
+    /// Pocket's `v3/add` API, as offered by Pocket-compatible services.
+    Pocket {
+        url: String,
+        consumer_key: Secret,
+        access_token: Secret,
+    },
+    /// Any other service: posts `body` to `url` after filling in `{url}`,
+    /// `{title}` and `{feed}`.
+    Http {
+        url: String,
+        #[serde(default)]
+        headers: BTreeMap<String, Secret>,
+        #[serde(default)]
+        body: String,
+        #[serde(default = "default_content_type")]
+        content_type: String,
+    },
+}
+
+fn default_content_type() -> String {
+    "application/json".to_string()
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SyncSettings {
+    pub backend: SyncBackendKind,
+    /// The server's API endpoint, such as `https://rss.example.com/api/greader.php`.
+    pub url: String,
+    pub username: String,
+    pub password: Option<Secret>,
+    /// API token, used by Miniflux instead of the username and password.
+    pub token: Option<Secret>,
+    /// How often the daemon syncs.
+    pub interval_minutes: u64,
+    /// Newest items fetched from the server on each sync.
+    pub max_items: usize,
+}
+
+impl Default for SyncSettings {
+    fn default() -> Self {
+        Self {
+            backend: SyncBackendKind::default(),
+            url: String::new(),
+            username: String::new(),
+            password: None,
+            token: None,
+            interval_minutes: 30,
+            max_items: 1000,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncBackendKind {
+    /// FreshRSS, Miniflux, Inoreader and others speaking the Google Reader API.
+    #[default]
+    GoogleReader,
+    /// The Fever API, offered by Miniflux, Tiny Tiny RSS and others.
+    Fever,
+    /// Miniflux's own REST API.
+    Miniflux,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WebSubSettings {
+    /// Subscribe to hubs advertised by feeds while the daemon runs.
+    pub enabled: bool,
+    /// Address the callback server listens on.
+    pub listen: String,
+    /// URL hubs use to reach the callback server, when it is behind a proxy
+    /// or tunnel. Defaults to `http://<listen address>/`.
+    pub callback_url: Option<String>,
+    pub lease_seconds: u64,
+}
+
+impl Default for WebSubSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            listen: "127.0.0.1:0".to_string(),
+            callback_url: None,
+            lease_seconds: 10 * 24 * 60 * 60,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FeedSettings {
+    pub auth: Option<Auth>,
+    pub headers: BTreeMap<String, Secret>,
+    pub proxy: Option<String>,
+    /// Overrides every other refresh interval hint for this feed.
+    pub refresh_minutes: Option<u64>,
+    /// Overrides `notifications.desktop` for this feed.
+    pub notify: Option<bool>,
+    /// Run after the global hooks when this feed has new entries.
+    pub hooks: Vec<String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum Auth {
+    Basic { username: String, password: Secret },
+    Bearer { token: Secret },
+}
+
+/// A credential kept out of `feeds.json`, either inline in the config file,
+/// in an environment variable, or printed by a command such as `pass`.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum Secret {
+    Value(String),
+    Env(String),
+    Command(String),
+}
+
+impl Secret {
+    pub async fn resolve(&self) -> Result<String, String> {
+        match self {
+            Self::Value(value) => Ok(value.clone()),
+            Self::Env(name) => {
+                std::env::var(name).map_err(|_| format!("environment variable {name} is not set"))
+            }
+            Self::Command(command) => {
+                let output = run_command(command, None, COMMAND_TIMEOUT)
+                    .await
+                    .map_err(|error| format!("secret command failed: {error}"))?;
+                let output = String::from_utf8(output)
+                    .map_err(|_| "secret command printed invalid UTF-8".to_string())?;
+                Ok(output.lines().next().unwrap_or_default().to_string())
+            }
+        }
+    }
+}
+
+pub fn load_config() -> AppResult<Config> {
+    match fs::read_to_string(CONFIG_FILE) {
+        Ok(contents) if contents.trim().is_empty() => Ok(Config::default()),
+        Ok(contents) => serde_json::from_str(&contents)
+            .map_err(|error| format!("invalid {CONFIG_FILE}: {error}").into()),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(Config::default()),
+        Err(error) => Err(error.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Auth, Config, Secret};
+
+    #[tokio::test]
+    async fn reads_per_feed_credentials_from_every_secret_kind() {
+        let config: Config = serde_json::from_str(
+            r#"{
+                "feeds": {
+                    "https://example.com/private.xml": {
+                        "auth": {
+                            "basic": { "username": "reader", "password": { "command": "echo hunter2" } }
+                        },
+                        "headers": {
+                            "Cookie": { "value": "session=abc" },
+                            "X-Token": { "env": "FEAD_TEST_UNSET_VARIABLE" }
+                        }
+                    }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let settings = config.feed("https://example.com/private.xml");
+        let Some(Auth::Basic { username, password }) = &settings.auth else {
+            panic!("expected basic auth");
+        };
+        assert_eq!(username, "reader");
+        assert_eq!(password.resolve().await.unwrap(), "hunter2");
+        assert_eq!(
+            settings.headers["Cookie"].resolve().await.unwrap(),
+            "session=abc"
+        );
+        assert!(settings.headers["X-Token"].resolve().await.is_err());
+        assert!(config.feed("https://example.com/other.xml").auth.is_none());
+        assert!(matches!(
+            serde_json::from_str::<Secret>(r#"{ "env": "TOKEN" }"#).unwrap(),
+            Secret::Env(name) if name == "TOKEN"
+        ));
+    }
+}
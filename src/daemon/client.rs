@@ -0,0 +1,111 @@
+use std::{error::Error, fmt, io, path::Path};
+
+use serde_json::Value;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines},
+    net::{
+        unix::{OwnedReadHalf, OwnedWriteHalf},
+        UnixStream,
+    },
+};
+
+use super::protocol::{Notification, Request, Response, RpcError, JSONRPC_VERSION};
+
+#[derive(Debug)]
+pub enum ClientError {
+    Io(io::Error),
+    Protocol(String),
+    Rpc(RpcError),
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(formatter, "daemon connection failed: {error}"),
+            Self::Protocol(message) => write!(formatter, "unexpected daemon reply: {message}"),
+            Self::Rpc(error) => write!(formatter, "{error}"),
+        }
+    }
+}
+
+impl Error for ClientError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+            Self::Rpc(error) => Some(error),
+            Self::Protocol(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for ClientError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+/// A connection to the daemon's control socket.
+pub struct DaemonClient {
+    lines: Lines<BufReader<OwnedReadHalf>>,
+    writer: OwnedWriteHalf,
+    next_id: u64,
+}
+
+impl DaemonClient {
+    pub async fn connect(socket: &Path) -> io::Result<Self> {
+        let (reader, writer) = UnixStream::connect(socket).await?.into_split();
+        Ok(Self {
+            lines: BufReader::new(reader).lines(),
+            writer,
+            next_id: 1,
+        })
+    }
+
+    /// Sends a request and waits for its response, skipping any
+    /// notifications that arrive first.
+    pub async fn call(&mut self, method: &str, params: Value) -> Result<Value, ClientError> {
+        let id = self.next_id;
+        self.next_id += 1;
+        let request = Request {
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            id: Some(id.into()),
+            method: method.to_string(),
+            params,
+        };
+        let mut line = serde_json::to_string(&request)
+            .map_err(|error| ClientError::Protocol(error.to_string()))?;
+        line.push('\n');
+        self.writer.write_all(line.as_bytes()).await?;
+
+        loop {
+            let line = self.next_line().await?;
+            let Ok(response) = serde_json::from_str::<Response>(&line) else {
+                continue;
+            };
+            if response.id != id {
+                continue;
+            }
+            return match (response.result, response.error) {
+                (_, Some(error)) => Err(ClientError::Rpc(error)),
+                (result, None) => Ok(result.unwrap_or(Value::Null)),
+            };
+        }
+    }
+
+    /// Waits for the next notification, as sent after a `watch` call.
+    pub async fn next_notification(&mut self) -> Result<Notification, ClientError> {
+        loop {
+            let line = self.next_line().await?;
+            if let Ok(notification) = serde_json::from_str::<Notification>(&line) {
+                return Ok(notification);
+            }
+        }
+    }
+
+    async fn next_line(&mut self) -> Result<String, ClientError> {
+        self.lines
+            .next_line()
+            .await?
+            .ok_or_else(|| ClientError::Io(io::ErrorKind::UnexpectedEof.into()))
+    }
+}
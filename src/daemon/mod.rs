@@ -0,0 +1,730 @@
+pub mod client;
+pub mod protocol;
+
+use std::{collections::HashSet, fs, io, path::Path, sync::Arc, time::Duration};
+
+use chrono::Utc;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::{UnixListener, UnixStream},
+    signal::unix::{signal, SignalKind},
+    sync::{broadcast, mpsc, oneshot, OnceCell},
+};
+
+use crate::app::AppResult;
+use crate::config::Config;
+use crate::feed::{
+    command::is_trusted,
+    entries::Entry,
+    feeds::{load_feeds, Feed, FeedsManager},
+    fetch::FetchError,
+    health::HealthStore,
+    refresh::{load_feed, record_outcome, LoadFeedError, LoadedFeed},
+    source::FeedSource,
+    store::EntryStore,
+};
+use crate::notify::{announce, NewEntries};
+use crate::sync::{self, reconcile, Snapshot, SyncBackend, SyncError, SyncState};
+use crate::tts::{
+    build_narration_units, model_ready, spawn_narration, NarrationEvent, NarrationHandle,
+    NarrationUiState, NarrationUnit, TTS,
+};
+use crate::websub::{ingest, PushedContent, Subscriber};
+
+use client::DaemonClient;
+use protocol::{
+    EntriesParams, EntrySummary, FeedSummary, MarkReadParams, Notification, PlayParams, Request,
+    Response, RpcError, JSONRPC_VERSION, LIST_ENTRIES, LIST_FEEDS, MARK_READ, NARRATION, PAUSE,
+    PLAY, STOP, SYNC, WATCH,
+};
+
+/// How often feeds are checked against their refresh schedule.
+const REFRESH_TICK: Duration = Duration::from_secs(60);
+const NARRATION_BACKLOG: usize = 64;
+
+type Call = (Request, oneshot::Sender<Response>);
+type Refreshed = (String, Result<LoadedFeed, LoadFeedError>);
+type Synced = Result<(Box<dyn SyncBackend>, Snapshot), SyncError>;
+
+/// Runs refreshes, WebSub and narration without a terminal, serving the
+/// control socket until interrupted.
+pub async fn run(config: Config) -> AppResult<()> {
+    let socket = config.daemon.socket.clone();
+    let listener = bind(&socket).await?;
+    eprintln!("fead daemon listening on {}", socket.display());
+
+    let (narration, mut narration_rx) = spawn_narration();
+    let (subscriber, mut pushed_rx) = if config.websub.enabled {
+        let (subscriber, pushed_rx) = Subscriber::start(&config.websub, &config.network).await?;
+        eprintln!("WebSub callbacks on {}", subscriber.local_addr());
+        (Some(Arc::new(subscriber)), Some(pushed_rx))
+    } else {
+        (None, None)
+    };
+    let mut daemon = Daemon::new(
+        config,
+        load_feeds()?,
+        HealthStore::load()?,
+        EntryStore::load()?,
+        narration.clone(),
+    );
+    let (synced_tx, mut synced_rx) = mpsc::unbounded_channel::<Synced>();
+    let mut sync_tick = match &daemon.config.sync {
+        Some(settings) => {
+            daemon.sync = Some(SyncJobs {
+                state: SyncState::load()?,
+                results: synced_tx,
+                running: false,
+            });
+            let minutes = settings.interval_minutes.max(1);
+            Some(tokio::time::interval(Duration::from_secs(minutes * 60)))
+        }
+        None => None,
+    };
+
+    let (call_tx, mut call_rx) = mpsc::unbounded_channel::<Call>();
+    let (refreshed_tx, mut refreshed_rx) = mpsc::unbounded_channel::<Refreshed>();
+    let (events, _) = broadcast::channel::<NarrationEvent>(NARRATION_BACKLOG);
+    let mut refreshing = HashSet::new();
+    let mut tick = tokio::time::interval(REFRESH_TICK);
+    let mut terminate = signal(SignalKind::terminate())?;
+
+    loop {
+        tokio::select! {
+            _ = tick.tick() => {
+                if let Ok(feeds) = load_feeds() {
+                    daemon.feeds = feeds;
+                }
+                for url in daemon.due_feeds(&refreshing) {
+                    refreshing.insert(url.clone());
+                    let config = daemon.config.clone();
+                    let refreshed_tx = refreshed_tx.clone();
+                    tokio::spawn(async move {
+                        let result = load_feed(&url, &config).await;
+                        let _ = refreshed_tx.send((url, result));
+                    });
+                }
+            }
+            Some((url, result)) = refreshed_rx.recv() => {
+                refreshing.remove(&url);
+                daemon.apply_refresh(&url, result, subscriber.as_ref());
+            }
+            accepted = listener.accept() => {
+                if let Ok((stream, _)) = accepted {
+                    tokio::spawn(serve(stream, call_tx.clone(), events.clone()));
+                }
+            }
+            Some((request, reply)) = call_rx.recv() => {
+                daemon.handle(request, reply);
+            }
+            Some(event) = narration_rx.recv() => {
+                if let NarrationEvent::State(state) = &event {
+                    daemon.narration_state = *state;
+                }
+                let _ = events.send(event);
+            }
+            Some(pushed) = next_push(&mut pushed_rx) => daemon.ingest_push(&pushed),
+            _ = next_tick(&mut sync_tick) => {
+                daemon.start_sync();
+            }
+            Some(synced) = synced_rx.recv() => daemon.apply_sync(synced),
+            _ = tokio::signal::ctrl_c() => break,
+            _ = terminate.recv() => break,
+        }
+    }
+
+    narration.shutdown();
+    let _ = fs::remove_file(&socket);
+    Ok(())
+}
+
+/// Binds the control socket, replacing a stale one left by a crashed daemon.
+async fn bind(socket: &Path) -> AppResult<UnixListener> {
+    if socket.exists() {
+        if DaemonClient::connect(socket).await.is_ok() {
+            return Err(format!("a daemon is already listening on {}", socket.display()).into());
+        }
+        fs::remove_file(socket)?;
+    }
+    Ok(UnixListener::bind(socket)?)
+}
+
+async fn next_tick(interval: &mut Option<tokio::time::Interval>) {
+    match interval {
+        Some(interval) => {
+            interval.tick().await;
+        }
+        None => std::future::pending().await,
+    }
+}
+
+async fn next_push(
+    pushed_rx: &mut Option<mpsc::UnboundedReceiver<PushedContent>>,
+) -> Option<PushedContent> {
+    match pushed_rx {
+        Some(pushed_rx) => pushed_rx.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Reads requests from one connection and hands them to the daemon loop.
+/// After a `watch` call, narration events are written as notifications.
+async fn serve(
+    stream: UnixStream,
+    calls: mpsc::UnboundedSender<Call>,
+    events: broadcast::Sender<NarrationEvent>,
+) -> io::Result<()> {
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader).lines();
+    let mut watching: Option<broadcast::Receiver<NarrationEvent>> = None;
+
+    loop {
+        let line = tokio::select! {
+            line = lines.next_line() => match line? {
+                Some(line) => line,
+                None => return Ok(()),
+            },
+            Some(event) = next_event(&mut watching) => {
+                let notification = Notification {
+                    jsonrpc: JSONRPC_VERSION.to_string(),
+                    method: NARRATION.to_string(),
+                    params: serde_json::to_value(event).unwrap_or_default(),
+                };
+                write_line(&mut writer, &notification).await?;
+                continue;
+            }
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let request = match serde_json::from_str::<Request>(&line) {
+            Ok(request) => request,
+            Err(error) => {
+                let error = RpcError::new(RpcError::PARSE_ERROR, error.to_string());
+                write_line(&mut writer, &Response::new(Value::Null, Err(error))).await?;
+                continue;
+            }
+        };
+        if request.method == WATCH {
+            watching = Some(events.subscribe());
+        }
+        let id = request.id.clone();
+        let (reply_tx, reply_rx) = oneshot::channel();
+        if calls.send((request, reply_tx)).is_err() {
+            return Ok(());
+        }
+        let Ok(response) = reply_rx.await else {
+            return Ok(());
+        };
+        if id.is_some() {
+            write_line(&mut writer, &response).await?;
+        }
+    }
+}
+
+async fn next_event(
+    watching: &mut Option<broadcast::Receiver<NarrationEvent>>,
+) -> Option<NarrationEvent> {
+    let Some(receiver) = watching else {
+        return std::future::pending().await;
+    };
+    loop {
+        match receiver.recv().await {
+            Ok(event) => return Some(event),
+            Err(broadcast::error::RecvError::Lagged(_)) => continue,
+            Err(broadcast::error::RecvError::Closed) => return None,
+        }
+    }
+}
+
+async fn write_line(
+    writer: &mut (impl AsyncWriteExt + Unpin),
+    message: &impl serde::Serialize,
+) -> io::Result<()> {
+    let mut line = serde_json::to_string(message)?;
+    line.push('\n');
+    writer.write_all(line.as_bytes()).await
+}
+
+/// State owned by the daemon loop; connections reach it through [`Call`]s.
+struct Daemon {
+    config: Config,
+    feeds: Vec<Feed>,
+    health: HealthStore,
+    entries: EntryStore,
+    narration: NarrationHandle,
+    narration_state: NarrationUiState,
+    /// Set once the TTS model has been handed to narration.
+    tts_loaded: Arc<OnceCell<()>>,
+    /// Present when a sync server is configured.
+    sync: Option<SyncJobs>,
+}
+
+struct SyncJobs {
+    state: SyncState,
+    results: mpsc::UnboundedSender<Synced>,
+    running: bool,
+}
+
+impl Daemon {
+    fn new(
+        config: Config,
+        feeds: Vec<Feed>,
+        health: HealthStore,
+        entries: EntryStore,
+        narration: NarrationHandle,
+    ) -> Self {
+        Self {
+            config,
+            feeds,
+            health,
+            entries,
+            narration,
+            narration_state: NarrationUiState::Idle,
+            tts_loaded: Arc::new(OnceCell::new()),
+            sync: None,
+        }
+    }
+
+    /// Fetches a snapshot from the sync server in the background. Returns
+    /// `false` when a sync is already running.
+    fn start_sync(&mut self) -> bool {
+        let Some(jobs) = self.sync.as_mut().filter(|jobs| !jobs.running) else {
+            return false;
+        };
+        jobs.running = true;
+        let config = self.config.clone();
+        let results = jobs.results.clone();
+        tokio::spawn(async move {
+            let max_items = config.sync.as_ref().map_or(0, |sync| sync.max_items);
+            let synced = match sync::connect(&config).await {
+                Ok(server) => server
+                    .snapshot(max_items)
+                    .await
+                    .map(|snapshot| (server, snapshot)),
+                Err(error) => Err(error),
+            };
+            let _ = results.send(synced);
+        });
+        true
+    }
+
+    fn apply_sync(&mut self, synced: Synced) {
+        let Some(jobs) = self.sync.as_mut() else {
+            return;
+        };
+        jobs.running = false;
+        let (server, snapshot) = match synced {
+            Ok(synced) => synced,
+            Err(error) => return eprintln!("sync: {error}"),
+        };
+        let _ = self.entries.reload();
+        // The TUI may have added or deleted feeds since the last tick.
+        if let Ok(feeds) = load_feeds() {
+            self.feeds = feeds;
+        }
+        let stored: HashSet<String> = self
+            .feeds
+            .iter()
+            .filter(|feed| !self.entries.entries(&feed.url).is_empty())
+            .map(|feed| feed.url.clone())
+            .collect();
+        let reconciled = match reconcile(
+            &snapshot,
+            &mut self.feeds,
+            &mut self.entries,
+            &mut jobs.state,
+            server.manages_subscriptions(),
+        ) {
+            Ok(reconciled) => reconciled,
+            Err(error) => return eprintln!("sync: {error}"),
+        };
+        eprintln!("sync: {reconciled}");
+        if !reconciled.added_feeds.is_empty() || !reconciled.removed_feeds.is_empty() {
+            if let Err(error) = self.feeds.persist() {
+                eprintln!("sync: could not save feeds: {error}");
+            }
+        }
+        for (url, added) in reconciled.new_entries {
+            if stored.contains(&url) {
+                self.announce(&url, added);
+            }
+        }
+        let changes = reconciled.changes;
+        tokio::spawn(async move {
+            if let Err(error) = server.push(&changes).await {
+                eprintln!("sync: {error}");
+            }
+        });
+    }
+
+    /// Feeds whose scheduled refresh has come, skipping commands that were
+    /// never confirmed in the TUI and feeds the sync server provides.
+    fn due_feeds(&self, refreshing: &HashSet<String>) -> Vec<String> {
+        let now = Utc::now();
+        self.feeds
+            .iter()
+            .map(|feed| feed.url.clone())
+            .filter(|url| !refreshing.contains(url))
+            .filter(|url| {
+                !self
+                    .sync
+                    .as_ref()
+                    .is_some_and(|jobs| jobs.state.is_synced(url))
+            })
+            .filter(|url| self.health.get(url).is_none_or(|health| health.is_due(now)))
+            .filter(|url| {
+                FeedSource::parse(url)
+                    .ok()
+                    .and_then(|source| source.command().map(is_trusted))
+                    .unwrap_or(true)
+            })
+            .collect()
+    }
+
+    fn apply_refresh(
+        &mut self,
+        url: &str,
+        result: Result<LoadedFeed, LoadFeedError>,
+        subscriber: Option<&Arc<Subscriber>>,
+    ) {
+        let _ = self.health.reload();
+        if let Err(error) = record_outcome(&mut self.health, &self.config, url, &result) {
+            eprintln!("{url}: could not record feed health: {error}");
+        }
+        let loaded = match result {
+            Ok(loaded) if loaded.offline.is_none() => loaded,
+            Ok(_) => return eprintln!("{url}: offline, will retry"),
+            Err(LoadFeedError::Fetch(FetchError::Gone)) => {
+                let _ = self.health.mark_gone(url);
+                return eprintln!("{url}: feed is gone (HTTP 410)");
+            }
+            Err(error) => return eprintln!("{url}: {error}"),
+        };
+
+        let _ = self.entries.reload();
+        let first_fetch = self.entries.entries(url).is_empty();
+        match self.entries.merge(url, &loaded.document) {
+            Ok(added) if !added.is_empty() => {
+                eprintln!("{url}: {} new entries", added.len());
+                // A feed's first entries are its backlog, not news.
+                if !first_fetch {
+                    self.announce(url, added);
+                }
+            }
+            Ok(_) => {}
+            Err(error) => eprintln!("{url}: could not store entries: {error}"),
+        }
+        if let Some(moved_to) = &loaded.moved_to {
+            eprintln!("{url}: moved permanently to {moved_to}; open it in fead to update");
+        }
+        if let (Some(subscriber), Some(hub)) = (subscriber, loaded.hub) {
+            if subscriber.needs_subscription(&hub.topic, Utc::now()) {
+                let subscriber = Arc::clone(subscriber);
+                let url = url.to_string();
+                tokio::spawn(async move {
+                    if let Err(error) = subscriber.subscribe(&url, &hub).await {
+                        eprintln!("{url}: WebSub subscription failed: {error}");
+                    }
+                });
+            }
+        }
+    }
+
+    fn ingest_push(&mut self, pushed: &PushedContent) {
+        let _ = self.entries.reload();
+        match ingest(&mut self.entries, pushed) {
+            Ok(added) => {
+                eprintln!("{}: {} entries pushed", pushed.feed_url, added.len());
+                self.announce(&pushed.feed_url, added);
+            }
+            Err(error) => eprintln!("{}: ignored pushed content: {error}", pushed.feed_url),
+        }
+    }
+
+    /// Sends new entries to the configured notifications and hooks without
+    /// holding up the daemon loop.
+    fn announce(&self, url: &str, added: Vec<Entry>) {
+        if added.is_empty() {
+            return;
+        }
+        let config = self.config.clone();
+        let url = url.to_string();
+        let title = self
+            .entries
+            .get(&url)
+            .map(|stored| stored.title.clone())
+            .unwrap_or_default();
+        tokio::spawn(async move {
+            let new = NewEntries {
+                feed: &url,
+                title: &title,
+                entries: &added,
+            };
+            for error in announce(&config, &new).await {
+                eprintln!("{url}: {error}");
+            }
+        });
+    }
+
+    /// Answers a call. The first `PLAY` is answered once the TTS model has
+    /// loaded, which happens off the daemon loop.
+    fn handle(&mut self, request: Request, reply: oneshot::Sender<Response>) {
+        // fead writes entries.json too, marking articles read and syncing.
+        let _ = self.entries.reload();
+        let id = request.id.unwrap_or_default();
+        let outcome = match request.method.as_str() {
+            LIST_FEEDS => Ok(self.list_feeds()),
+            LIST_ENTRIES => params(request.params).and_then(|params| self.list_entries(params)),
+            MARK_READ => params(request.params).and_then(|params| self.mark_read(params)),
+            PLAY => match params(request.params).and_then(|params| self.narration_units(params)) {
+                Ok(units) => return self.play(id, units, reply),
+                Err(error) => Err(error),
+            },
+            PAUSE => {
+                self.narration.toggle_pause();
+                Ok(Value::Null)
+            }
+            STOP => {
+                self.narration.stop();
+                Ok(Value::Null)
+            }
+            SYNC if self.sync.is_none() => Err(RpcError::new(
+                RpcError::UNAVAILABLE,
+                "no sync server is configured",
+            )),
+            SYNC => Ok(json!({ "started": self.start_sync() })),
+            WATCH => serde_json::to_value(self.narration_state)
+                .map_err(|error| RpcError::new(RpcError::INTERNAL_ERROR, error.to_string())),
+            method => Err(RpcError::new(
+                RpcError::METHOD_NOT_FOUND,
+                format!("unknown method `{method}`"),
+            )),
+        };
+        let _ = reply.send(Response::new(id, outcome));
+    }
+
+    fn list_feeds(&self) -> Value {
+        let feeds: Vec<FeedSummary> = self
+            .feeds
+            .iter()
+            .map(|feed| {
+                let health = self.health.get(&feed.url);
+                FeedSummary {
+                    title: feed.title.clone(),
+                    url: feed.url.clone(),
+                    unread: self
+                        .entries
+                        .get(&feed.url)
+                        .map_or(0, |stored| stored.unread_count()),
+                    failing: health.is_some_and(|health| health.is_failing()),
+                    next_refresh: health
+                        .and_then(|health| health.schedule)
+                        .map(|schedule| schedule.next),
+                }
+            })
+            .collect();
+        json!(feeds)
+    }
+
+    fn list_entries(&self, params: EntriesParams) -> Result<Value, RpcError> {
+        let Some(stored) = self.entries.get(&params.feed) else {
+            return Err(not_found(format!("no entries stored for {}", params.feed)));
+        };
+        let entries: Vec<EntrySummary> = stored
+            .entries
+            .iter()
+            .map(|entry| EntrySummary {
+                key: entry.key().to_string(),
+                title: entry.title.clone(),
+                link: entry.link.clone(),
+                published: entry.published.clone(),
+                read: stored.is_read(entry),
+            })
+            .collect();
+        Ok(json!(entries))
+    }
+
+    fn mark_read(&mut self, params: MarkReadParams) -> Result<Value, RpcError> {
+        match self
+            .entries
+            .mark_read(&params.feed, &params.entry, params.read)
+        {
+            Ok(true) => Ok(json!({ "read": params.read })),
+            Ok(false) => Err(not_found(format!(
+                "no entry `{}` in {}",
+                params.entry, params.feed
+            ))),
+            Err(error) => Err(RpcError::new(RpcError::INTERNAL_ERROR, error.to_string())),
+        }
+    }
+
+    fn narration_units(&self, params: PlayParams) -> Result<Vec<NarrationUnit>, RpcError> {
+        Ok(match params {
+            PlayParams::Units { units } => units,
+            PlayParams::Entry { feed, entry } => {
+                let entry = self
+                    .entries
+                    .entries(&feed)
+                    .iter()
+                    .find(|stored| stored.key() == entry)
+                    .ok_or_else(|| not_found(format!("no entry `{entry}` in {feed}")))?;
+                let body = entry.body().ok_or_else(|| {
+                    RpcError::new(RpcError::INVALID_PARAMS, "the entry has no content to read")
+                })?;
+                build_narration_units(&entry.title, body).map_err(|_| {
+                    RpcError::new(RpcError::INVALID_PARAMS, "the entry has no content to read")
+                })?
+            }
+        })
+    }
+
+    fn play(&self, id: Value, units: Vec<NarrationUnit>, reply: oneshot::Sender<Response>) {
+        if self.tts_loaded.initialized() {
+            self.narration.play(units);
+            let _ = reply.send(Response::new(id, Ok(Value::Null)));
+            return;
+        }
+        if !model_ready() {
+            let error = RpcError::new(
+                RpcError::UNAVAILABLE,
+                "TTS model is not downloaded; press t in fead to set it up",
+            );
+            let _ = reply.send(Response::new(id, Err(error)));
+            return;
+        }
+        let tts_loaded = Arc::clone(&self.tts_loaded);
+        let narration = self.narration.clone();
+        tokio::spawn(async move {
+            let loaded = tts_loaded
+                .get_or_try_init(|| async {
+                    let tts = tokio::task::spawn_blocking(TTS::load)
+                        .await
+                        .map_err(|error| error.to_string())??;
+                    narration.set_engine(Some(Arc::new(tts)));
+                    Ok::<_, String>(())
+                })
+                .await;
+            let outcome = match loaded {
+                Ok(()) => {
+                    narration.play(units);
+                    Ok(Value::Null)
+                }
+                Err(error) => Err(RpcError::new(RpcError::UNAVAILABLE, error)),
+            };
+            let _ = reply.send(Response::new(id, outcome));
+        });
+    }
+}
+
+fn params<T: DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    serde_json::from_value(params)
+        .map_err(|error| RpcError::new(RpcError::INVALID_PARAMS, error.to_string()))
+}
+
+fn not_found(message: String) -> RpcError {
+    RpcError::new(RpcError::NOT_FOUND, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+    use tokio::{net::UnixListener, sync::broadcast, sync::mpsc};
+
+    use crate::config::Config;
+    use crate::feed::{
+        entries::{Entry, FeedDocument},
+        feeds::Feed,
+        health::HealthStore,
+        store::EntryStore,
+    };
+    use crate::tts::spawn_narration;
+
+    use super::{
+        client::{ClientError, DaemonClient},
+        protocol::{RpcError, LIST_ENTRIES, LIST_FEEDS, MARK_READ, PLAY},
+        serve, Call, Daemon,
+    };
+
+    #[tokio::test]
+    async fn answers_json_rpc_calls_over_the_socket() {
+        let url = "https://example.com/feed.xml";
+        let mut entries = EntryStore::in_memory();
+        let document = FeedDocument {
+            title: "Example".to_string(),
+            entries: vec![Entry {
+                id: Some("1".to_string()),
+                title: "Hello".to_string(),
+                ..Entry::default()
+            }],
+            ..FeedDocument::default()
+        };
+        entries.merge(url, &document).unwrap();
+        let (narration, _narration_rx) = spawn_narration();
+        let feeds = vec![Feed {
+            title: "Example".to_string(),
+            url: url.to_string(),
+        }];
+        let mut daemon = Daemon::new(
+            Config::default(),
+            feeds,
+            HealthStore::in_memory(),
+            entries,
+            narration,
+        );
+
+        let socket = std::env::temp_dir().join(format!("fead-daemon-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&socket);
+        let listener = UnixListener::bind(&socket).unwrap();
+        let (call_tx, mut call_rx) = mpsc::unbounded_channel::<Call>();
+        let (events, _) = broadcast::channel(4);
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            serve(stream, call_tx, events).await
+        });
+        tokio::spawn(async move {
+            while let Some((request, reply)) = call_rx.recv().await {
+                daemon.handle(request, reply);
+            }
+        });
+
+        let mut client = DaemonClient::connect(&socket).await.unwrap();
+        let feeds = client.call(LIST_FEEDS, Value::Null).await.unwrap();
+        assert_eq!(feeds[0]["unread"], 1);
+
+        let read = client
+            .call(MARK_READ, json!({ "feed": url, "entry": "1" }))
+            .await
+            .unwrap();
+        assert_eq!(read, json!({ "read": true }));
+        let listed = client
+            .call(LIST_ENTRIES, json!({ "feed": url }))
+            .await
+            .unwrap();
+        assert_eq!(listed[0]["title"], "Hello");
+        assert_eq!(listed[0]["read"], true);
+
+        let missing = client
+            .call(PLAY, json!({ "feed": url, "entry": "2" }))
+            .await;
+        assert!(matches!(
+            missing,
+            Err(ClientError::Rpc(RpcError {
+                code: RpcError::NOT_FOUND,
+                ..
+            }))
+        ));
+        let unknown = client.call("explode", Value::Null).await;
+        assert!(matches!(
+            unknown,
+            Err(ClientError::Rpc(RpcError {
+                code: RpcError::METHOD_NOT_FOUND,
+                ..
+            }))
+        ));
+        let _ = std::fs::remove_file(socket);
+    }
+}
@@ -0,0 +1,142 @@
+use std::{error::Error, fmt};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::tts::NarrationUnit;
+
+pub const JSONRPC_VERSION: &str = "2.0";
+
+pub const LIST_FEEDS: &str = "list_feeds";
+pub const LIST_ENTRIES: &str = "list_entries";
+pub const MARK_READ: &str = "mark_read";
+pub const PLAY: &str = "play";
+pub const PAUSE: &str = "pause";
+pub const STOP: &str = "stop";
+/// Starts a sync with the configured server unless one is running.
+pub const SYNC: &str = "sync";
+/// Streams narration events to the connection as notifications.
+pub const WATCH: &str = "watch";
+/// Method name of the notifications sent to watching connections.
+pub const NARRATION: &str = "narration";
+
+/// One JSON-RPC 2.0 request per line. Requests without an id are
+/// notifications and get no response.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Request {
+    pub jsonrpc: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<Value>,
+    pub method: String,
+    #[serde(default)]
+    pub params: Value,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Response {
+    pub jsonrpc: String,
+    pub id: Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<RpcError>,
+}
+
+impl Response {
+    pub fn new(id: Value, outcome: Result<Value, RpcError>) -> Self {
+        let (result, error) = match outcome {
+            Ok(result) => (Some(result), None),
+            Err(error) => (None, Some(error)),
+        };
+        Self {
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            id,
+            result,
+            error,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Notification {
+    pub jsonrpc: String,
+    pub method: String,
+    #[serde(default)]
+    pub params: Value,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+}
+
+impl RpcError {
+    pub const PARSE_ERROR: i64 = -32700;
+    pub const METHOD_NOT_FOUND: i64 = -32601;
+    pub const INVALID_PARAMS: i64 = -32602;
+    pub const INTERNAL_ERROR: i64 = -32603;
+    /// The feed or entry named in the request does not exist.
+    pub const NOT_FOUND: i64 = -32001;
+    /// The request is valid but cannot be served right now.
+    pub const UNAVAILABLE: i64 = -32002;
+
+    pub fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for RpcError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(&self.message)
+    }
+}
+
+impl Error for RpcError {}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct FeedSummary {
+    pub title: String,
+    pub url: String,
+    pub unread: usize,
+    pub failing: bool,
+    pub next_refresh: Option<DateTime<Utc>>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct EntrySummary {
+    pub key: String,
+    pub title: String,
+    pub link: Option<String>,
+    pub published: Option<String>,
+    pub read: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EntriesParams {
+    pub feed: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MarkReadParams {
+    pub feed: String,
+    pub entry: String,
+    #[serde(default = "read_by_default")]
+    pub read: bool,
+}
+
+fn read_by_default() -> bool {
+    true
+}
+
+/// Narrates a stored entry, or text the caller already prepared.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum PlayParams {
+    Entry { feed: String, entry: String },
+    Units { units: Vec<NarrationUnit> },
+}
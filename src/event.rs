@@ -13,6 +13,7 @@ pub enum Event {
 
 #[derive(Debug)]
 pub struct EventHandler {
+    sender: mpsc::UnboundedSender<Event>,
     receiver: mpsc::UnboundedReceiver<Event>,
     handler: tokio::task::JoinHandle<()>,
 }
@@ -20,7 +21,16 @@ pub struct EventHandler {
 impl EventHandler {
     pub fn new() -> Self {
         let (sender, receiver) = mpsc::unbounded_channel();
-        let handler = tokio::spawn(async move {
+        let handler = Self::listen(sender.clone());
+        Self {
+            sender,
+            receiver,
+            handler,
+        }
+    }
+
+    fn listen(sender: mpsc::UnboundedSender<Event>) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
             let mut reader = crossterm::event::EventStream::new();
 
             while let Some(Ok(event)) = reader.next().await {
@@ -39,9 +49,17 @@ impl EventHandler {
                     break;
                 }
             }
-        });
+        })
+    }
+
+    /// Stops reading the terminal so another program can use it.
+    pub async fn pause(&mut self) {
+        self.handler.abort();
+        let _ = (&mut self.handler).await;
+    }
 
-        Self { receiver, handler }
+    pub fn resume(&mut self) {
+        self.handler = Self::listen(self.sender.clone());
     }
 
     pub async fn next(&mut self) -> AppResult<Event> {
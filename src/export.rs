@@ -0,0 +1,515 @@
+use std::{
+    error::Error,
+    fmt,
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Utc};
+use htmd::HtmlToMarkdown;
+use pulldown_cmark::{html::push_html, Event, Options, Parser};
+use zip::{result::ZipError, write::SimpleFileOptions, CompressionMethod, ZipWriter};
+
+use crate::config::ExportFormat;
+use crate::feed::{cache::fnv1a, entries::Entry};
+
+/// Longest file name stem derived from a title.
+const MAX_STEM_LENGTH: usize = 60;
+
+const STYLESHEET: &str = "body { font-family: Georgia, serif; line-height: 1.5; margin: 0 auto; max-width: 40em; padding: 1em; }
+header { border-bottom: 1px solid #ccc; margin-bottom: 1.5em; }
+.meta { color: #666; }
+img { max-width: 100%; height: auto; }
+pre { overflow-x: auto; white-space: pre-wrap; }
+";
+
+#[derive(Debug)]
+pub enum ExportError {
+    NothingToExport,
+    Io(io::Error),
+    Epub(ZipError),
+}
+
+impl fmt::Display for ExportError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NothingToExport => formatter.write_str("there are no entries to export"),
+            Self::Io(error) => write!(formatter, "could not write the file: {error}"),
+            Self::Epub(error) => write!(formatter, "could not write the EPUB: {error}"),
+        }
+    }
+}
+
+impl Error for ExportError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::NothingToExport => None,
+            Self::Io(error) => Some(error),
+            Self::Epub(error) => Some(error),
+        }
+    }
+}
+
+impl From<io::Error> for ExportError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl From<ZipError> for ExportError {
+    fn from(error: ZipError) -> Self {
+        Self::Epub(error)
+    }
+}
+
+/// An entry together with the title of the feed it came from.
+#[derive(Clone, Copy, Debug)]
+pub struct Article<'a> {
+    pub entry: &'a Entry,
+    pub source: &'a str,
+}
+
+impl Article<'_> {
+    /// The feed title and publication date, as shown under the heading.
+    fn byline(&self) -> String {
+        let date = self.entry.published.as_deref().map(|published| {
+            DateTime::parse_from_rfc3339(published)
+                .map(|date| date.format("%Y-%m-%d").to_string())
+                .unwrap_or_else(|_| published.to_string())
+        });
+        [Some(self.source.to_string()), date]
+            .into_iter()
+            .flatten()
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join(" · ")
+    }
+
+    fn body_markdown(&self) -> String {
+        match self.entry.body() {
+            Some(content) if content.kind.is_markup() => HtmlToMarkdown::builder()
+                .skip_tags(vec!["script", "style", "noscript"])
+                .build()
+                .convert(&content.value)
+                .unwrap_or_else(|_| content.value.clone()),
+            Some(content) => content.value.clone(),
+            None => String::new(),
+        }
+    }
+
+    /// The body as XHTML. Markup goes through Markdown first, which leaves
+    /// out scripts, styles and presentational markup, and any raw HTML left
+    /// over is shown as text so the result stays well formed.
+    fn body_xhtml(&self) -> String {
+        match self.entry.body() {
+            Some(content) if content.kind.is_markup() => {
+                let markdown = self.body_markdown();
+                let parser =
+                    Parser::new_ext(&markdown, Options::ENABLE_TABLES).map(|event| match event {
+                        Event::Html(html) | Event::InlineHtml(html) => Event::Text(html),
+                        event => event,
+                    });
+                let mut html = String::new();
+                push_html(&mut html, parser);
+                html
+            }
+            Some(content) => content
+                .value
+                .split("\n\n")
+                .map(str::trim)
+                .filter(|paragraph| !paragraph.is_empty())
+                .map(|paragraph| format!("<p>{}</p>\n", escape(paragraph)))
+                .collect(),
+            None => String::new(),
+        }
+    }
+
+    fn xhtml(&self) -> String {
+        let mut header = format!("<h1>{}</h1>\n", escape(&self.entry.title));
+        let byline = self.byline();
+        if !byline.is_empty() {
+            header.push_str(&format!("<p class=\"meta\">{}</p>\n", escape(&byline)));
+        }
+        if let Some(link) = &self.entry.link {
+            let link = escape(link);
+            header.push_str(&format!(
+                "<p class=\"meta\"><a href=\"{link}\">{link}</a></p>\n"
+            ));
+        }
+        format!(
+            "<article>\n<header>\n{header}</header>\n{}</article>\n",
+            self.body_xhtml()
+        )
+    }
+}
+
+/// The article as Markdown, with the title, source, date and link up top.
+pub fn markdown(article: Article<'_>) -> String {
+    let mut markdown = format!("# {}\n\n", article.entry.title);
+    let byline = article.byline();
+    if !byline.is_empty() {
+        markdown.push_str(&format!("*{byline}*\n\n"));
+    }
+    if let Some(link) = &article.entry.link {
+        markdown.push_str(&format!("<{link}>\n\n"));
+    }
+    markdown.push_str(article.body_markdown().trim());
+    markdown.push('\n');
+    markdown
+}
+
+/// The article as a single HTML page that needs no other files.
+pub fn html(article: Article<'_>) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>\n{STYLESHEET}</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+        escape(&article.entry.title),
+        article.xhtml()
+    )
+}
+
+/// Writes the article into `directory`, named after its title, and returns
+/// the new file's path. Existing files are never overwritten.
+pub fn save_article(
+    article: Article<'_>,
+    format: ExportFormat,
+    directory: &Path,
+) -> Result<PathBuf, ExportError> {
+    let (contents, extension) = match format {
+        ExportFormat::Markdown => (markdown(article), "md"),
+        ExportFormat::Html => (html(article), "html"),
+    };
+    let (path, mut file) = create_unique(directory, &article.entry.title, extension)?;
+    file.write_all(contents.as_bytes())?;
+    Ok(path)
+}
+
+/// Bundles the articles, in order, into an EPUB 3 book with one chapter each.
+pub fn save_epub(
+    title: &str,
+    articles: &[Article<'_>],
+    directory: &Path,
+) -> Result<PathBuf, ExportError> {
+    if articles.is_empty() {
+        return Err(ExportError::NothingToExport);
+    }
+    let (path, file) = create_unique(directory, title, "epub")?;
+    if let Err(error) = write_epub(file, title, articles) {
+        let _ = fs::remove_file(&path);
+        return Err(error);
+    }
+    Ok(path)
+}
+
+fn write_epub(file: File, title: &str, articles: &[Article<'_>]) -> Result<(), ExportError> {
+    let mut zip = ZipWriter::new(file);
+    // Readers identify the format by an uncompressed `mimetype` entry that
+    // comes first in the archive.
+    zip.start_file(
+        "mimetype",
+        SimpleFileOptions::default().compression_method(CompressionMethod::Stored),
+    )?;
+    zip.write_all(b"application/epub+zip")?;
+
+    let chapters: Vec<String> = (1..=articles.len())
+        .map(|number| format!("chapter-{number:03}.xhtml"))
+        .collect();
+    let identifier = format!(
+        "urn:fead:{:016x}",
+        fnv1a(
+            articles
+                .iter()
+                .map(|article| article.entry.key())
+                .collect::<Vec<_>>()
+                .join("\n")
+                .as_bytes()
+        )
+    );
+    let files = [
+        ("META-INF/container.xml".to_string(), CONTAINER.to_string()),
+        (
+            "OEBPS/content.opf".to_string(),
+            package(title, &identifier, &chapters),
+        ),
+        (
+            "OEBPS/nav.xhtml".to_string(),
+            navigation(title, articles, &chapters),
+        ),
+        (
+            "OEBPS/toc.ncx".to_string(),
+            ncx(title, &identifier, articles, &chapters),
+        ),
+        ("OEBPS/style.css".to_string(), STYLESHEET.to_string()),
+    ];
+    let chapter_files = articles.iter().zip(&chapters).map(|(article, chapter)| {
+        (
+            format!("OEBPS/{chapter}"),
+            xhtml_document(&article.entry.title, &article.xhtml()),
+        )
+    });
+
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    for (name, contents) in files.into_iter().chain(chapter_files) {
+        zip.start_file(name, options)?;
+        zip.write_all(contents.as_bytes())?;
+    }
+    zip.finish()?;
+    Ok(())
+}
+
+const CONTAINER: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<container version="1.0" xmlns="urn:oasis:names:tc:opendocument:xmlns:container">
+  <rootfiles>
+    <rootfile full-path="OEBPS/content.opf" media-type="application/oebps-package+xml"/>
+  </rootfiles>
+</container>
+"#;
+
+fn package(title: &str, identifier: &str, chapters: &[String]) -> String {
+    let modified = Utc::now().format("%Y-%m-%dT%H:%M:%SZ");
+    let manifest: String = chapters
+        .iter()
+        .enumerate()
+        .map(|(index, chapter)| {
+            format!(
+                "    <item id=\"chapter-{index}\" href=\"{chapter}\" media-type=\"application/xhtml+xml\"/>\n"
+            )
+        })
+        .collect();
+    let spine: String = (0..chapters.len())
+        .map(|index| format!("    <itemref idref=\"chapter-{index}\"/>\n"))
+        .collect();
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<package xmlns="http://www.idpf.org/2007/opf" version="3.0" unique-identifier="book-id">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+    <dc:identifier id="book-id">{identifier}</dc:identifier>
+    <dc:title>{}</dc:title>
+    <dc:language>en</dc:language>
+    <dc:creator>fead</dc:creator>
+    <meta property="dcterms:modified">{modified}</meta>
+  </metadata>
+  <manifest>
+    <item id="nav" href="nav.xhtml" media-type="application/xhtml+xml" properties="nav"/>
+    <item id="ncx" href="toc.ncx" media-type="application/x-dtbncx+xml"/>
+    <item id="style" href="style.css" media-type="text/css"/>
+{manifest}  </manifest>
+  <spine toc="ncx">
+{spine}  </spine>
+</package>
+"#,
+        escape(title)
+    )
+}
+
+fn navigation(title: &str, articles: &[Article<'_>], chapters: &[String]) -> String {
+    let items: String = articles
+        .iter()
+        .zip(chapters)
+        .map(|(article, chapter)| {
+            format!(
+                "<li><a href=\"{chapter}\">{}</a></li>\n",
+                escape(&article.entry.title)
+            )
+        })
+        .collect();
+    xhtml_document(
+        title,
+        &format!(
+            "<nav epub:type=\"toc\" id=\"toc\">\n<h1>{}</h1>\n<ol>\n{items}</ol>\n</nav>\n",
+            escape(title)
+        ),
+    )
+}
+
+/// The EPUB 2 table of contents, still read by older devices.
+fn ncx(title: &str, identifier: &str, articles: &[Article<'_>], chapters: &[String]) -> String {
+    let points: String = articles
+        .iter()
+        .zip(chapters)
+        .enumerate()
+        .map(|(index, (article, chapter))| {
+            format!(
+                "    <navPoint id=\"point-{index}\" playOrder=\"{}\"><navLabel><text>{}</text></navLabel><content src=\"{chapter}\"/></navPoint>\n",
+                index + 1,
+                escape(&article.entry.title)
+            )
+        })
+        .collect();
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<ncx xmlns="http://www.daisy.org/z3986/2005/ncx/" version="2005-1">
+  <head><meta name="dtb:uid" content="{identifier}"/></head>
+  <docTitle><text>{}</text></docTitle>
+  <navMap>
+{points}  </navMap>
+</ncx>
+"#,
+        escape(title)
+    )
+}
+
+fn xhtml_document(title: &str, body: &str) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE html>
+<html xmlns="http://www.w3.org/1999/xhtml" xmlns:epub="http://www.idpf.org/2007/ops">
+<head>
+<title>{}</title>
+<link rel="stylesheet" type="text/css" href="style.css"/>
+</head>
+<body>
+{body}</body>
+</html>
+"#,
+        escape(title)
+    )
+}
+
+/// Opens a new file named after `title`, adding `-2`, `-3`, … when the
+/// name is taken.
+fn create_unique(directory: &Path, title: &str, extension: &str) -> io::Result<(PathBuf, File)> {
+    fs::create_dir_all(directory)?;
+    let stem = file_stem(title);
+    for attempt in 1.. {
+        let name = match attempt {
+            1 => format!("{stem}.{extension}"),
+            _ => format!("{stem}-{attempt}.{extension}"),
+        };
+        let path = directory.join(name);
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(file) => return Ok((path, file)),
+            Err(error) if error.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(error) => return Err(error),
+        }
+    }
+    unreachable!("ran out of file names")
+}
+
+/// Lowercase letters and digits from the title, joined by dashes.
+fn file_stem(title: &str) -> String {
+    let mut stem = String::new();
+    for word in title
+        .split(|character: char| !character.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+    {
+        let word = word.to_lowercase();
+        if stem.len() + word.len() >= MAX_STEM_LENGTH {
+            break;
+        }
+        if !stem.is_empty() {
+            stem.push('-');
+        }
+        stem.push_str(&word);
+    }
+    if stem.is_empty() {
+        stem.push_str("article");
+    }
+    stem
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for character in text.chars() {
+        match character {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            character => escaped.push(character),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, io::Read};
+
+    use zip::ZipArchive;
+
+    use crate::config::ExportFormat;
+    use crate::feed::entries::{ContentKind, Entry, EntryContent};
+
+    use super::{markdown, save_article, save_epub, Article};
+
+    fn entry(title: &str, body: &str) -> Entry {
+        Entry {
+            id: Some(title.to_string()),
+            title: title.to_string(),
+            author: None,
+            link: Some("https://example.com/post?a=1&b=2".to_string()),
+            summary: None,
+            content: Some(EntryContent {
+                value: body.to_string(),
+                kind: ContentKind::Html,
+            }),
+            published: Some("2025-07-14T18:20:00Z".to_string()),
+        }
+    }
+
+    #[test]
+    fn writes_articles_as_markdown_and_html_files() {
+        let entry = entry(
+            "Rust & Friends",
+            "<p>Hello <b>world</b></p><script>x()</script>",
+        );
+        let article = Article {
+            entry: &entry,
+            source: "Example Blog",
+        };
+        assert_eq!(
+            markdown(article),
+            "# Rust & Friends\n\n*Example Blog · 2025-07-14*\n\n<https://example.com/post?a=1&b=2>\n\nHello **world**\n"
+        );
+
+        let dir = std::env::temp_dir().join(format!("fead-export-{}", std::process::id()));
+        let first = save_article(article, ExportFormat::Html, &dir).unwrap();
+        let second = save_article(article, ExportFormat::Html, &dir).unwrap();
+        assert_eq!(first.file_name().unwrap(), "rust-friends.html");
+        assert_eq!(second.file_name().unwrap(), "rust-friends-2.html");
+        let html = fs::read_to_string(&first).unwrap();
+        assert!(html.contains("<h1>Rust &amp; Friends</h1>"));
+        assert!(html.contains("<strong>world</strong>"));
+        assert!(!html.contains("x()"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn bundles_entries_into_an_epub() {
+        let entries = [
+            entry("First", "<p>One</p>"),
+            entry("Second", "<p>Two<br>lines</p>"),
+        ];
+        let articles: Vec<_> = entries
+            .iter()
+            .map(|entry| Article {
+                entry,
+                source: "Example",
+            })
+            .collect();
+        let dir = std::env::temp_dir().join(format!("fead-epub-{}", std::process::id()));
+
+        let path = save_epub("Example unread", &articles, &dir).unwrap();
+        assert_eq!(path.file_name().unwrap(), "example-unread.epub");
+        let mut archive = ZipArchive::new(fs::File::open(&path).unwrap()).unwrap();
+        assert_eq!(archive.by_index(0).unwrap().name(), "mimetype");
+        let mut package = String::new();
+        archive
+            .by_name("OEBPS/content.opf")
+            .unwrap()
+            .read_to_string(&mut package)
+            .unwrap();
+        assert!(package.contains(r#"<itemref idref="chapter-1"/>"#));
+        let mut chapter = String::new();
+        archive
+            .by_name("OEBPS/chapter-002.xhtml")
+            .unwrap()
+            .read_to_string(&mut chapter)
+            .unwrap();
+        assert!(chapter.contains("<h1>Second</h1>"));
+        assert!(chapter.contains("href=\"https://example.com/post?a=1&amp;b=2\""));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
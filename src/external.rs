@@ -0,0 +1,183 @@
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+use std::{
+    env,
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::PathBuf,
+    process::{ExitStatus, Stdio},
+};
+
+use tokio::{io::AsyncWriteExt, process::Command};
+
+const DEFAULT_PAGER: &str = "less";
+const DEFAULT_EDITOR: &str = "vi";
+#[cfg(target_os = "macos")]
+const DEFAULT_OPENER: &str = "open";
+#[cfg(not(target_os = "macos"))]
+const DEFAULT_OPENER: &str = "xdg-open";
+
+/// A program the article can be handed to while the TUI is suspended.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Viewer {
+    /// `$PAGER`, reading the article on stdin.
+    Pager,
+    /// `$VISUAL` or `$EDITOR`, opening a temporary copy of the article.
+    Editor,
+}
+
+impl Viewer {
+    /// The shell command to run, from the environment or a common default.
+    pub fn command(self) -> String {
+        let variables: &[&str] = match self {
+            Self::Pager => &["PAGER"],
+            Self::Editor => &["VISUAL", "EDITOR"],
+        };
+        variables
+            .iter()
+            .filter_map(|name| env::var(name).ok())
+            .find(|command| !command.trim().is_empty())
+            .unwrap_or_else(|| {
+                match self {
+                    Self::Pager => DEFAULT_PAGER,
+                    Self::Editor => DEFAULT_EDITOR,
+                }
+                .to_string()
+            })
+    }
+
+    /// Runs the viewer on `text` in the current terminal and waits for it to
+    /// exit. The caller must have released the terminal first.
+    pub async fn open(self, text: &str) -> io::Result<ExitStatus> {
+        self.open_with(&self.command(), text).await
+    }
+
+    /// Like [`Self::open`], running `command` instead of the configured viewer.
+    pub async fn open_with(self, command: &str, text: &str) -> io::Result<ExitStatus> {
+        match self {
+            Self::Pager => {
+                let mut child = Command::new("sh")
+                    .arg("-c")
+                    .arg(command)
+                    .stdin(Stdio::piped())
+                    .spawn()?;
+                if let Some(mut stdin) = child.stdin.take() {
+                    // Quitting the pager early closes the pipe.
+                    match stdin.write_all(text.as_bytes()).await {
+                        Err(error) if error.kind() != io::ErrorKind::BrokenPipe => {
+                            return Err(error)
+                        }
+                        _ => {}
+                    }
+                }
+                child.wait().await
+            }
+            Self::Editor => {
+                let path = create_scratch(text)?;
+                // The path is passed as `$1` so it never needs quoting.
+                let status = Command::new("sh")
+                    .arg("-c")
+                    .arg(format!("{command} \"$1\""))
+                    .arg("sh")
+                    .arg(&path)
+                    .status()
+                    .await;
+                let _ = fs::remove_file(&path);
+                status
+            }
+        }
+    }
+}
+
+/// Opens a link in `$BROWSER`, or the desktop's default handler, without
+/// waiting for it.
+pub fn open_url(url: &str) -> io::Result<()> {
+    let mut command = match env::var("BROWSER")
+        .ok()
+        .filter(|browser| !browser.trim().is_empty())
+    {
+        Some(browser) => {
+            let mut command = Command::new("sh");
+            command
+                .arg("-c")
+                .arg(format!("{browser} \"$1\""))
+                .arg("sh")
+                .arg(url);
+            command
+        }
+        None => {
+            let mut command = Command::new(DEFAULT_OPENER);
+            command.arg(url);
+            command
+        }
+    };
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map(drop)
+}
+
+/// Writes the article to a new file in the temporary directory, readable
+/// only by the user. Existing files, and symlinks planted in their place, are
+/// never opened; the next free name is used instead.
+fn create_scratch(text: &str) -> io::Result<PathBuf> {
+    for attempt in 1.. {
+        let path =
+            env::temp_dir().join(format!("fead-article-{}-{attempt}.md", std::process::id()));
+        let mut options = OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        options.mode(0o600);
+        match options.open(&path) {
+            Ok(mut file) => {
+                if let Err(error) = file.write_all(text.as_bytes()) {
+                    let _ = fs::remove_file(&path);
+                    return Err(error);
+                }
+                return Ok(path);
+            }
+            Err(error) if error.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(error) => return Err(error),
+        }
+    }
+    unreachable!("ran out of file names")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{create_scratch, Viewer};
+
+    #[tokio::test]
+    async fn pipes_the_article_to_the_pager() {
+        let output = std::env::temp_dir().join(format!("fead-pager-{}.md", std::process::id()));
+        let command = format!("cat > {}", output.display());
+
+        let status = Viewer::Pager
+            .open_with(&command, "# Title\n\nBody\n")
+            .await
+            .unwrap();
+        assert!(status.success());
+        assert_eq!(
+            std::fs::read_to_string(&output).unwrap(),
+            "# Title\n\nBody\n"
+        );
+        std::fs::remove_file(&output).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn writes_scratch_copies_to_new_private_files() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let first = create_scratch("first").unwrap();
+        let second = create_scratch("second").unwrap();
+        assert_ne!(first, second);
+        assert_eq!(std::fs::read_to_string(&first).unwrap(), "first");
+        let mode = std::fs::metadata(&first).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        std::fs::remove_file(first).unwrap();
+        std::fs::remove_file(second).unwrap();
+    }
+}
@@ -0,0 +1,122 @@
+use std::{
+    fs, io,
+    path::PathBuf,
+    time::{Duration, SystemTime},
+};
+
+const CACHE_DIR: &str = "cache/feeds";
+
+#[derive(Clone, Debug)]
+pub struct CachedFeed {
+    pub content: Vec<u8>,
+    pub fetched_at: SystemTime,
+}
+
+impl CachedFeed {
+    pub fn age(&self) -> Duration {
+        SystemTime::now()
+            .duration_since(self.fetched_at)
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct FeedCache {
+    dir: PathBuf,
+}
+
+impl Default for FeedCache {
+    fn default() -> Self {
+        Self::new(CACHE_DIR)
+    }
+}
+
+impl FeedCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn store(&self, url: &str, content: &[u8]) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let path = self.path_for(url);
+        let partial = path.with_extension("part");
+        fs::write(&partial, content)?;
+        fs::rename(partial, path)
+    }
+
+    pub fn load(&self, url: &str) -> io::Result<Option<CachedFeed>> {
+        let path = self.path_for(url);
+        let content = match fs::read(&path) {
+            Ok(content) => content,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(error) => return Err(error),
+        };
+        let fetched_at = fs::metadata(&path)?.modified()?;
+        Ok(Some(CachedFeed {
+            content,
+            fetched_at,
+        }))
+    }
+
+    pub fn remove(&self, url: &str) -> io::Result<()> {
+        match fs::remove_file(self.path_for(url)) {
+            Err(error) if error.kind() != io::ErrorKind::NotFound => Err(error),
+            _ => Ok(()),
+        }
+    }
+
+    fn path_for(&self, url: &str) -> PathBuf {
+        self.dir.join(format!("{:016x}.xml", fnv1a(url.as_bytes())))
+    }
+}
+
+pub(crate) fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+pub fn format_age(age: Duration) -> String {
+    let seconds = age.as_secs();
+    match seconds {
+        0..60 => "just now".to_string(),
+        60..3_600 => format!("{}m ago", seconds / 60),
+        3_600..86_400 => format!("{}h ago", seconds / 3_600),
+        _ => format!("{}d ago", seconds / 86_400),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{format_age, FeedCache};
+
+    #[test]
+    fn stores_and_loads_raw_feed_bytes_per_url() {
+        let dir = std::env::temp_dir().join(format!("fead-cache-{}", std::process::id()));
+        let cache = FeedCache::new(&dir);
+
+        cache.store("https://example.com/a.xml", b"<rss/>").unwrap();
+        cache
+            .store("https://example.com/b.xml", b"<feed/>")
+            .unwrap();
+
+        let cached = cache.load("https://example.com/a.xml").unwrap().unwrap();
+        assert_eq!(cached.content, b"<rss/>");
+        assert!(cached.age() < Duration::from_secs(60));
+        assert!(cache.load("https://example.com/c.xml").unwrap().is_none());
+
+        cache.remove("https://example.com/a.xml").unwrap();
+        assert!(cache.load("https://example.com/a.xml").unwrap().is_none());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn formats_ages_in_the_largest_whole_unit() {
+        assert_eq!(format_age(Duration::from_secs(5)), "just now");
+        assert_eq!(format_age(Duration::from_secs(150)), "2m ago");
+        assert_eq!(format_age(Duration::from_secs(3 * 3_600 + 59)), "3h ago");
+        assert_eq!(format_age(Duration::from_secs(2 * 86_400)), "2d ago");
+    }
+}
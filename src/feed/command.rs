@@ -0,0 +1,169 @@
+use std::{fs, io, path::Path, process::Stdio, time::Duration};
+
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    process::Command,
+};
+
+use crate::app::AppResult;
+use crate::feed::fetch::FetchError;
+
+pub const COMMAND_TIMEOUT: Duration = Duration::from_secs(30);
+
+const TRUSTED_COMMANDS: &str = "trusted_commands.json";
+const MAX_STDERR_CHARS: usize = 200;
+
+pub async fn run_command(
+    command: &str,
+    input: Option<&[u8]>,
+    timeout: Duration,
+) -> Result<Vec<u8>, FetchError> {
+    run_command_limited(command, input, timeout, u64::MAX).await
+}
+
+/// Runs `command` like [`run_command`], stopping it as soon as its output
+/// exceeds `limit` bytes.
+pub async fn run_command_limited(
+    command: &str,
+    input: Option<&[u8]>,
+    timeout: Duration,
+    limit: u64,
+) -> Result<Vec<u8>, FetchError> {
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .stdin(if input.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(FetchError::Command)?;
+
+    if let (Some(mut stdin), Some(input)) = (child.stdin.take(), input) {
+        let input = input.to_vec();
+        tokio::spawn(async move {
+            let _ = stdin.write_all(&input).await;
+        });
+    }
+    let stderr = child.stderr.take().map(|mut stderr| {
+        tokio::spawn(async move {
+            let mut output = Vec::new();
+            let _ = stderr.read_to_end(&mut output).await;
+            output
+        })
+    });
+
+    let run = async {
+        let mut stdout = Vec::new();
+        if let Some(pipe) = child.stdout.take() {
+            pipe.take(limit.saturating_add(1))
+                .read_to_end(&mut stdout)
+                .await
+                .map_err(FetchError::Command)?;
+        }
+        if stdout.len() as u64 > limit {
+            return Err(FetchError::TooLarge { limit });
+        }
+        let status = child.wait().await.map_err(FetchError::Command)?;
+        Ok((status, stdout))
+    };
+    let (status, stdout) = tokio::time::timeout(timeout, run)
+        .await
+        .map_err(|_| FetchError::CommandTimedOut(timeout))??;
+
+    if !status.success() {
+        let stderr = match stderr {
+            Some(task) => task.await.unwrap_or_default(),
+            None => Vec::new(),
+        };
+        return Err(FetchError::CommandFailed {
+            status: status.code(),
+            stderr: summarize_stderr(&stderr),
+        });
+    }
+    Ok(stdout)
+}
+
+fn summarize_stderr(stderr: &[u8]) -> String {
+    let stderr = String::from_utf8_lossy(stderr);
+    let summary = stderr.split_whitespace().collect::<Vec<_>>().join(" ");
+    if summary.chars().count() <= MAX_STDERR_CHARS {
+        return summary;
+    }
+    let mut truncated = summary.chars().take(MAX_STDERR_CHARS).collect::<String>();
+    truncated.push('…');
+    truncated
+}
+
+pub fn is_trusted(command: &str) -> bool {
+    load_trusted().is_ok_and(|trusted| trusted.iter().any(|known| known == command))
+}
+
+pub fn trust(command: &str) -> AppResult<()> {
+    let mut trusted = load_trusted()?;
+    if !trusted.iter().any(|known| known == command) {
+        trusted.push(command.to_string());
+    }
+    let json = serde_json::to_string_pretty(&trusted)?;
+    fs::write(TRUSTED_COMMANDS, format!("{json}\n"))?;
+    Ok(())
+}
+
+fn load_trusted() -> AppResult<Vec<String>> {
+    let path = Path::new(TRUSTED_COMMANDS);
+    match fs::read_to_string(path) {
+        Ok(contents) if contents.trim().is_empty() => Ok(Vec::new()),
+        Ok(contents) => Ok(serde_json::from_str(&contents)?),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(error) => Err(error.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::feed::fetch::FetchError;
+
+    use super::{run_command, run_command_limited, COMMAND_TIMEOUT};
+
+    #[tokio::test]
+    async fn captures_stdout_and_pipes_input() {
+        let output = run_command("printf '<rss/>'", None, COMMAND_TIMEOUT)
+            .await
+            .unwrap();
+        assert_eq!(output, b"<rss/>");
+
+        let filtered = run_command("tr a-z A-Z", Some(b"<rss/>"), COMMAND_TIMEOUT)
+            .await
+            .unwrap();
+        assert_eq!(filtered, b"<RSS/>");
+    }
+
+    #[tokio::test]
+    async fn reports_stderr_and_timeouts() {
+        let failed = run_command("echo 'no such feed' >&2; exit 3", None, COMMAND_TIMEOUT).await;
+        assert!(matches!(
+            failed,
+            Err(FetchError::CommandFailed { status: Some(3), ref stderr }) if stderr == "no such feed"
+        ));
+
+        let slow = run_command("sleep 5", None, Duration::from_millis(50)).await;
+        assert!(matches!(slow, Err(FetchError::CommandTimedOut(_))));
+    }
+
+    #[tokio::test]
+    async fn stops_commands_whose_output_is_too_large() {
+        let endless = run_command_limited("yes", None, COMMAND_TIMEOUT, 1024).await;
+        assert!(matches!(endless, Err(FetchError::TooLarge { limit: 1024 })));
+
+        let small = run_command_limited("printf '<rss/>'", None, COMMAND_TIMEOUT, 6)
+            .await
+            .unwrap();
+        assert_eq!(small, b"<rss/>");
+    }
+}
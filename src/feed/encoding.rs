@@ -0,0 +1,171 @@
+use std::borrow::Cow;
+
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, UTF_8, WINDOWS_1252};
+
+/// How far into the document an XML declaration is looked for.
+const PROLOG_LIMIT: usize = 256;
+
+/// Transcodes a feed document to UTF-8 and rewrites its XML declaration to
+/// match, so the XML parser does not decode it a second time.
+///
+/// A byte-order mark wins, then the HTTP `charset`, then the XML declaration.
+/// Declarations are often wrong, so bytes that are valid UTF-8 are kept as
+/// UTF-8 and invalid UTF-8 falls back to Windows-1252.
+pub fn to_utf8<'a>(content: &'a [u8], http_charset: Option<&str>) -> Cow<'a, [u8]> {
+    if let Some((encoding, bom_length)) = Encoding::for_bom(content) {
+        let (text, _) = encoding.decode_without_bom_handling(&content[bom_length..]);
+        return Cow::Owned(declare_utf8(&text).into_bytes());
+    }
+
+    let declared = xml_declared_encoding(content);
+    let label = http_charset.or(declared.as_deref());
+    let encoding = label
+        .and_then(|label| Encoding::for_label(label.trim().as_bytes()))
+        .or_else(|| sniff_utf16(content));
+
+    let encoding = match encoding {
+        Some(encoding) if encoding == UTF_16LE || encoding == UTF_16BE => encoding,
+        _ if std::str::from_utf8(content).is_ok() => UTF_8,
+        Some(encoding) if encoding != UTF_8 => encoding,
+        _ => WINDOWS_1252,
+    };
+
+    if encoding == UTF_8 && declared.as_deref().is_none_or(is_utf8_label) {
+        return Cow::Borrowed(content);
+    }
+    let (text, _) = encoding.decode_without_bom_handling(content);
+    Cow::Owned(declare_utf8(&text).into_bytes())
+}
+
+/// Extracts the charset parameter from a `Content-Type` header value.
+pub fn charset_parameter(content_type: &str) -> Option<&str> {
+    content_type.split(';').skip(1).find_map(|parameter| {
+        let (name, value) = parameter.split_once('=')?;
+        name.trim()
+            .eq_ignore_ascii_case("charset")
+            .then(|| value.trim().trim_matches(['"', '\'']))
+    })
+}
+
+fn is_utf8_label(label: &str) -> bool {
+    Encoding::for_label(label.trim().as_bytes()) == Some(UTF_8)
+}
+
+fn sniff_utf16(content: &[u8]) -> Option<&'static Encoding> {
+    match content {
+        [b'<', 0, b'?', 0, ..] => Some(UTF_16LE),
+        [0, b'<', 0, b'?', ..] => Some(UTF_16BE),
+        _ => None,
+    }
+}
+
+fn xml_declared_encoding(content: &[u8]) -> Option<String> {
+    let prolog = &content[..content.len().min(PROLOG_LIMIT)];
+    let prolog = String::from_utf8_lossy(prolog);
+    let (start, end) = declaration_bounds(&prolog)?;
+    let (value_start, value_end) = encoding_value_bounds(&prolog[start..end])?;
+    Some(prolog[start + value_start..start + value_end].to_string())
+}
+
+fn declare_utf8(text: &str) -> String {
+    let Some((start, end)) = declaration_bounds(text) else {
+        return text.to_string();
+    };
+    let Some((value_start, value_end)) = encoding_value_bounds(&text[start..end]) else {
+        return text.to_string();
+    };
+    format!(
+        "{}UTF-8{}",
+        &text[..start + value_start],
+        &text[start + value_end..]
+    )
+}
+
+fn declaration_bounds(text: &str) -> Option<(usize, usize)> {
+    let start = text.len() - text.trim_start().len();
+    if !text[start..].starts_with("<?xml") {
+        return None;
+    }
+    let end = start + text[start..].find("?>")?;
+    Some((start, end))
+}
+
+fn encoding_value_bounds(declaration: &str) -> Option<(usize, usize)> {
+    let attribute = declaration.find("encoding")?;
+    let after_name = attribute + "encoding".len();
+    let equals = after_name + declaration[after_name..].find('=')?;
+    let quote_offset = declaration[equals + 1..].find(['"', '\''])?;
+    let value_start = equals + 1 + quote_offset + 1;
+    let quote = declaration[value_start - 1..].chars().next()?;
+    let value_end = value_start + declaration[value_start..].find(quote)?;
+    Some((value_start, value_end))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use super::{charset_parameter, to_utf8};
+
+    fn decoded(content: &[u8], charset: Option<&str>) -> String {
+        String::from_utf8(to_utf8(content, charset).into_owned()).unwrap()
+    }
+
+    #[test]
+    fn transcodes_declared_legacy_encodings() {
+        let latin1 = b"<?xml version=\"1.0\" encoding=\"ISO-8859-1\"?><title>Caf\xe9</title>";
+        assert_eq!(
+            decoded(latin1, None),
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?><title>Café</title>"
+        );
+
+        let quotes = b"<title>\x93Hello\x94</title>";
+        assert_eq!(
+            decoded(quotes, Some("windows-1252")),
+            "<title>\u{201c}Hello\u{201d}</title>"
+        );
+    }
+
+    #[test]
+    fn prefers_the_bytes_over_lying_declarations() {
+        let utf8_declared_latin1 =
+            "<?xml version='1.0' encoding='iso-8859-1'?><title>Café</title>".as_bytes();
+        assert_eq!(
+            decoded(utf8_declared_latin1, None),
+            "<?xml version='1.0' encoding='UTF-8'?><title>Café</title>"
+        );
+
+        let latin1_declared_utf8 = b"<?xml version=\"1.0\" encoding=\"utf-8\"?><t>Caf\xe9</t>";
+        assert_eq!(
+            decoded(latin1_declared_utf8, None),
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?><t>Café</t>"
+        );
+
+        let header_says_utf8 = b"<t>na\xefve</t>";
+        assert_eq!(decoded(header_says_utf8, Some("utf-8")), "<t>naïve</t>");
+    }
+
+    #[test]
+    fn honors_byte_order_marks_and_leaves_utf8_untouched() {
+        let mut utf16 = vec![0xff, 0xfe];
+        for unit in "<?xml version=\"1.0\" encoding=\"UTF-16\"?><t>é</t>".encode_utf16() {
+            utf16.extend_from_slice(&unit.to_le_bytes());
+        }
+        assert_eq!(
+            decoded(&utf16, Some("iso-8859-1")),
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?><t>é</t>"
+        );
+
+        let utf8 = "<?xml version=\"1.0\"?><t>é</t>".as_bytes();
+        assert!(matches!(to_utf8(utf8, None), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn reads_the_charset_parameter() {
+        assert_eq!(
+            charset_parameter("application/rss+xml; Charset=\"ISO-8859-1\""),
+            Some("ISO-8859-1")
+        );
+        assert_eq!(charset_parameter("text/xml"), None);
+    }
+}
@@ -1,4 +1,7 @@
-#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ContentKind {
     #[default]
     Text,
@@ -11,17 +14,18 @@ impl ContentKind {
     }
 }
 
-#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
 pub struct EntryContent {
     pub value: String,
     pub kind: ContentKind,
 }
 
-#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
 pub struct Entry {
     pub id: Option<String>,
     pub title: String,
     pub link: Option<String>,
+    pub author: Option<String>,
     pub summary: Option<EntryContent>,
     pub content: Option<EntryContent>,
     pub published: Option<String>,
@@ -31,10 +35,20 @@ impl Entry {
     pub fn body(&self) -> Option<&EntryContent> {
         self.content.as_ref().or(self.summary.as_ref())
     }
+
+    /// Identifies the entry across fetches: its id, else its link, else its title.
+    pub fn key(&self) -> &str {
+        self.id
+            .as_deref()
+            .or(self.link.as_deref())
+            .unwrap_or(&self.title)
+    }
 }
 
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
 pub struct FeedDocument {
     pub title: String,
     pub entries: Vec<Entry>,
+    /// Set when the document was malformed and only some of it was salvaged.
+    pub recovered: bool,
 }
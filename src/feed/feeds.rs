@@ -4,6 +4,8 @@ use serde::{Deserialize, Serialize};
 
 use crate::app::AppResult;
 
+const FEEDS_FILE: &str = "feeds.json";
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Feed {
     pub title: String,
@@ -14,13 +16,12 @@ pub trait FeedsManager {
     fn persist(&self) -> AppResult<()>;
     fn add_feed(&mut self, title: String, url: String);
     fn remove_feed(&mut self, title: &str);
+    fn move_feed(&mut self, from: &str, to: String);
 }
 
 impl FeedsManager for Vec<Feed> {
     fn persist(&self) -> AppResult<()> {
-        let feeds_json = serde_json::to_string_pretty(self)?;
-        fs::write("feeds.json", format!("{feeds_json}\n"))?;
-        Ok(())
+        save(self, Path::new(FEEDS_FILE))
     }
 
     fn add_feed(&mut self, title: String, url: String) {
@@ -30,10 +31,20 @@ impl FeedsManager for Vec<Feed> {
     fn remove_feed(&mut self, title: &str) {
         self.retain(|feed| feed.title != title);
     }
+
+    fn move_feed(&mut self, from: &str, to: String) {
+        if let Some(feed) = self.iter_mut().find(|feed| feed.url == from) {
+            feed.url = to;
+        }
+    }
 }
 
 pub fn load_feeds() -> AppResult<Vec<Feed>> {
-    let path = Path::new("feeds.json");
+    load_feeds_from(FEEDS_FILE)
+}
+
+pub fn load_feeds_from(path: impl AsRef<Path>) -> AppResult<Vec<Feed>> {
+    let path = path.as_ref();
 
     if !path.exists() {
         fs::write(path, "[]\n")?;
@@ -48,3 +59,27 @@ pub fn load_feeds() -> AppResult<Vec<Feed>> {
 
     Ok(serde_json::from_str(&contents)?)
 }
+
+/// Applies `change` to feeds.json as it is now and saves it, so feeds the
+/// daemon or a sync added since the caller loaded its copy are kept. Returns
+/// the saved list.
+pub fn update_feeds(change: impl FnOnce(&mut Vec<Feed>)) -> AppResult<Vec<Feed>> {
+    update_feeds_at(FEEDS_FILE, change)
+}
+
+pub fn update_feeds_at(
+    path: impl AsRef<Path>,
+    change: impl FnOnce(&mut Vec<Feed>),
+) -> AppResult<Vec<Feed>> {
+    let path = path.as_ref();
+    let mut feeds = load_feeds_from(path)?;
+    change(&mut feeds);
+    save(&feeds, path)?;
+    Ok(feeds)
+}
+
+fn save(feeds: &[Feed], path: &Path) -> AppResult<()> {
+    let feeds_json = serde_json::to_string_pretty(feeds)?;
+    fs::write(path, format!("{feeds_json}\n"))?;
+    Ok(())
+}
@@ -1,6 +1,28 @@
-use std::{error::Error, fmt, time::Duration};
+use std::{error::Error, fmt, io, path::Path, time::Duration};
 
-use reqwest::{Client, StatusCode, Url};
+use chrono::{DateTime, Utc};
+use futures::{future::BoxFuture, StreamExt};
+use reqwest::{
+    header::{
+        HeaderMap, HeaderName, HeaderValue, CACHE_CONTROL, CONTENT_TYPE, DATE, EXPIRES, LOCATION,
+        RETRY_AFTER,
+    },
+    redirect::Policy,
+    RequestBuilder, Response, StatusCode, Url,
+};
+use tokio::io::AsyncReadExt;
+
+use crate::config::{Auth, Config, FeedSettings, NetworkSettings};
+use crate::feed::{
+    command::{run_command_limited, COMMAND_TIMEOUT},
+    encoding::{charset_parameter, to_utf8},
+    source::FeedSource,
+};
+use crate::http::client_builder;
+
+const MAX_REDIRECTS: usize = 10;
+const RETRY_BASE_DELAY: Duration = Duration::from_secs(1);
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(30);
 
 #[derive(Debug)]
 pub enum FetchError {
@@ -8,6 +30,17 @@ pub enum FetchError {
     UnsupportedScheme,
     Request(reqwest::Error),
     HttpStatus(StatusCode),
+    Gone,
+    TooManyRedirects,
+    TooLarge { limit: u64 },
+    UnsupportedContentType(String),
+    WebPage,
+    File(io::Error),
+    Command(io::Error),
+    CommandFailed { status: Option<i32>, stderr: String },
+    CommandTimedOut(Duration),
+    Credentials(String),
+    Network(String),
     EmptyResponse,
 }
 
@@ -15,9 +48,41 @@ impl fmt::Display for FetchError {
     fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::InvalidUrl => formatter.write_str("invalid feed URL"),
-            Self::UnsupportedScheme => formatter.write_str("feed URL must use HTTP or HTTPS"),
+            Self::UnsupportedScheme => {
+                formatter.write_str("feed URL must use HTTP, HTTPS or a file path")
+            }
             Self::Request(_) => formatter.write_str("feed request failed"),
             Self::HttpStatus(status) => write!(formatter, "feed returned HTTP {status}"),
+            Self::Gone => formatter.write_str("feed is gone (HTTP 410)"),
+            Self::TooManyRedirects => formatter.write_str("feed redirected too many times"),
+            Self::TooLarge { limit } => write!(
+                formatter,
+                "feed is larger than the {} KiB limit",
+                limit / 1024
+            ),
+            Self::UnsupportedContentType(content_type) => {
+                write!(formatter, "URL returned {content_type}, not a feed")
+            }
+            Self::WebPage => formatter.write_str("this is a web page, not a feed"),
+            Self::File(error) => write!(formatter, "could not read feed file: {error}"),
+            Self::Command(error) => write!(formatter, "could not run feed command: {error}"),
+            Self::CommandFailed { status, stderr } => {
+                match status {
+                    Some(code) => write!(formatter, "feed command exited with status {code}")?,
+                    None => formatter.write_str("feed command was terminated")?,
+                }
+                if !stderr.is_empty() {
+                    write!(formatter, ": {stderr}")?;
+                }
+                Ok(())
+            }
+            Self::CommandTimedOut(timeout) => write!(
+                formatter,
+                "feed command timed out after {}s",
+                timeout.as_secs()
+            ),
+            Self::Credentials(message) => write!(formatter, "feed credentials: {message}"),
+            Self::Network(message) => write!(formatter, "network settings: {message}"),
             Self::EmptyResponse => formatter.write_str("feed returned an empty response"),
         }
     }
@@ -27,37 +92,657 @@ impl Error for FetchError {
     fn source(&self) -> Option<&(dyn Error + 'static)> {
         match self {
             Self::Request(error) => Some(error),
+            Self::File(error) | Self::Command(error) => Some(error),
             _ => None,
         }
     }
 }
 
+impl FetchError {
+    /// Whether the message names a cause the user can fix locally, such as a
+    /// failing command or bad credentials, rather than a generic failure.
+    pub fn is_actionable(&self) -> bool {
+        matches!(
+            self,
+            Self::File(_)
+                | Self::Command(_)
+                | Self::CommandFailed { .. }
+                | Self::CommandTimedOut(_)
+                | Self::Credentials(_)
+                | Self::Network(_)
+                | Self::TooLarge { .. }
+                | Self::UnsupportedContentType(_)
+                | Self::WebPage
+        )
+    }
+}
+
 impl From<reqwest::Error> for FetchError {
     fn from(error: reqwest::Error) -> Self {
         Self::Request(error)
     }
 }
 
-pub async fn fetch_content(url: &str) -> Result<Vec<u8>, FetchError> {
-    let url = Url::parse(url).map_err(|_| FetchError::InvalidUrl)?;
-    if !matches!(url.scheme(), "http" | "https") {
-        return Err(FetchError::UnsupportedScheme);
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct FetchedFeed {
+    pub content: Vec<u8>,
+    /// The new URL when every redirect on the way was permanent (301/308).
+    pub moved_to: Option<String>,
+    /// How long the server says the response stays fresh.
+    pub cache_lifetime: Option<Duration>,
+}
+
+impl From<Vec<u8>> for FetchedFeed {
+    fn from(content: Vec<u8>) -> Self {
+        Self {
+            content,
+            moved_to: None,
+            cache_lifetime: None,
+        }
+    }
+}
+
+pub async fn fetch_content(source: &str, config: &Config) -> Result<FetchedFeed, FetchError> {
+    let options = FetchOptions {
+        network: config.network.clone(),
+        feed: config.feed(source),
+    };
+    fetch_source(FeedSource::parse(source)?, options).await
+}
+
+struct FetchOptions {
+    network: NetworkSettings,
+    feed: FeedSettings,
+}
+
+fn fetch_source(
+    source: FeedSource,
+    options: FetchOptions,
+) -> BoxFuture<'static, Result<FetchedFeed, FetchError>> {
+    Box::pin(async move {
+        match source {
+            FeedSource::Http(url) => fetch_http(url, &options).await,
+            FeedSource::File(path) => read_file(&path, options.network.max_feed_size)
+                .await
+                .map(FetchedFeed::from),
+            FeedSource::Exec(command) => {
+                let limit = options.network.max_feed_size;
+                non_empty(run_command_limited(&command, None, COMMAND_TIMEOUT, limit).await?)
+                    .map(FetchedFeed::from)
+            }
+            FeedSource::Filter { command, source } => {
+                let limit = options.network.max_feed_size;
+                let input = fetch_source(*source, options).await?;
+                let output =
+                    run_command_limited(&command, Some(&input.content), COMMAND_TIMEOUT, limit)
+                        .await?;
+                non_empty(output).map(FetchedFeed::from)
+            }
+        }
+    })
+}
+
+/// Reads a feed piped to stdin, up to `limit` bytes.
+pub async fn read_stdin(limit: u64) -> Result<Vec<u8>, FetchError> {
+    let mut content = Vec::new();
+    tokio::io::stdin()
+        .take(limit.saturating_add(1))
+        .read_to_end(&mut content)
+        .await
+        .map_err(FetchError::File)?;
+    if content.len() as u64 > limit {
+        return Err(FetchError::TooLarge { limit });
     }
+    non_empty(content)
+}
 
-    let client = Client::builder()
+async fn fetch_http(url: Url, options: &FetchOptions) -> Result<FetchedFeed, FetchError> {
+    let client = client_builder(&options.network, &url, options.feed.proxy.as_deref())
+        .map_err(FetchError::Network)?
+        .redirect(Policy::none())
         .timeout(Duration::from_secs(15))
-        .user_agent(concat!("fead/", env!("CARGO_PKG_VERSION")))
         .build()?;
-    let response = client.get(url).send().await?;
 
-    if !response.status().is_success() {
-        return Err(FetchError::HttpStatus(response.status()));
+    let mut current = url.clone();
+    let mut redirects = 0;
+    let mut permanent = true;
+    let mut attempt = 0;
+    let response = loop {
+        let mut request = client.get(current.clone());
+        if current.origin() == url.origin() {
+            request = authorize(request, &options.feed).await?;
+        }
+        let response = match request.send().await {
+            Ok(response) => response,
+            Err(error) if attempt < options.network.max_retries && is_transient(&error) => {
+                tokio::time::sleep(backoff(attempt)).await;
+                attempt += 1;
+                continue;
+            }
+            Err(error) => return Err(error.into()),
+        };
+        let status = response.status();
+
+        if let Some(location) = redirect_location(&response) {
+            redirects += 1;
+            if redirects > MAX_REDIRECTS {
+                return Err(FetchError::TooManyRedirects);
+            }
+            permanent &= matches!(
+                status,
+                StatusCode::MOVED_PERMANENTLY | StatusCode::PERMANENT_REDIRECT
+            );
+            current = current.join(location).map_err(|_| FetchError::InvalidUrl)?;
+            continue;
+        }
+        if status.is_success() {
+            break response;
+        }
+        if status == StatusCode::GONE {
+            return Err(FetchError::Gone);
+        }
+        if attempt >= options.network.max_retries || !is_retryable(status) {
+            return Err(FetchError::HttpStatus(status));
+        }
+        let Some(delay) = retry_delay(response.headers(), attempt) else {
+            return Err(FetchError::HttpStatus(status));
+        };
+        tokio::time::sleep(delay).await;
+        attempt += 1;
+    };
+
+    let content_type = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    if let Some(content_type) = content_type
+        .as_deref()
+        .filter(|content_type| !is_feed_content_type(content_type))
+    {
+        return Err(FetchError::UnsupportedContentType(content_type.to_string()));
     }
 
-    let content = response.bytes().await?;
+    let lifetime = cache_lifetime(response.headers());
+    let content = read_limited(response, options.network.max_feed_size).await?;
+    let charset = content_type.as_deref().and_then(charset_parameter);
+    let content = non_empty(to_utf8(&content, charset).into_owned())?;
+    if is_web_page(&content) {
+        return Err(FetchError::WebPage);
+    }
+    Ok(FetchedFeed {
+        content,
+        moved_to: (redirects > 0 && permanent).then(|| current.to_string()),
+        cache_lifetime: lifetime,
+    })
+}
+
+/// Reads the (already decompressed) body, stopping as soon as it exceeds
+/// `limit` so an oversized response never has to fit in memory.
+async fn read_limited(response: Response, limit: u64) -> Result<Vec<u8>, FetchError> {
+    if response
+        .content_length()
+        .is_some_and(|length| length > limit)
+    {
+        return Err(FetchError::TooLarge { limit });
+    }
+
+    let mut content = Vec::new();
+    let mut stream = response.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk?;
+        if (content.len() + chunk.len()) as u64 > limit {
+            return Err(FetchError::TooLarge { limit });
+        }
+        content.extend_from_slice(&chunk);
+    }
+    Ok(content)
+}
+
+/// Rejects media types that can never hold a feed. Anything textual or
+/// generic is let through, since many servers mislabel feeds.
+fn is_feed_content_type(content_type: &str) -> bool {
+    let essence = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    let binary = ["image/", "audio/", "video/", "font/", "model/"]
+        .iter()
+        .any(|prefix| essence.starts_with(prefix));
+    let archive = matches!(
+        essence.as_str(),
+        "application/pdf" | "application/zip" | "application/gzip" | "application/x-tar"
+    );
+    !(binary || archive)
+}
+
+/// Whether the document opens as HTML rather than XML. Servers label feeds
+/// `text/html` often enough that the content type alone cannot tell.
+fn is_web_page(content: &[u8]) -> bool {
+    let mut rest = content.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(content);
+    loop {
+        rest = rest.trim_ascii_start();
+        let skipped = if rest.starts_with(b"<!--") {
+            rest.windows(3)
+                .position(|window| window == b"-->")
+                .map(|end| end + 3)
+        } else if rest.starts_with(b"<?") {
+            rest.windows(2)
+                .position(|window| window == b"?>")
+                .map(|end| end + 2)
+        } else {
+            break;
+        };
+        match skipped {
+            Some(end) => rest = &rest[end..],
+            None => return false,
+        }
+    }
+    let start = rest[..rest.len().min(14)].to_ascii_lowercase();
+    start.starts_with(b"<!doctype html") || start.starts_with(b"<html")
+}
+
+fn redirect_location(response: &Response) -> Option<&str> {
+    if !response.status().is_redirection() {
+        return None;
+    }
+    response.headers().get(LOCATION)?.to_str().ok()
+}
+
+fn is_retryable(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::REQUEST_TIMEOUT
+            | StatusCode::TOO_MANY_REQUESTS
+            | StatusCode::INTERNAL_SERVER_ERROR
+            | StatusCode::BAD_GATEWAY
+            | StatusCode::SERVICE_UNAVAILABLE
+            | StatusCode::GATEWAY_TIMEOUT
+    )
+}
+
+/// Refused or reset connections and timeouts, which usually pass.
+fn is_transient(error: &reqwest::Error) -> bool {
+    error.is_connect() || error.is_timeout() || error.is_request()
+}
+
+/// Honors `Retry-After` (seconds or an HTTP date) and otherwise backs off
+/// exponentially. Returns `None` when the server asks for a longer wait than
+/// is reasonable while the user is looking at the screen.
+fn retry_delay(headers: &HeaderMap, attempt: u32) -> Option<Duration> {
+    let requested = headers
+        .get(RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(parse_retry_after);
+    let delay = requested.unwrap_or_else(|| backoff(attempt));
+    (delay <= MAX_RETRY_DELAY).then_some(delay)
+}
+
+fn backoff(attempt: u32) -> Duration {
+    RETRY_BASE_DELAY
+        .saturating_mul(2u32.saturating_pow(attempt))
+        .min(MAX_RETRY_DELAY)
+}
+
+/// Freshness lifetime from `Cache-Control: max-age`, or `Expires` relative to
+/// the response `Date`. `no-store` and `no-cache` give no lifetime at all.
+fn cache_lifetime(headers: &HeaderMap) -> Option<Duration> {
+    let header = |name| {
+        headers
+            .get(name)
+            .and_then(|value: &HeaderValue| value.to_str().ok())
+    };
+    if let Some(cache_control) = header(CACHE_CONTROL) {
+        let mut max_age = None;
+        for directive in cache_control.split(',').map(str::trim) {
+            if directive.eq_ignore_ascii_case("no-store")
+                || directive.eq_ignore_ascii_case("no-cache")
+            {
+                return None;
+            }
+            if let Some((name, value)) = directive.split_once('=') {
+                if name.trim().eq_ignore_ascii_case("max-age") {
+                    max_age = value
+                        .trim_matches('"')
+                        .parse()
+                        .ok()
+                        .map(Duration::from_secs);
+                }
+            }
+        }
+        if max_age.is_some() {
+            return max_age;
+        }
+    }
+
+    let date = |value: &str| DateTime::parse_from_rfc2822(value.trim()).ok();
+    let expires = date(header(EXPIRES)?)?;
+    let now = header(DATE)
+        .and_then(date)
+        .map_or_else(Utc::now, |now| now.with_timezone(&Utc));
+    (expires.with_timezone(&Utc) - now).to_std().ok()
+}
+
+fn parse_retry_after(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    Some(
+        (date.with_timezone(&Utc) - Utc::now())
+            .to_std()
+            .unwrap_or_default(),
+    )
+}
+
+async fn authorize(
+    mut request: RequestBuilder,
+    settings: &FeedSettings,
+) -> Result<RequestBuilder, FetchError> {
+    match &settings.auth {
+        Some(Auth::Basic { username, password }) => {
+            let password = password.resolve().await.map_err(FetchError::Credentials)?;
+            request = request.basic_auth(username, Some(password));
+        }
+        Some(Auth::Bearer { token }) => {
+            let token = token.resolve().await.map_err(FetchError::Credentials)?;
+            request = request.bearer_auth(token);
+        }
+        None => {}
+    }
+
+    for (name, value) in &settings.headers {
+        let name = HeaderName::from_bytes(name.as_bytes())
+            .map_err(|_| FetchError::Credentials(format!("invalid header name {name}")))?;
+        let value = value.resolve().await.map_err(FetchError::Credentials)?;
+        let mut value = HeaderValue::from_str(&value)
+            .map_err(|_| FetchError::Credentials(format!("invalid value for header {name}")))?;
+        value.set_sensitive(true);
+        request = request.header(name, value);
+    }
+    Ok(request)
+}
+
+async fn read_file(path: &Path, limit: u64) -> Result<Vec<u8>, FetchError> {
+    let metadata = tokio::fs::metadata(path).await.map_err(FetchError::File)?;
+    if metadata.len() > limit {
+        return Err(FetchError::TooLarge { limit });
+    }
+    let content = tokio::fs::read(path).await.map_err(FetchError::File)?;
+    non_empty(content)
+}
+
+fn non_empty(content: Vec<u8>) -> Result<Vec<u8>, FetchError> {
     if content.is_empty() {
         return Err(FetchError::EmptyResponse);
     }
+    Ok(content)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::io::Write;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use flate2::{write::GzEncoder, Compression};
+    use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+
+    use crate::config::{Auth, Config, FeedSettings, Secret};
+    use crate::test_support::{MockResponse, MockServer};
+
+    use super::{cache_lifetime, fetch_content, parse_retry_after, FetchError};
+
+    #[tokio::test]
+    async fn reads_feeds_from_paths_and_file_urls() {
+        let path = std::env::temp_dir().join(format!("fead-fetch-{}.xml", std::process::id()));
+        std::fs::write(&path, "<rss/>").unwrap();
+
+        let by_path = fetch_content(path.to_str().unwrap(), &Config::default())
+            .await
+            .unwrap();
+        let by_url = fetch_content(&format!("file://{}", path.display()), &Config::default())
+            .await
+            .unwrap();
+        std::fs::write(&path, "").unwrap();
+        let empty = fetch_content(path.to_str().unwrap(), &Config::default()).await;
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(by_path.content, b"<rss/>");
+        assert_eq!(by_url.content, b"<rss/>");
+        assert!(matches!(empty, Err(FetchError::EmptyResponse)));
+        assert!(matches!(
+            fetch_content(path.to_str().unwrap(), &Config::default()).await,
+            Err(FetchError::File(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn sends_configured_credentials_and_headers() {
+        let server = MockServer::start(|_| MockResponse::ok("<rss/>")).await;
+        let url = server.url("/private.xml");
+        let mut config = Config::default();
+        config.feeds.insert(
+            url.clone(),
+            FeedSettings {
+                auth: Some(Auth::Bearer {
+                    token: Secret::Command("echo s3cret".into()),
+                }),
+                headers: BTreeMap::from([(
+                    "Cookie".to_string(),
+                    Secret::Value("session=abc".into()),
+                )]),
+                ..FeedSettings::default()
+            },
+        );
+
+        fetch_content(&url, &config).await.unwrap();
+        fetch_content(&server.url("/public.xml"), &config)
+            .await
+            .unwrap();
+
+        let requests = server.requests();
+        let private = requests.iter().find(|r| r.path == "/private.xml").unwrap();
+        let public = requests.iter().find(|r| r.path == "/public.xml").unwrap();
+        assert_eq!(private.header("authorization"), Some("Bearer s3cret"));
+        assert_eq!(private.header("cookie"), Some("session=abc"));
+        assert_eq!(public.header("authorization"), None);
+    }
+
+    #[tokio::test]
+    async fn retries_retryable_statuses_after_the_requested_delay() {
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&attempts);
+        let server = MockServer::start(move |_| match counter.fetch_add(1, Ordering::SeqCst) {
+            0 => MockResponse::status(503).header("Retry-After", "0"),
+            _ => MockResponse::ok("<rss/>"),
+        })
+        .await;
+
+        let content = fetch_content(&server.url("/flaky.xml"), &Config::default())
+            .await
+            .unwrap();
+
+        assert_eq!(content.content, b"<rss/>");
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+    }
 
-    Ok(content.to_vec())
+    #[tokio::test]
+    async fn retries_when_the_connection_drops() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            // The first connection is closed before any response.
+            drop(listener.accept().await.unwrap());
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = [0; 1024];
+            let _ = stream.read(&mut request).await;
+            let response =
+                "HTTP/1.1 200 OK\r\nContent-Length: 6\r\nConnection: close\r\n\r\n<rss/>";
+            stream.write_all(response.as_bytes()).await.unwrap();
+        });
+
+        let content = fetch_content(&format!("http://{address}/feed.xml"), &Config::default())
+            .await
+            .unwrap();
+
+        assert_eq!(content.content, b"<rss/>");
+    }
+
+    #[tokio::test]
+    async fn gives_up_on_permanent_statuses_and_long_retry_after() {
+        let server = MockServer::start(|request| match request.path.as_str() {
+            "/missing.xml" => MockResponse::status(404),
+            _ => MockResponse::status(429).header("Retry-After", "3600"),
+        })
+        .await;
+
+        let missing = fetch_content(&server.url("/missing.xml"), &Config::default()).await;
+        let limited = fetch_content(&server.url("/limited.xml"), &Config::default()).await;
+
+        assert!(matches!(missing, Err(FetchError::HttpStatus(status)) if status == 404));
+        assert!(matches!(limited, Err(FetchError::HttpStatus(status)) if status == 429));
+        assert_eq!(server.requests().len(), 2);
+    }
+
+    #[test]
+    fn parses_retry_after_seconds_and_dates() {
+        assert_eq!(parse_retry_after("120"), Some(Duration::from_secs(120)));
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(parse_retry_after("soon"), None);
+    }
+
+    #[test]
+    fn reads_cache_lifetime_from_response_headers() {
+        let headers = |pairs: &[(&'static str, &'static str)]| {
+            pairs
+                .iter()
+                .map(|&(name, value)| {
+                    (
+                        HeaderName::from_static(name),
+                        HeaderValue::from_static(value),
+                    )
+                })
+                .collect::<HeaderMap>()
+        };
+
+        assert_eq!(
+            cache_lifetime(&headers(&[("cache-control", "public, max-age=1800")])),
+            Some(Duration::from_secs(1800))
+        );
+        assert_eq!(
+            cache_lifetime(&headers(&[
+                ("date", "Wed, 21 Oct 2015 07:00:00 GMT"),
+                ("expires", "Wed, 21 Oct 2015 09:00:00 GMT"),
+            ])),
+            Some(Duration::from_secs(7200))
+        );
+        assert_eq!(
+            cache_lifetime(&headers(&[
+                ("cache-control", "no-cache"),
+                ("expires", "Wed, 21 Oct 2099 09:00:00 GMT"),
+            ])),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn reports_where_permanently_redirected_feeds_moved() {
+        let server = MockServer::start(|request| match request.path.as_str() {
+            "/old.xml" => MockResponse::status(301).header("Location", "/new.xml"),
+            "/temporary.xml" => MockResponse::status(302).header("Location", "/old.xml"),
+            "/dead.xml" => MockResponse::status(410),
+            _ => MockResponse::ok("<rss/>"),
+        })
+        .await;
+        let config = Config::default();
+
+        let moved = fetch_content(&server.url("/old.xml"), &config)
+            .await
+            .unwrap();
+        let temporary = fetch_content(&server.url("/temporary.xml"), &config)
+            .await
+            .unwrap();
+        let gone = fetch_content(&server.url("/dead.xml"), &config).await;
+
+        assert_eq!(moved.content, b"<rss/>");
+        assert_eq!(moved.moved_to, Some(server.url("/new.xml")));
+        assert_eq!(temporary.moved_to, None);
+        assert!(matches!(gone, Err(FetchError::Gone)));
+    }
+
+    #[tokio::test]
+    async fn enforces_size_limits_and_rejects_media_and_web_pages() {
+        let server = MockServer::start(|request| match request.path.as_str() {
+            "/huge.xml" => MockResponse::ok(vec![b' '; 64 * 1024]),
+            "/photo.xml" => MockResponse::ok("GIF89a").header("Content-Type", "image/gif"),
+            "/blog" => MockResponse::ok("\n<!DOCTYPE html>\n<html><body>Blog</body></html>")
+                .header("Content-Type", "text/html"),
+            "/mislabelled.xml" => MockResponse::ok("<?xml version=\"1.0\"?><rss/>")
+                .header("Content-Type", "text/html"),
+            _ => MockResponse::ok("<rss/>").header("Content-Type", "text/xml; charset=utf-8"),
+        })
+        .await;
+        let mut config = Config::default();
+        config.network.max_feed_size = 16 * 1024;
+
+        let huge = fetch_content(&server.url("/huge.xml"), &config).await;
+        let photo = fetch_content(&server.url("/photo.xml"), &config).await;
+        let feed = fetch_content(&server.url("/feed.xml"), &config).await;
+        let blog = fetch_content(&server.url("/blog"), &config).await;
+        let mislabelled = fetch_content(&server.url("/mislabelled.xml"), &config).await;
+
+        assert!(matches!(huge, Err(FetchError::TooLarge { limit: 16384 })));
+        assert!(
+            matches!(photo, Err(FetchError::UnsupportedContentType(ref kind)) if kind == "image/gif")
+        );
+        assert_eq!(feed.unwrap().content, b"<rss/>");
+        assert!(matches!(blog, Err(FetchError::WebPage)));
+        assert!(mislabelled.is_ok());
+    }
+
+    #[tokio::test]
+    async fn decompresses_gzip_encoded_feeds() {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(b"<rss>compressed</rss>").unwrap();
+        let compressed = encoder.finish().unwrap();
+        let server = MockServer::start(move |request| {
+            assert!(request
+                .header("accept-encoding")
+                .is_some_and(|accepted| accepted.contains("gzip")));
+            MockResponse::ok(compressed.clone()).header("Content-Encoding", "gzip")
+        })
+        .await;
+
+        let fetched = fetch_content(&server.url("/feed.xml"), &Config::default())
+            .await
+            .unwrap();
+
+        assert_eq!(fetched.content, b"<rss>compressed</rss>");
+    }
+
+    #[tokio::test]
+    async fn transcodes_using_the_content_type_charset() {
+        let server = MockServer::start(|_| {
+            MockResponse::ok(b"<rss>Caf\xe9</rss>".to_vec())
+                .header("Content-Type", "application/rss+xml; charset=ISO-8859-1")
+        })
+        .await;
+
+        let fetched = fetch_content(&server.url("/feed.xml"), &Config::default())
+            .await
+            .unwrap();
+
+        assert_eq!(fetched.content, "<rss>Café</rss>".as_bytes());
+    }
 }
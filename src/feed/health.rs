@@ -0,0 +1,159 @@
+use std::{collections::BTreeMap, fs, io, path::PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::app::AppResult;
+use crate::feed::schedule::RefreshSchedule;
+
+const HEALTH_FILE: &str = "health.json";
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FeedHealth {
+    pub last_success: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+    pub last_error_at: Option<DateTime<Utc>>,
+    pub consecutive_failures: u32,
+    /// Set when the server answered 410 Gone.
+    pub gone: bool,
+    pub schedule: Option<RefreshSchedule>,
+}
+
+impl FeedHealth {
+    pub fn is_failing(&self) -> bool {
+        self.consecutive_failures > 0
+    }
+
+    /// Whether a background refresh is due; unscheduled feeds always are.
+    pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+        !self.gone && self.schedule.is_none_or(|schedule| schedule.is_due(now))
+    }
+}
+
+/// Per-feed fetch outcomes, keyed by feed URL and kept in `health.json`.
+#[derive(Clone, Debug)]
+pub struct HealthStore {
+    path: PathBuf,
+    records: BTreeMap<String, FeedHealth>,
+}
+
+impl Default for HealthStore {
+    fn default() -> Self {
+        Self::in_memory()
+    }
+}
+
+impl HealthStore {
+    pub fn load() -> AppResult<Self> {
+        Self::load_from(HEALTH_FILE)
+    }
+
+    pub fn load_from(path: impl Into<PathBuf>) -> AppResult<Self> {
+        let path = path.into();
+        let records = match fs::read_to_string(&path) {
+            Ok(contents) if contents.trim().is_empty() => BTreeMap::new(),
+            Ok(contents) => serde_json::from_str(&contents)?,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(error) => return Err(error.into()),
+        };
+        Ok(Self { path, records })
+    }
+
+    /// Picks up changes another process wrote to the file.
+    pub fn reload(&mut self) -> AppResult<()> {
+        if !self.path.as_os_str().is_empty() {
+            *self = Self::load_from(self.path.clone())?;
+        }
+        Ok(())
+    }
+
+    /// A store that is never written to disk.
+    pub fn in_memory() -> Self {
+        Self {
+            path: PathBuf::new(),
+            records: BTreeMap::new(),
+        }
+    }
+
+    pub fn get(&self, url: &str) -> Option<&FeedHealth> {
+        self.records.get(url)
+    }
+
+    pub fn record_success(&mut self, url: &str) -> AppResult<()> {
+        let record = self.records.entry(url.to_string()).or_default();
+        record.last_success = Some(Utc::now());
+        record.consecutive_failures = 0;
+        record.gone = false;
+        self.persist()
+    }
+
+    pub fn record_failure(&mut self, url: &str, error: impl Into<String>) -> AppResult<()> {
+        let record = self.records.entry(url.to_string()).or_default();
+        record.last_error = Some(error.into());
+        record.last_error_at = Some(Utc::now());
+        record.consecutive_failures = record.consecutive_failures.saturating_add(1);
+        self.persist()
+    }
+
+    pub fn schedule(&mut self, url: &str, schedule: RefreshSchedule) -> AppResult<()> {
+        self.records.entry(url.to_string()).or_default().schedule = Some(schedule);
+        self.persist()
+    }
+
+    pub fn mark_gone(&mut self, url: &str) -> AppResult<()> {
+        self.records.entry(url.to_string()).or_default().gone = true;
+        self.persist()
+    }
+
+    pub fn rename(&mut self, from: &str, to: &str) -> AppResult<()> {
+        if let Some(record) = self.records.remove(from) {
+            self.records.insert(to.to_string(), record);
+            self.persist()?;
+        }
+        Ok(())
+    }
+
+    pub fn remove(&mut self, url: &str) -> AppResult<()> {
+        if self.records.remove(url).is_some() {
+            self.persist()?;
+        }
+        Ok(())
+    }
+
+    fn persist(&self) -> AppResult<()> {
+        if self.path.as_os_str().is_empty() {
+            return Ok(());
+        }
+        let json = serde_json::to_string_pretty(&self.records)?;
+        fs::write(&self.path, format!("{json}\n"))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HealthStore;
+
+    #[test]
+    fn tracks_consecutive_failures_until_the_next_success() {
+        let path = std::env::temp_dir().join(format!("fead-health-{}.json", std::process::id()));
+        let url = "https://example.com/feed.xml";
+        let mut store = HealthStore::load_from(&path).unwrap();
+
+        store.record_failure(url, "feed returned HTTP 503").unwrap();
+        store.record_failure(url, "feed request failed").unwrap();
+        let reloaded = HealthStore::load_from(&path).unwrap();
+        let record = reloaded.get(url).unwrap();
+        assert_eq!(record.consecutive_failures, 2);
+        assert_eq!(record.last_error.as_deref(), Some("feed request failed"));
+        assert!(record.is_failing());
+
+        store.record_success(url).unwrap();
+        let record = store.get(url).unwrap();
+        assert!(!record.is_failing());
+        assert!(record.last_success.is_some());
+        assert!(record.last_error.is_some());
+        let _ = std::fs::remove_file(path);
+    }
+}
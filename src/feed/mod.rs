@@ -1,4 +1,14 @@
+pub mod cache;
+pub mod command;
+pub mod encoding;
 pub mod entries;
 pub mod feeds;
 pub mod fetch;
+pub mod health;
 pub mod reader;
+pub mod reading;
+pub mod recovery;
+pub mod refresh;
+pub mod schedule;
+pub mod source;
+pub mod store;
@@ -4,16 +4,30 @@ use feed_rs::{
 };
 use markup5ever_rcdom::NodeData;
 
-use crate::feed::entries::{ContentKind, Entry, EntryContent, FeedDocument};
+use crate::feed::{
+    encoding::to_utf8,
+    entries::{ContentKind, Entry, EntryContent, FeedDocument},
+    recovery::recover,
+};
 
+/// Parses a feed, falling back to [`recover`] for malformed documents. The
+/// strict parser's error is returned when nothing can be salvaged.
 pub fn parse_feed(source: &[u8]) -> Result<FeedDocument, ParseFeedError> {
-    parser::parse(source).map(normalize_feed)
+    let source = to_utf8(source, None);
+    let error = match parser::parse(source.as_ref()) {
+        Ok(feed) => return Ok(normalize_feed(feed, false)),
+        Err(error) => error,
+    };
+    recover(&String::from_utf8_lossy(&source))
+        .map(|feed| normalize_feed(feed, true))
+        .ok_or(error)
 }
 
-fn normalize_feed(feed: feed_rs::model::Feed) -> FeedDocument {
+fn normalize_feed(feed: feed_rs::model::Feed, recovered: bool) -> FeedDocument {
     FeedDocument {
         title: feed.title.map(normalize_title).unwrap_or_default(),
         entries: feed.entries.into_iter().map(normalize_entry).collect(),
+        recovered,
     }
 }
 
@@ -32,6 +46,11 @@ fn normalize_entry(entry: ParsedEntry) -> Entry {
         id: (!entry.id.is_empty()).then_some(entry.id),
         title,
         link: select_link(&entry.links),
+        author: entry
+            .authors
+            .into_iter()
+            .map(|person| person.name.trim().to_string())
+            .find(|name| !name.is_empty()),
         summary: entry.summary.and_then(normalize_text),
         content: entry.content.and_then(normalize_content),
         published,
@@ -172,6 +191,7 @@ mod tests {
                 <title type="html">Atom &lt;em&gt;entry&lt;/em&gt;</title>
                 <updated>2025-07-15T09:00:00Z</updated>
                 <published>2025-07-14T18:20:00Z</published>
+                <author><name>Ada Lovelace</name></author>
                 <link rel="self" href="https://example.com/api/7" />
                 <link rel="alternate" href="https://example.com/articles/7" />
                 <summary type="html">&lt;p&gt;Summary&lt;/p&gt;</summary>
@@ -196,6 +216,7 @@ mod tests {
             entry.published.as_deref(),
             Some("2025-07-14T18:20:00+00:00")
         );
+        assert_eq!(entry.author.as_deref(), Some("Ada Lovelace"));
         assert_eq!(entry.summary.as_ref().unwrap().value, "<p>Summary</p>");
         assert_eq!(entry.content.as_ref().unwrap().kind, ContentKind::Html);
         assert!(entry
@@ -253,4 +274,44 @@ mod tests {
         let truncated = b"<rss><channel><title>Broken</title><item>";
         assert!(parse_feed(truncated).is_err());
     }
+
+    #[test]
+    fn recovers_entries_from_malformed_feeds() {
+        let broken = b"<rss version=\"2.0\"><channel><title>Tom & Jerry&nbsp;News</title>\
+            <item><title>Cats &amp; mice</title></item>\
+            <item><title>Cut off";
+
+        let feed = parse_feed(broken).expect("one complete item");
+
+        assert!(feed.recovered);
+        assert_eq!(feed.title, "Tom & Jerry News");
+        assert_eq!(feed.entries.len(), 1);
+        assert_eq!(feed.entries[0].title, "Cats & mice");
+        let valid = b"<rss version=\"2.0\"><channel><title>Fine</title></channel></rss>";
+        assert!(!parse_feed(valid).unwrap().recovered);
+    }
+
+    #[test]
+    fn parses_legacy_and_misdeclared_encodings() {
+        let latin1 = b"<?xml version=\"1.0\" encoding=\"ISO-8859-1\"?>\
+            <rss version=\"2.0\"><channel><title>Caf\xe9</title>\
+            <item><title>\xc9t\xe9</title></item></channel></rss>";
+        let feed = parse_feed(latin1).expect("ISO-8859-1 feed");
+        assert_eq!(feed.title, "Café");
+        assert_eq!(feed.entries[0].title, "Été");
+
+        let claims_latin1 = "<?xml version=\"1.0\" encoding=\"ISO-8859-1\"?>\
+            <rss version=\"2.0\"><channel><title>Café</title>\
+            <item><title>Naïve</title></item></channel></rss>";
+        let feed = parse_feed(claims_latin1.as_bytes()).expect("UTF-8 feed");
+        assert_eq!(feed.title, "Café");
+        assert_eq!(feed.entries[0].title, "Naïve");
+
+        let claims_utf8 = b"<?xml version=\"1.0\" encoding=\"UTF-8\"?>\
+            <rss version=\"2.0\"><channel><title>\x93Quoted\x94</title>\
+            <item><title>Caf\xe9</title></item></channel></rss>";
+        let feed = parse_feed(claims_utf8).expect("Windows-1252 feed");
+        assert_eq!(feed.title, "\u{201c}Quoted\u{201d}");
+        assert_eq!(feed.entries[0].title, "Café");
+    }
 }
@@ -0,0 +1,189 @@
+use std::{collections::BTreeMap, fs, io, path::PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::app::AppResult;
+use crate::feed::entries::Entry;
+
+const READING_FILE: &str = "reading.json";
+/// Articles kept in the recently read list.
+const MAX_RECENT: usize = 50;
+/// Scroll positions kept; the least recently read are forgotten first.
+const MAX_POSITIONS: usize = 1_000;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Position {
+    pub offset: u16,
+    pub read_at: DateTime<Utc>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct RecentEntry {
+    /// The feed the article was read from; `None` for a document opened from
+    /// the command line.
+    pub feed_url: Option<String>,
+    pub entry: Entry,
+    pub read_at: DateTime<Utc>,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+struct ReadingState {
+    /// Keyed by [`position_key`].
+    positions: BTreeMap<String, Position>,
+    /// Most recent first.
+    recent: Vec<RecentEntry>,
+}
+
+/// Where each article was left and which were read last, kept in
+/// `reading.json`.
+#[derive(Clone, Debug)]
+pub struct ReadingStore {
+    path: PathBuf,
+    state: ReadingState,
+}
+
+impl Default for ReadingStore {
+    fn default() -> Self {
+        Self::in_memory()
+    }
+}
+
+impl ReadingStore {
+    pub fn load() -> AppResult<Self> {
+        Self::load_from(READING_FILE)
+    }
+
+    pub fn load_from(path: impl Into<PathBuf>) -> AppResult<Self> {
+        let path = path.into();
+        let state = match fs::read_to_string(&path) {
+            Ok(contents) if contents.trim().is_empty() => ReadingState::default(),
+            Ok(contents) => serde_json::from_str(&contents)?,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => ReadingState::default(),
+            Err(error) => return Err(error.into()),
+        };
+        Ok(Self { path, state })
+    }
+
+    /// A store that is never written to disk.
+    pub fn in_memory() -> Self {
+        Self {
+            path: PathBuf::new(),
+            state: ReadingState::default(),
+        }
+    }
+
+    /// The scroll offset the article of `feed_url` was left at, or 0.
+    pub fn position(&self, feed_url: Option<&str>, entry: &Entry) -> u16 {
+        self.state
+            .positions
+            .get(&position_key(feed_url, entry))
+            .map_or(0, |position| position.offset)
+    }
+
+    pub fn recent(&self) -> &[RecentEntry] {
+        &self.state.recent
+    }
+
+    /// Remembers how far the article was read and moves it to the top of
+    /// the recently read list.
+    pub fn record(&mut self, feed_url: Option<&str>, entry: &Entry, offset: u16) -> AppResult<()> {
+        let read_at = Utc::now();
+        self.state
+            .positions
+            .insert(position_key(feed_url, entry), Position { offset, read_at });
+        if self.state.positions.len() > MAX_POSITIONS {
+            let oldest = self
+                .state
+                .positions
+                .iter()
+                .min_by_key(|(_, position)| position.read_at)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                self.state.positions.remove(&oldest);
+            }
+        }
+
+        self.state.recent.retain(|recent| {
+            recent.entry.key() != entry.key() || recent.feed_url.as_deref() != feed_url
+        });
+        self.state.recent.insert(
+            0,
+            RecentEntry {
+                feed_url: feed_url.map(str::to_string),
+                entry: entry.clone(),
+                read_at,
+            },
+        );
+        self.state.recent.truncate(MAX_RECENT);
+        self.persist()
+    }
+
+    fn persist(&self) -> AppResult<()> {
+        if self.path.as_os_str().is_empty() {
+            return Ok(());
+        }
+        let json = serde_json::to_string_pretty(&self.state)?;
+        let partial = self.path.with_extension("part");
+        fs::write(&partial, format!("{json}\n"))?;
+        fs::rename(partial, &self.path)?;
+        Ok(())
+    }
+}
+
+/// Entry keys are only unique within a feed; servers often number entries
+/// from 1. The unit separator keeps the two parts apart.
+fn position_key(feed_url: Option<&str>, entry: &Entry) -> String {
+    format!("{}\u{1f}{}", feed_url.unwrap_or_default(), entry.key())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::feed::entries::Entry;
+
+    use super::ReadingStore;
+
+    #[test]
+    fn remembers_positions_and_the_most_recent_articles_first() {
+        let path = std::env::temp_dir().join(format!("fead-reading-{}.json", std::process::id()));
+        let entry = |id: &str| Entry {
+            id: Some(id.to_string()),
+            title: id.to_string(),
+            ..Entry::default()
+        };
+        let mut store = ReadingStore::load_from(&path).unwrap();
+
+        store
+            .record(Some("https://example.com/feed.xml"), &entry("a"), 12)
+            .unwrap();
+        store.record(None, &entry("b"), 3).unwrap();
+        store
+            .record(Some("https://example.com/feed.xml"), &entry("a"), 40)
+            .unwrap();
+
+        // Another feed numbering its entries the same way.
+        store
+            .record(Some("https://example.org/feed.xml"), &entry("a"), 7)
+            .unwrap();
+
+        let reloaded = ReadingStore::load_from(&path).unwrap();
+        assert_eq!(
+            reloaded.position(Some("https://example.com/feed.xml"), &entry("a")),
+            40
+        );
+        assert_eq!(
+            reloaded.position(Some("https://example.org/feed.xml"), &entry("a")),
+            7
+        );
+        assert_eq!(reloaded.position(None, &entry("b")), 3);
+        assert_eq!(reloaded.position(None, &entry("c")), 0);
+        let recent: Vec<_> = reloaded
+            .recent()
+            .iter()
+            .map(|recent| recent.entry.title.as_str())
+            .collect();
+        assert_eq!(recent, ["a", "a", "b"]);
+        let _ = std::fs::remove_file(path);
+    }
+}
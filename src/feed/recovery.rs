@@ -0,0 +1,261 @@
+use std::borrow::Cow;
+
+use feed_rs::{model::Feed, parser};
+
+/// Entity references longer than this are treated as stray ampersands.
+const MAX_ENTITY_LENGTH: usize = 10;
+
+const CDATA_START: &str = "<![CDATA[";
+const CDATA_END: &str = "]]>";
+
+/// HTML entities that commonly leak into feeds, which XML does not define.
+const HTML_ENTITIES: &[(&str, char)] = &[
+    ("nbsp", '\u{a0}'),
+    ("copy", '©'),
+    ("reg", '®'),
+    ("trade", '™'),
+    ("hellip", '…'),
+    ("mdash", '—'),
+    ("ndash", '–'),
+    ("lsquo", '‘'),
+    ("rsquo", '’'),
+    ("ldquo", '“'),
+    ("rdquo", '”'),
+    ("laquo", '«'),
+    ("raquo", '»'),
+    ("bull", '•'),
+    ("middot", '·'),
+    ("deg", '°'),
+    ("times", '×'),
+    ("euro", '€'),
+    ("pound", '£'),
+    ("eacute", 'é'),
+    ("egrave", 'è'),
+    ("agrave", 'à'),
+    ("ccedil", 'ç'),
+    ("auml", 'ä'),
+    ("ouml", 'ö'),
+    ("uuml", 'ü'),
+    ("szlig", 'ß'),
+];
+
+/// Salvages what it can from a document the strict parser rejected: stray
+/// ampersands and HTML entities are escaped first, then every complete
+/// `<item>` or `<entry>` is kept and the document is closed after the last
+/// one. Returns `None` when not a single entry survives.
+pub(crate) fn recover(text: &str) -> Option<Feed> {
+    let repaired = repair_entities(text);
+    if let Ok(feed) = parser::parse(repaired.as_bytes()) {
+        return Some(feed);
+    }
+    salvage_entries(&repaired)
+}
+
+fn repair_entities(text: &str) -> Cow<'_, str> {
+    if !text.contains('&') {
+        return Cow::Borrowed(text);
+    }
+
+    let mut repaired = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(index) = rest.find(['&', '<']) {
+        repaired.push_str(&rest[..index]);
+        rest = &rest[index..];
+        if rest.starts_with(CDATA_START) {
+            let end = rest
+                .find(CDATA_END)
+                .map_or(rest.len(), |end| end + CDATA_END.len());
+            repaired.push_str(&rest[..end]);
+            rest = &rest[end..];
+            continue;
+        }
+        if rest.starts_with('<') {
+            repaired.push('<');
+            rest = &rest[1..];
+            continue;
+        }
+
+        rest = &rest[1..];
+        let reference = rest
+            .find(';')
+            .filter(|&end| end <= MAX_ENTITY_LENGTH)
+            .map(|end| &rest[..end]);
+        match reference {
+            Some(name) if is_xml_reference(name) => repaired.push('&'),
+            Some(name) => match html_entity(name) {
+                Some(character) => {
+                    repaired.push_str(&format!("&#{};", u32::from(character)));
+                    rest = &rest[name.len() + 1..];
+                }
+                None => repaired.push_str("&amp;"),
+            },
+            None => repaired.push_str("&amp;"),
+        }
+    }
+    repaired.push_str(rest);
+    Cow::Owned(repaired)
+}
+
+fn is_xml_reference(name: &str) -> bool {
+    if let Some(hex) = name.strip_prefix("#x") {
+        return !hex.is_empty() && hex.chars().all(|c| c.is_ascii_hexdigit());
+    }
+    if let Some(decimal) = name.strip_prefix('#') {
+        return !decimal.is_empty() && decimal.chars().all(|c| c.is_ascii_digit());
+    }
+    matches!(name, "amp" | "lt" | "gt" | "quot" | "apos")
+}
+
+fn html_entity(name: &str) -> Option<char> {
+    HTML_ENTITIES
+        .iter()
+        .find(|(entity, _)| *entity == name)
+        .map(|&(_, character)| character)
+}
+
+fn salvage_entries(text: &str) -> Option<Feed> {
+    let (first, name) = ["item", "entry"]
+        .into_iter()
+        .filter_map(|name| find_start_tag(text, name).map(|index| (index, name)))
+        .min()?;
+    let prefix = &text[..first];
+    let closing: String = open_elements(prefix)
+        .iter()
+        .rev()
+        .map(|element| format!("</{element}>"))
+        .collect();
+    let elements = complete_elements(&text[first..], name);
+    let rebuild = |elements: &[&str]| format!("{prefix}{}{closing}", elements.concat());
+
+    if let Ok(feed) = parser::parse(rebuild(&elements).as_bytes()) {
+        return (!feed.entries.is_empty()).then_some(feed);
+    }
+    // One bad entry spoils the whole document, so keep only those that parse.
+    let parseable: Vec<&str> = elements
+        .into_iter()
+        .filter(|element| {
+            parser::parse(rebuild(&[element]).as_bytes()).is_ok_and(|feed| !feed.entries.is_empty())
+        })
+        .collect();
+    if parseable.is_empty() {
+        return None;
+    }
+    parser::parse(rebuild(&parseable).as_bytes()).ok()
+}
+
+/// Elements still open at the end of `prefix`, outermost first.
+fn open_elements(prefix: &str) -> Vec<&str> {
+    let mut open = Vec::new();
+    let mut rest = prefix;
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start..];
+        let terminator = if rest.starts_with("<!--") {
+            "-->"
+        } else if rest.starts_with(CDATA_START) {
+            CDATA_END
+        } else {
+            ">"
+        };
+        let Some(end) = rest.find(terminator) else {
+            break;
+        };
+        let tag = &rest[1..end];
+        rest = &rest[end + terminator.len()..];
+
+        if let Some(name) = tag.strip_prefix('/') {
+            if let Some(position) = open.iter().rposition(|element| *element == name.trim()) {
+                open.truncate(position);
+            }
+        } else if !tag.starts_with(['?', '!']) && !tag.ends_with('/') {
+            open.push(tag_name(tag));
+        }
+    }
+    open
+}
+
+fn tag_name(tag: &str) -> &str {
+    tag.split(|c: char| c.is_whitespace() || c == '/')
+        .next()
+        .unwrap_or(tag)
+}
+
+fn find_start_tag(text: &str, name: &str) -> Option<usize> {
+    let open = format!("<{name}");
+    text.match_indices(&open)
+        .map(|(index, _)| index)
+        .find(|&index| {
+            text[index + open.len()..]
+                .chars()
+                .next()
+                .is_some_and(|next| next.is_whitespace() || next == '>')
+        })
+}
+
+/// Every `<name>…</name>` element that is closed before the next one starts.
+fn complete_elements<'a>(text: &'a str, name: &str) -> Vec<&'a str> {
+    let close = format!("</{name}>");
+    let mut elements = Vec::new();
+    let mut rest = text;
+    while let Some(start) = find_start_tag(rest, name) {
+        rest = &rest[start..];
+        let Some(end) = rest.find(&close) else {
+            break;
+        };
+        if let Some(next) = find_start_tag(&rest[1..], name).map(|next| next + 1) {
+            if next < end {
+                rest = &rest[next..];
+                continue;
+            }
+        }
+        let end = end + close.len();
+        elements.push(&rest[..end]);
+        rest = &rest[end..];
+    }
+    elements
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{recover, repair_entities};
+
+    #[test]
+    fn escapes_stray_ampersands_and_html_entities() {
+        assert_eq!(
+            repair_entities("Q&A &amp; R&D&nbsp;&bogus; <![CDATA[a & b]]>"),
+            "Q&amp;A &amp; R&amp;D&#160;&amp;bogus; <![CDATA[a & b]]>"
+        );
+    }
+
+    #[test]
+    fn salvages_complete_entries_from_truncated_documents() {
+        let truncated = r#"<?xml version="1.0"?>
+            <feed xmlns="http://www.w3.org/2005/Atom">
+              <title>Cut short</title>
+              <entry><id>1</id><title>First</title></entry>
+              <entry><id>2</id><title>Second</title></entry>
+              <entry><id>3</id><title>Thi"#;
+
+        let feed = recover(truncated).expect("two complete entries");
+
+        let titles: Vec<_> = feed
+            .entries
+            .iter()
+            .map(|entry| entry.title.as_ref().unwrap().content.as_str())
+            .collect();
+        assert_eq!(titles, ["First", "Second"]);
+    }
+
+    #[test]
+    fn drops_entries_that_cannot_be_parsed() {
+        let broken = r#"<rss version="2.0"><channel><title>Mixed</title>
+            <item><title>Good</title></item>
+            <item><title>Bad</b></title></item>
+            <item><title>Also good</title></item>
+            </channel>"#;
+
+        let feed = recover(broken).expect("two good items");
+
+        assert_eq!(feed.entries.len(), 2);
+        assert!(recover("<rss><channel><title>Broken</title><item>").is_none());
+    }
+}
@@ -0,0 +1,153 @@
+use std::fmt;
+use std::time::SystemTime;
+
+use chrono::Utc;
+use feed_rs::parser::ParseFeedError;
+
+use crate::app::AppResult;
+use crate::config::Config;
+use crate::feed::{
+    cache::FeedCache,
+    entries::FeedDocument,
+    fetch::{fetch_content, FetchError},
+    health::HealthStore,
+    reader::parse_feed,
+    schedule::{RefreshHints, RefreshSchedule},
+};
+use crate::websub::{discover_hub, Hub};
+
+#[derive(Debug)]
+pub enum LoadFeedError {
+    Fetch(FetchError),
+    Parse(ParseFeedError),
+    NoEntries,
+}
+
+impl fmt::Display for LoadFeedError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Fetch(error) => formatter.write_str(&describe_fetch_error(error)),
+            Self::Parse(error) => write!(formatter, "feed is not valid RSS or Atom: {error}"),
+            Self::NoEntries => formatter.write_str("feed contains no readable entries"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct LoadedFeed {
+    pub document: FeedDocument,
+    pub offline: Option<OfflineCopy>,
+    pub moved_to: Option<String>,
+    pub hints: RefreshHints,
+    /// A WebSub hub advertised by the feed.
+    pub hub: Option<Hub>,
+}
+
+/// A cached copy served because the network request failed.
+#[derive(Debug)]
+pub struct OfflineCopy {
+    pub fetched_at: SystemTime,
+    pub error: FetchError,
+}
+
+/// Fetches and parses a feed, falling back to the cached copy when the
+/// network is unreachable.
+pub async fn load_feed(url: &str, config: &Config) -> Result<LoadedFeed, LoadFeedError> {
+    let cache = FeedCache::default();
+    let fetched = match fetch_content(url, config).await {
+        Ok(fetched) => fetched,
+        Err(FetchError::Request(error)) => {
+            let Ok(Some(cached)) = cache.load(url) else {
+                return Err(LoadFeedError::Fetch(FetchError::Request(error)));
+            };
+            let document = parse_entries(&cached.content)?;
+            return Ok(LoadedFeed {
+                document,
+                offline: Some(OfflineCopy {
+                    fetched_at: cached.fetched_at,
+                    error: FetchError::Request(error),
+                }),
+                moved_to: None,
+                hints: RefreshHints::default(),
+                hub: None,
+            });
+        }
+        Err(error) => return Err(LoadFeedError::Fetch(error)),
+    };
+
+    let document = parse_entries(&fetched.content)?;
+    let url = fetched.moved_to.as_deref().unwrap_or(url);
+    let _ = cache.store(url, &fetched.content);
+    Ok(LoadedFeed {
+        document,
+        offline: None,
+        hints: RefreshHints {
+            cache_lifetime: fetched.cache_lifetime,
+            ..RefreshHints::from_document(&fetched.content)
+        },
+        hub: discover_hub(&fetched.content, url),
+        moved_to: fetched.moved_to,
+    })
+}
+
+pub fn describe_fetch_error(error: &FetchError) -> String {
+    match error {
+        FetchError::Request(source) => format!("{error}: {source}"),
+        error => error.to_string(),
+    }
+}
+
+/// Records the outcome of loading `url` and schedules its next refresh.
+pub fn record_outcome(
+    health: &mut HealthStore,
+    config: &Config,
+    url: &str,
+    result: &Result<LoadedFeed, LoadFeedError>,
+) -> AppResult<()> {
+    match result {
+        Ok(LoadedFeed {
+            offline: None,
+            hints,
+            ..
+        }) => record_success(health, config, url, hints),
+        Ok(LoadedFeed {
+            offline: Some(copy),
+            ..
+        }) => record_failure(health, config, url, describe_fetch_error(&copy.error)),
+        Err(error) => record_failure(health, config, url, error.to_string()),
+    }
+}
+
+/// Records a successful fetch and schedules the next background refresh.
+pub fn record_success(
+    health: &mut HealthStore,
+    config: &Config,
+    url: &str,
+    hints: &RefreshHints,
+) -> AppResult<()> {
+    health.record_success(url)?;
+    let schedule = RefreshSchedule::plan(hints, &config.feed(url), &config.refresh, Utc::now());
+    health.schedule(url, schedule)
+}
+
+fn record_failure(
+    health: &mut HealthStore,
+    config: &Config,
+    url: &str,
+    error: String,
+) -> AppResult<()> {
+    health.record_failure(url, error)?;
+    let failures = health
+        .get(url)
+        .map_or(1, |health| health.consecutive_failures);
+    let schedule = RefreshSchedule::after_failure(failures, &config.refresh, Utc::now());
+    health.schedule(url, schedule)
+}
+
+fn parse_entries(content: &[u8]) -> Result<FeedDocument, LoadFeedError> {
+    let feed = parse_feed(content).map_err(LoadFeedError::Parse)?;
+    if feed.entries.is_empty() {
+        return Err(LoadFeedError::NoEntries);
+    }
+    Ok(feed)
+}
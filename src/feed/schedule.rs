@@ -0,0 +1,423 @@
+use std::{fmt, time::Duration};
+
+use chrono::{DateTime, Datelike, TimeDelta, Timelike, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+
+use crate::config::{FeedSettings, RefreshSettings};
+
+const HOUR: Duration = Duration::from_secs(3_600);
+/// Longest wait between refreshes, whatever a feed or server asks for.
+const MAX_INTERVAL: Duration = Duration::from_secs(30 * 24 * 3_600);
+
+/// Refresh hints published by a feed and the server that delivered it.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct RefreshHints {
+    /// `<ttl>` or `sy:updatePeriod`/`sy:updateFrequency`, whichever is longer.
+    pub feed_interval: Option<Duration>,
+    /// `Cache-Control: max-age` or `Expires` from the HTTP response.
+    pub cache_lifetime: Option<Duration>,
+    /// RSS `<skipHours>`, in UTC.
+    pub skip_hours: Vec<u32>,
+    /// RSS `<skipDays>`.
+    pub skip_days: Vec<Weekday>,
+}
+
+impl RefreshHints {
+    pub fn from_document(content: &[u8]) -> Self {
+        let text = String::from_utf8_lossy(content);
+        let text = channel_header(&text);
+        let ttl = element_texts(text, "ttl")
+            .first()
+            .and_then(|minutes| minutes.trim().parse::<u64>().ok())
+            .filter(|&minutes| minutes > 0)
+            .map(|minutes| Duration::from_secs(minutes.saturating_mul(60)));
+        let update_period = element_texts(text, "updatePeriod")
+            .first()
+            .and_then(|period| period_length(period.trim()))
+            .map(|period| {
+                let frequency = element_texts(text, "updateFrequency")
+                    .first()
+                    .and_then(|frequency| frequency.trim().parse::<u32>().ok())
+                    .filter(|&frequency| frequency > 0)
+                    .unwrap_or(1);
+                period / frequency
+            });
+
+        Self {
+            feed_interval: ttl.max(update_period),
+            cache_lifetime: None,
+            skip_hours: element_texts(section(text, "skipHours"), "hour")
+                .iter()
+                .filter_map(|hour| hour.trim().parse::<u32>().ok())
+                .filter(|&hour| hour <= 24)
+                .map(|hour| hour % 24)
+                .collect(),
+            skip_days: element_texts(section(text, "skipDays"), "day")
+                .iter()
+                .filter_map(|day| day.trim().parse::<Weekday>().ok())
+                .collect(),
+        }
+    }
+}
+
+/// Where a feed's refresh interval came from.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IntervalSource {
+    Override,
+    Feed,
+    Http,
+    Default,
+    /// Backing off after failed fetches.
+    Backoff,
+}
+
+impl fmt::Display for IntervalSource {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(match self {
+            Self::Override => "config.json",
+            Self::Feed => "feed hint",
+            Self::Http => "HTTP caching",
+            Self::Default => "default",
+            Self::Backoff => "retrying after errors",
+        })
+    }
+}
+
+/// When a feed should next be refreshed in the background.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct RefreshSchedule {
+    pub next: DateTime<Utc>,
+    pub interval_secs: u64,
+    pub source: IntervalSource,
+}
+
+impl RefreshSchedule {
+    /// Schedules the refresh after a successful fetch at `now`. A per-feed
+    /// override wins; otherwise the longest hint is used. Either way it is
+    /// kept between the configured minimum and 30 days.
+    pub fn plan(
+        hints: &RefreshHints,
+        feed: &FeedSettings,
+        settings: &RefreshSettings,
+        now: DateTime<Utc>,
+    ) -> Self {
+        let minutes = |minutes: u64| Duration::from_secs(minutes.saturating_mul(60));
+        let minimum = minutes(settings.min_interval_minutes);
+        let (interval, source) = if let Some(override_minutes) = feed.refresh_minutes {
+            (minutes(override_minutes), IntervalSource::Override)
+        } else {
+            let hinted = match (hints.feed_interval, hints.cache_lifetime) {
+                (Some(feed), Some(http)) if http > feed => Some((http, IntervalSource::Http)),
+                (Some(feed), _) => Some((feed, IntervalSource::Feed)),
+                (None, Some(http)) => Some((http, IntervalSource::Http)),
+                (None, None) => None,
+            };
+            hinted.unwrap_or((minutes(settings.interval_minutes), IntervalSource::Default))
+        };
+        let interval = interval.max(minimum).min(MAX_INTERVAL);
+
+        Self {
+            next: skip_excluded(after(now, interval), hints),
+            interval_secs: interval.as_secs(),
+            source,
+        }
+    }
+
+    /// Retries after the `failures`-th consecutive failure, doubling the
+    /// minimum interval each time up to the default interval.
+    pub fn after_failure(failures: u32, settings: &RefreshSettings, now: DateTime<Utc>) -> Self {
+        let minimum = settings.min_interval_minutes.saturating_mul(60);
+        let maximum = settings.interval_minutes.saturating_mul(60).max(minimum);
+        let exponent = failures.saturating_sub(1).min(16);
+        let interval = minimum
+            .saturating_mul(1 << exponent)
+            .min(maximum)
+            .min(MAX_INTERVAL.as_secs());
+        Self {
+            next: after(now, Duration::from_secs(interval)),
+            interval_secs: interval,
+            source: IntervalSource::Backoff,
+        }
+    }
+
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(self.interval_secs)
+    }
+
+    pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+        self.next <= now
+    }
+}
+
+/// Formats an interval such as "every 2h" or "every 90m".
+pub fn format_interval(interval: Duration) -> String {
+    let minutes = interval.as_secs() / 60;
+    match minutes {
+        0 => format!("every {}s", interval.as_secs()),
+        _ if minutes.is_multiple_of(1_440) => format!("every {}d", minutes / 1_440),
+        _ if minutes.is_multiple_of(60) => format!("every {}h", minutes / 60),
+        _ => format!("every {minutes}m"),
+    }
+}
+
+/// Formats the wait until a refresh, such as "in 45m" or "due now".
+pub fn format_until(wait: Duration) -> String {
+    let seconds = wait.as_secs();
+    match seconds {
+        0 => "due now".to_string(),
+        1..60 => "in under a minute".to_string(),
+        60..3_600 => format!("in {}m", seconds / 60),
+        3_600..86_400 => format!("in {}h", seconds / 3_600),
+        _ => format!("in {}d", seconds / 86_400),
+    }
+}
+
+/// `interval` after `now`, or the end of time rather than overflowing.
+fn after(now: DateTime<Utc>, interval: Duration) -> DateTime<Utc> {
+    TimeDelta::from_std(interval)
+        .ok()
+        .and_then(|interval| now.checked_add_signed(interval))
+        .unwrap_or(DateTime::<Utc>::MAX_UTC)
+}
+
+/// Moves `time` past any hours and days the feed asks readers to skip.
+fn skip_excluded(mut time: DateTime<Utc>, hints: &RefreshHints) -> DateTime<Utc> {
+    let skipped = |time: DateTime<Utc>| {
+        hints.skip_hours.contains(&time.hour()) || hints.skip_days.contains(&time.weekday())
+    };
+    // A feed that skips every hour of the week is ignored rather than obeyed.
+    for _ in 0..24 * 7 {
+        if !skipped(time) {
+            return time;
+        }
+        let start_of_hour = time
+            - TimeDelta::seconds(i64::from(time.minute() * 60 + time.second()))
+            - TimeDelta::nanoseconds(i64::from(time.nanosecond()));
+        time = start_of_hour + HOUR;
+    }
+    time
+}
+
+fn period_length(period: &str) -> Option<Duration> {
+    let hours = match period.to_ascii_lowercase().as_str() {
+        "hourly" => 1,
+        "daily" => 24,
+        "weekly" => 24 * 7,
+        "monthly" => 24 * 30,
+        "yearly" => 24 * 365,
+        _ => return None,
+    };
+    Some(HOUR * hours)
+}
+
+/// Everything between `<name>` and `</name>`, or nothing if absent.
+fn section<'a>(text: &'a str, name: &str) -> &'a str {
+    let Some(start) = text.find(&format!("<{name}>")) else {
+        return "";
+    };
+    let inner = &text[start + name.len() + 2..];
+    inner
+        .find(&format!("</{name}>"))
+        .map_or(inner, |end| &inner[..end])
+}
+
+/// The feed's own metadata: everything before its first item or entry, so
+/// elements inside an entry's content cannot pose as channel hints.
+fn channel_header(text: &str) -> &str {
+    let mut offset = 0;
+    while let Some(start) = text[offset..].find('<') {
+        let tag_start = offset + start;
+        let tag = &text[tag_start + 1..];
+        let name = tag
+            .split(|c: char| c.is_whitespace() || c == '>' || c == '/')
+            .next()
+            .unwrap_or_default();
+        if matches!(name.rsplit(':').next(), Some("item" | "entry")) {
+            return &text[..tag_start];
+        }
+        offset = tag_start + 1;
+    }
+    text
+}
+
+/// Text of every element with the given local name, whatever its prefix.
+fn element_texts<'a>(text: &'a str, local_name: &str) -> Vec<&'a str> {
+    let mut values = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start + 1..];
+        let Some(end) = rest.find('>') else {
+            break;
+        };
+        let tag = &rest[..end];
+        rest = &rest[end + 1..];
+        if tag.starts_with(['/', '?', '!']) || tag.ends_with('/') {
+            continue;
+        }
+        let name = tag.split_whitespace().next().unwrap_or_default();
+        if name.rsplit(':').next() == Some(local_name) {
+            values.push(rest.find("</").map_or(rest, |close| &rest[..close]));
+        }
+    }
+    values
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use chrono::{DateTime, Timelike, Utc, Weekday};
+
+    use crate::config::{FeedSettings, RefreshSettings};
+
+    use super::{format_interval, IntervalSource, RefreshHints, RefreshSchedule};
+
+    fn at(time: &str) -> DateTime<Utc> {
+        time.parse().unwrap()
+    }
+
+    #[test]
+    fn reads_ttl_update_period_and_skip_hints() {
+        let rss = r#"<rss xmlns:sy="http://purl.org/rss/1.0/modules/syndication/"><channel>
+            <ttl>30</ttl>
+            <sy:updatePeriod>daily</sy:updatePeriod>
+            <sy:updateFrequency>4</sy:updateFrequency>
+            <skipHours><hour>0</hour><hour>24</hour><hour>5</hour></skipHours>
+            <skipDays><day>Saturday</day><day>Sunday</day></skipDays>
+        </channel></rss>"#;
+
+        let hints = RefreshHints::from_document(rss.as_bytes());
+
+        assert_eq!(hints.feed_interval, Some(Duration::from_secs(6 * 3_600)));
+        assert_eq!(hints.skip_hours, [0, 0, 5]);
+        assert_eq!(hints.skip_days, [Weekday::Sat, Weekday::Sun]);
+        assert_eq!(
+            RefreshHints::from_document(b"<rss><ttl>0</ttl></rss>"),
+            RefreshHints::default()
+        );
+    }
+
+    #[test]
+    fn ignores_hint_elements_inside_items() {
+        let rss = r#"<rss><channel><title>Example</title>
+            <item><title>Post</title><ttl>600</ttl><hour>3</hour></item>
+            <item><skipDays><day>Monday</day></skipDays></item>
+        </channel></rss>"#;
+        assert_eq!(
+            RefreshHints::from_document(rss.as_bytes()),
+            RefreshHints::default()
+        );
+
+        let atom = r#"<feed xmlns="http://www.w3.org/2005/Atom"><title>Example</title>
+            <entry><content type="xhtml"><ttl>600</ttl></content></entry>
+        </feed>"#;
+        assert_eq!(
+            RefreshHints::from_document(atom.as_bytes()).feed_interval,
+            None
+        );
+    }
+
+    #[test]
+    fn picks_the_override_then_the_longest_hint_above_the_minimum() {
+        let settings = RefreshSettings::default();
+        let now = at("2025-07-15T10:00:00Z");
+        let mut hints = RefreshHints {
+            feed_interval: Some(Duration::from_secs(60)),
+            cache_lifetime: Some(Duration::from_secs(2 * 3_600)),
+            ..RefreshHints::default()
+        };
+
+        let plan = RefreshSchedule::plan(&hints, &FeedSettings::default(), &settings, now);
+        assert_eq!(plan.source, IntervalSource::Http);
+        assert_eq!(plan.next, at("2025-07-15T12:00:00Z"));
+
+        hints.cache_lifetime = None;
+        let plan = RefreshSchedule::plan(&hints, &FeedSettings::default(), &settings, now);
+        assert_eq!(
+            (plan.source, plan.interval_secs),
+            (IntervalSource::Feed, 300)
+        );
+
+        let mut feed = FeedSettings {
+            refresh_minutes: Some(10),
+            ..FeedSettings::default()
+        };
+        let plan = RefreshSchedule::plan(&hints, &feed, &settings, now);
+        assert_eq!(
+            (plan.source, plan.interval_secs),
+            (IntervalSource::Override, 600)
+        );
+        feed.refresh_minutes = Some(0);
+        let plan = RefreshSchedule::plan(&hints, &feed, &settings, now);
+        assert_eq!(plan.interval_secs, 300);
+    }
+
+    #[test]
+    fn caps_huge_hints_instead_of_overflowing() {
+        let hints =
+            RefreshHints::from_document(b"<rss><channel><ttl>999999999999</ttl></channel></rss>");
+        let now = at("2025-07-15T10:00:00Z");
+        let plan = RefreshSchedule::plan(
+            &hints,
+            &FeedSettings::default(),
+            &RefreshSettings::default(),
+            now,
+        );
+        assert_eq!(plan.next, at("2025-08-14T10:00:00Z"));
+
+        let hints = RefreshHints {
+            cache_lifetime: Some(Duration::MAX),
+            ..RefreshHints::default()
+        };
+        let plan = RefreshSchedule::plan(
+            &hints,
+            &FeedSettings::default(),
+            &RefreshSettings::default(),
+            now,
+        );
+        assert_eq!(plan.interval_secs, 30 * 24 * 3_600);
+    }
+
+    #[test]
+    fn skips_excluded_hours_and_days() {
+        let hints = RefreshHints {
+            feed_interval: Some(Duration::from_secs(3_600)),
+            skip_hours: vec![23, 0, 1],
+            skip_days: vec![Weekday::Sat],
+            ..RefreshHints::default()
+        };
+        let friday_evening = at("2025-07-18T22:30:00Z");
+
+        let plan = RefreshSchedule::plan(
+            &hints,
+            &FeedSettings::default(),
+            &RefreshSettings::default(),
+            friday_evening,
+        );
+
+        assert_eq!(plan.next, at("2025-07-20T02:00:00Z"));
+        assert_eq!(plan.next.minute(), 0);
+    }
+
+    #[test]
+    fn backs_off_after_failures_and_formats_intervals() {
+        let settings = RefreshSettings::default();
+        let now = at("2025-07-15T10:00:00Z");
+
+        assert_eq!(
+            RefreshSchedule::after_failure(1, &settings, now).interval_secs,
+            300
+        );
+        assert_eq!(
+            RefreshSchedule::after_failure(3, &settings, now).interval_secs,
+            1_200
+        );
+        assert_eq!(
+            RefreshSchedule::after_failure(30, &settings, now).interval_secs,
+            3_600
+        );
+        assert_eq!(format_interval(Duration::from_secs(5_400)), "every 90m");
+        assert_eq!(format_interval(Duration::from_secs(7_200)), "every 2h");
+    }
+}
@@ -0,0 +1,161 @@
+use std::path::{Path, PathBuf};
+
+use reqwest::Url;
+
+use crate::feed::fetch::FetchError;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum FeedSource {
+    Http(Url),
+    File(PathBuf),
+    Exec(String),
+    Filter {
+        command: String,
+        source: Box<FeedSource>,
+    },
+}
+
+impl FeedSource {
+    pub fn parse(source: &str) -> Result<Self, FetchError> {
+        let source = source.trim();
+        if let Some(command) = source.strip_prefix("exec:") {
+            return non_empty_command(command).map(Self::Exec);
+        }
+        if let Some(filter) = source.strip_prefix("filter:") {
+            let (command, inner) = split_filter(filter).ok_or(FetchError::InvalidUrl)?;
+            return Ok(Self::Filter {
+                command: non_empty_command(command)?,
+                source: Box::new(inner),
+            });
+        }
+        Self::parse_location(source)
+    }
+
+    pub fn command(&self) -> Option<&str> {
+        match self {
+            Self::Exec(command) | Self::Filter { command, .. } => Some(command),
+            Self::Http(_) | Self::File(_) => None,
+        }
+    }
+
+    fn parse_location(source: &str) -> Result<Self, FetchError> {
+        if source.is_empty() {
+            return Err(FetchError::InvalidUrl);
+        }
+        if looks_like_path(source) {
+            return Ok(Self::File(expand_home(source)));
+        }
+
+        let url = Url::parse(source).map_err(|_| FetchError::InvalidUrl)?;
+        match url.scheme() {
+            "http" | "https" => Ok(Self::Http(url)),
+            "file" => url
+                .to_file_path()
+                .map(Self::File)
+                .map_err(|_| FetchError::InvalidUrl),
+            _ => Err(FetchError::UnsupportedScheme),
+        }
+    }
+}
+
+fn non_empty_command(command: &str) -> Result<String, FetchError> {
+    let command = command.trim();
+    if command.is_empty() {
+        return Err(FetchError::InvalidUrl);
+    }
+    Ok(command.to_string())
+}
+
+/// Splits `<command>:<location>` at the first colon whose remainder is a
+/// URL or an explicit path, so commands may contain colons themselves.
+fn split_filter(filter: &str) -> Option<(&str, FeedSource)> {
+    filter
+        .match_indices(':')
+        .map(|(index, _)| (&filter[..index], filter[index + 1..].trim()))
+        .find_map(|(command, location)| {
+            let explicit = location.starts_with(['/', '.', '~']) || location.contains("://");
+            explicit
+                .then(|| FeedSource::parse_location(location).ok())
+                .flatten()
+                .map(|source| (command, source))
+        })
+}
+
+/// Explicit paths, and bare names of files that exist. Other input without a
+/// scheme, such as `example.com/feed`, is a mistyped URL rather than a file.
+fn looks_like_path(source: &str) -> bool {
+    source.starts_with(['/', '.', '~']) || Path::new(source).exists()
+}
+
+fn expand_home(source: &str) -> PathBuf {
+    match source.strip_prefix("~/") {
+        Some(rest) => std::env::var_os("HOME")
+            .map(|home| PathBuf::from(home).join(rest))
+            .unwrap_or_else(|| PathBuf::from(source)),
+        None => PathBuf::from(source),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use reqwest::Url;
+
+    use crate::feed::fetch::FetchError;
+
+    use super::FeedSource;
+
+    #[test]
+    fn distinguishes_urls_file_urls_and_plain_paths() {
+        assert!(matches!(
+            FeedSource::parse("https://example.com/feed.xml"),
+            Ok(FeedSource::Http(_))
+        ));
+        assert_eq!(
+            FeedSource::parse("file:///tmp/feed.xml").unwrap(),
+            FeedSource::File(PathBuf::from("/tmp/feed.xml"))
+        );
+        assert_eq!(
+            FeedSource::parse("./fixtures/feed.xml").unwrap(),
+            FeedSource::File(PathBuf::from("./fixtures/feed.xml"))
+        );
+        assert_eq!(
+            FeedSource::parse("Cargo.toml").unwrap(),
+            FeedSource::File(PathBuf::from("Cargo.toml"))
+        );
+        assert!(matches!(
+            FeedSource::parse("example.com/feed"),
+            Err(FetchError::InvalidUrl)
+        ));
+        assert!(matches!(
+            FeedSource::parse("gopher://example.com/feed"),
+            Err(FetchError::UnsupportedScheme)
+        ));
+    }
+
+    #[test]
+    fn parses_exec_and_filter_commands() {
+        assert_eq!(
+            FeedSource::parse("exec: ./build-feed.sh --all").unwrap(),
+            FeedSource::Exec("./build-feed.sh --all".into())
+        );
+        assert_eq!(
+            FeedSource::parse("filter:sed 's/a:b/c/':https://example.com/feed.xml").unwrap(),
+            FeedSource::Filter {
+                command: "sed 's/a:b/c/'".into(),
+                source: Box::new(FeedSource::Http(
+                    Url::parse("https://example.com/feed.xml").unwrap()
+                )),
+            }
+        );
+        assert_eq!(
+            FeedSource::parse("filter:xsltproc fix.xsl -:./feed.xml")
+                .unwrap()
+                .command(),
+            Some("xsltproc fix.xsl -")
+        );
+        assert!(FeedSource::parse("exec:").is_err());
+        assert!(FeedSource::parse("filter:cat").is_err());
+    }
+}
@@ -0,0 +1,265 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashSet},
+    fs, io,
+    path::PathBuf,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::app::AppResult;
+use crate::feed::entries::{Entry, FeedDocument};
+
+const ENTRIES_FILE: &str = "entries.json";
+/// Older entries beyond this many are dropped from each feed.
+const MAX_ENTRIES_PER_FEED: usize = 500;
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StoredFeed {
+    pub title: String,
+    /// Newest first.
+    pub entries: Vec<Entry>,
+    /// Keys of the entries that have been read.
+    pub read: BTreeSet<String>,
+    /// Keys of the entries that have been starred.
+    pub starred: BTreeSet<String>,
+}
+
+impl StoredFeed {
+    pub fn is_read(&self, entry: &Entry) -> bool {
+        self.read.contains(entry.key())
+    }
+
+    pub fn is_starred(&self, entry: &Entry) -> bool {
+        self.starred.contains(entry.key())
+    }
+
+    pub fn unread_count(&self) -> usize {
+        self.entries
+            .iter()
+            .filter(|entry| !self.is_read(entry))
+            .count()
+    }
+}
+
+/// Entries collected from fetches and pushes, keyed by feed URL and kept in
+/// `entries.json`.
+#[derive(Clone, Debug)]
+pub struct EntryStore {
+    path: PathBuf,
+    feeds: BTreeMap<String, StoredFeed>,
+}
+
+impl Default for EntryStore {
+    fn default() -> Self {
+        Self::in_memory()
+    }
+}
+
+impl EntryStore {
+    pub fn load() -> AppResult<Self> {
+        Self::load_from(ENTRIES_FILE)
+    }
+
+    pub fn load_from(path: impl Into<PathBuf>) -> AppResult<Self> {
+        let path = path.into();
+        let feeds = match fs::read_to_string(&path) {
+            Ok(contents) if contents.trim().is_empty() => BTreeMap::new(),
+            Ok(contents) => serde_json::from_str(&contents)?,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(error) => return Err(error.into()),
+        };
+        Ok(Self { path, feeds })
+    }
+
+    /// Picks up changes another process wrote to the file.
+    pub fn reload(&mut self) -> AppResult<()> {
+        if !self.path.as_os_str().is_empty() {
+            *self = Self::load_from(self.path.clone())?;
+        }
+        Ok(())
+    }
+
+    /// A store that is never written to disk.
+    pub fn in_memory() -> Self {
+        Self {
+            path: PathBuf::new(),
+            feeds: BTreeMap::new(),
+        }
+    }
+
+    pub fn get(&self, url: &str) -> Option<&StoredFeed> {
+        self.feeds.get(url)
+    }
+
+    pub fn entries(&self, url: &str) -> &[Entry] {
+        self.feeds
+            .get(url)
+            .map_or(&[], |feed| feed.entries.as_slice())
+    }
+
+    /// Adds the document's entries to the feed, replacing stored copies of
+    /// the same entries, and returns the ones not seen before.
+    pub fn merge(&mut self, url: &str, document: &FeedDocument) -> AppResult<Vec<Entry>> {
+        let feed = self.feeds.entry(url.to_string()).or_default();
+        if !document.title.is_empty() {
+            feed.title = document.title.clone();
+        }
+
+        let known: HashSet<&str> = feed.entries.iter().map(Entry::key).collect();
+        let added: Vec<Entry> = document
+            .entries
+            .iter()
+            .filter(|entry| !known.contains(entry.key()))
+            .cloned()
+            .collect();
+        let incoming: HashSet<&str> = document.entries.iter().map(Entry::key).collect();
+        let mut entries = document.entries.clone();
+        entries.extend(
+            feed.entries
+                .drain(..)
+                .filter(|entry| !incoming.contains(entry.key())),
+        );
+        entries.truncate(MAX_ENTRIES_PER_FEED);
+        let kept: HashSet<&str> = entries.iter().map(Entry::key).collect();
+        feed.read.retain(|key| kept.contains(key.as_str()));
+        feed.starred.retain(|key| kept.contains(key.as_str()));
+        feed.entries = entries;
+
+        self.persist()?;
+        Ok(added)
+    }
+
+    /// Marks a stored entry as read or unread. Returns `false` when the feed
+    /// has no entry with that key.
+    pub fn mark_read(&mut self, url: &str, key: &str, read: bool) -> AppResult<bool> {
+        let Some(feed) = self.feeds.get_mut(url) else {
+            return Ok(false);
+        };
+        if !feed.entries.iter().any(|entry| entry.key() == key) {
+            return Ok(false);
+        }
+        let changed = if read {
+            feed.read.insert(key.to_string())
+        } else {
+            feed.read.remove(key)
+        };
+        if changed {
+            self.persist()?;
+        }
+        Ok(true)
+    }
+
+    /// Replaces a feed's read and starred keys at once, ignoring keys of
+    /// entries it does not hold.
+    pub fn set_flags(
+        &mut self,
+        url: &str,
+        read: BTreeSet<String>,
+        starred: BTreeSet<String>,
+    ) -> AppResult<()> {
+        let Some(feed) = self.feeds.get_mut(url) else {
+            return Ok(());
+        };
+        let known: HashSet<&str> = feed.entries.iter().map(Entry::key).collect();
+        let read: BTreeSet<String> = read
+            .into_iter()
+            .filter(|key| known.contains(key.as_str()))
+            .collect();
+        let starred: BTreeSet<String> = starred
+            .into_iter()
+            .filter(|key| known.contains(key.as_str()))
+            .collect();
+        if feed.read != read || feed.starred != starred {
+            feed.read = read;
+            feed.starred = starred;
+            self.persist()?;
+        }
+        Ok(())
+    }
+
+    pub fn rename(&mut self, from: &str, to: &str) -> AppResult<()> {
+        if let Some(feed) = self.feeds.remove(from) {
+            self.feeds.insert(to.to_string(), feed);
+            self.persist()?;
+        }
+        Ok(())
+    }
+
+    pub fn remove(&mut self, url: &str) -> AppResult<()> {
+        if self.feeds.remove(url).is_some() {
+            self.persist()?;
+        }
+        Ok(())
+    }
+
+    fn persist(&self) -> AppResult<()> {
+        if self.path.as_os_str().is_empty() {
+            return Ok(());
+        }
+        let json = serde_json::to_string_pretty(&self.feeds)?;
+        let partial = self.path.with_extension("part");
+        fs::write(&partial, format!("{json}\n"))?;
+        fs::rename(partial, &self.path)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::feed::entries::{Entry, FeedDocument};
+
+    use super::EntryStore;
+
+    fn entry(id: &str, title: &str) -> Entry {
+        Entry {
+            id: Some(id.to_string()),
+            title: title.to_string(),
+            ..Entry::default()
+        }
+    }
+
+    #[test]
+    fn merges_new_entries_and_keeps_older_ones() {
+        let path = std::env::temp_dir().join(format!("fead-entries-{}.json", std::process::id()));
+        let url = "https://example.com/feed.xml";
+        let mut store = EntryStore::load_from(&path).unwrap();
+
+        let first = FeedDocument {
+            title: "Example".to_string(),
+            entries: vec![entry("2", "Second"), entry("1", "First")],
+            ..FeedDocument::default()
+        };
+        assert_eq!(store.merge(url, &first).unwrap().len(), 2);
+
+        let second = FeedDocument {
+            entries: vec![entry("3", "Third"), entry("2", "Second, edited")],
+            ..FeedDocument::default()
+        };
+        let added = store.merge(url, &second).unwrap();
+        assert_eq!(added, [entry("3", "Third")]);
+
+        let reloaded = EntryStore::load_from(&path).unwrap();
+        let titles: Vec<_> = reloaded
+            .entries(url)
+            .iter()
+            .map(|entry| entry.title.as_str())
+            .collect();
+        assert_eq!(titles, ["Third", "Second, edited", "First"]);
+        assert_eq!(reloaded.get(url).unwrap().title, "Example");
+
+        assert!(store.mark_read(url, "2", true).unwrap());
+        assert!(!store.mark_read(url, "missing", true).unwrap());
+        let reloaded = EntryStore::load_from(&path).unwrap();
+        let feed = reloaded.get(url).unwrap();
+        assert!(feed.is_read(&feed.entries[1]));
+        assert_eq!(feed.unread_count(), 2);
+
+        // Another process marks an entry read; a reload keeps its write.
+        let mut other = EntryStore::load_from(&path).unwrap();
+        assert!(other.mark_read(url, "3", true).unwrap());
+        store.reload().unwrap();
+        assert_eq!(store.get(url).unwrap().unread_count(), 1);
+        let _ = std::fs::remove_file(path);
+    }
+}
@@ -1,15 +1,16 @@
 use crate::app::{App, AppResult, ConfirmationChoice, ConfirmationKind};
-use crate::tts::TtsModelEvent;
+use crate::tts::{NarrationHandle, TtsModelEvent};
 use crossterm::event::{KeyCode, KeyEvent};
 use tokio::sync::mpsc;
 
-use super::feed_actions::delete_selected_feed;
+use super::feed_actions::{delete_selected_feed, run_trusted_command};
 use super::tts::start_tts_download;
 
-pub(super) fn handle_confirmation(
+pub(super) async fn handle_confirmation(
     key: KeyEvent,
     app: &mut App,
     model_tx: &mpsc::UnboundedSender<TtsModelEvent>,
+    narration: &NarrationHandle,
 ) -> AppResult<()> {
     match key.code {
         KeyCode::Esc => app.confirmation_popup = None,
@@ -28,6 +29,7 @@ pub(super) fn handle_confirmation(
             match popup.kind {
                 ConfirmationKind::DeleteFeed => delete_selected_feed(app)?,
                 ConfirmationKind::DownloadTtsModel => start_tts_download(app, model_tx)?,
+                ConfirmationKind::RunCommand => run_trusted_command(app, narration).await?,
             }
         }
         _ => {}
@@ -0,0 +1,68 @@
+use crate::app::{App, AppResult};
+use crate::export::{save_article, save_epub, Article, ExportError};
+use crate::feed::{entries::Entry, store::EntryStore};
+use crate::screen::Screen;
+
+/// Saves the open article, or the selected one in the entry list, in the
+/// configured format.
+pub(super) fn export_entry(app: &mut App) {
+    let entry = match app.screen {
+        Screen::Article => &app.current_entry,
+        Screen::Feed => match app.entry_list.state.selected() {
+            Some(selected) => &app.entry_list.items[selected],
+            None => return,
+        },
+        Screen::Home => return,
+    };
+    let article = Article {
+        entry,
+        source: source(app),
+    };
+    let settings = &app.config.export;
+    match save_article(article, settings.format, &settings.directory) {
+        Ok(path) => app.show_info(format!("Saved {}.", path.display())),
+        Err(error) => app.show_error(format!("Could not export the article: {error}.")),
+    }
+}
+
+/// Bundles the listed entries that have not been read into an EPUB.
+pub(super) fn export_unread(app: &mut App) -> AppResult<()> {
+    let store = EntryStore::load()?;
+    let stored = app
+        .current_feed
+        .as_ref()
+        .and_then(|feed| store.get(&feed.url));
+    let unread: Vec<&Entry> = app
+        .entry_list
+        .items
+        .iter()
+        .filter(|entry| stored.is_none_or(|feed| !feed.is_read(entry)))
+        .collect();
+    let source = source(app);
+    let articles: Vec<Article<'_>> = unread
+        .into_iter()
+        .map(|entry| Article { entry, source })
+        .collect();
+    let title = if source.is_empty() {
+        "Unread articles".to_string()
+    } else {
+        format!("{source} unread")
+    };
+
+    let count = articles.len();
+    match save_epub(&title, &articles, &app.config.export.directory) {
+        Ok(path) => app.show_info(format!(
+            "Saved {count} unread articles to {}.",
+            path.display()
+        )),
+        Err(ExportError::NothingToExport) => app.show_info("Every article here has been read."),
+        Err(error) => app.show_error(format!("Could not export the articles: {error}.")),
+    }
+    Ok(())
+}
+
+fn source(app: &App) -> &str {
+    app.current_feed
+        .as_ref()
+        .map_or("", |feed| feed.title.as_str())
+}
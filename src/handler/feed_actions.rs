@@ -1,29 +1,30 @@
-use crate::app::{App, AppResult, InputMode};
+use crate::app::{
+    App, AppResult, ConfirmationChoice, ConfirmationKind, ConfirmationPopup, InputMode,
+};
 use crate::feed::{
-    entries::FeedDocument,
-    feeds::FeedsManager,
-    fetch::{fetch_content, FetchError},
-    reader::parse_feed,
+    cache::{format_age, FeedCache},
+    command::{is_trusted, trust},
+    feeds::{update_feeds, Feed, FeedsManager},
+    fetch::FetchError,
+    refresh::{load_feed, record_outcome, record_success, LoadFeedError, LoadedFeed},
+    source::FeedSource,
+    store::EntryStore,
 };
 use crate::screen::Screen;
+use crate::sync::synced_entries;
 use crate::tts::NarrationHandle;
 
 use super::tts::stop_narration;
 
-#[derive(Debug)]
-pub(super) enum LoadFeedError {
-    Fetch(FetchError),
-    Parse,
-    NoEntries,
-}
-
-pub(super) async fn load_feed(url: &str) -> Result<FeedDocument, LoadFeedError> {
-    let content = fetch_content(url).await.map_err(LoadFeedError::Fetch)?;
-    let feed = parse_feed(&content).map_err(|_| LoadFeedError::Parse)?;
-    if feed.entries.is_empty() {
-        return Err(LoadFeedError::NoEntries);
-    }
-    Ok(feed)
+/// The daemon records health too, so health.json is reloaded before each
+/// change rather than overwritten with the copy loaded at startup.
+fn record_health(
+    app: &mut App,
+    url: &str,
+    result: &Result<LoadedFeed, LoadFeedError>,
+) -> AppResult<()> {
+    app.health.reload()?;
+    record_outcome(&mut app.health, &app.config, url, result)
 }
 
 pub(super) async fn add_feed(app: &mut App) -> AppResult<()> {
@@ -36,19 +37,37 @@ pub(super) async fn add_feed(app: &mut App) -> AppResult<()> {
         app.show_error("That feed is already in your list.");
         return Ok(());
     }
+    if let Some(command) = untrusted_command(&url) {
+        confirm_command(app, command);
+        return Ok(());
+    }
 
-    let feed = match load_feed(&url).await {
-        Ok(feed) => feed,
+    let (feed, url, health_error) = match load_feed(&url, &app.config).await {
+        Ok(loaded) => {
+            let url = loaded.moved_to.unwrap_or(url);
+            let health_error = app
+                .health
+                .reload()
+                .and_then(|()| record_success(&mut app.health, &app.config, &url, &loaded.hints))
+                .err();
+            (loaded.document, url, health_error)
+        }
         Err(LoadFeedError::Fetch(FetchError::InvalidUrl | FetchError::UnsupportedScheme)) => {
-            app.show_error("Enter a valid HTTP or HTTPS feed URL.");
+            app.show_error("Enter a valid HTTP or HTTPS feed URL or a file path.");
+            return Ok(());
+        }
+        Err(LoadFeedError::Fetch(error)) if error.is_actionable() => {
+            app.show_error(format!("Could not add the feed: {error}."));
             return Ok(());
         }
         Err(LoadFeedError::Fetch(_)) => {
             app.show_error("The feed request failed. Check the URL and your connection.");
             return Ok(());
         }
-        Err(LoadFeedError::Parse) => {
-            app.show_error("The URL did not return valid RSS or Atom XML.");
+        Err(LoadFeedError::Parse(error)) => {
+            app.show_error(format!(
+                "The URL did not return valid RSS or Atom XML ({error})."
+            ));
             return Ok(());
         }
         Err(LoadFeedError::NoEntries) => {
@@ -62,14 +81,21 @@ pub(super) async fn add_feed(app: &mut App) -> AppResult<()> {
     } else {
         &feed.title
     };
-    app.feed_list
-        .items
-        .add_feed(title.to_string(), url.to_string());
-    app.feed_list.items.persist()?;
-    app.feed_list.state.select_last();
+    app.feed_list.items = update_feeds(|feeds| {
+        if !feeds.iter().any(|feed| feed.url == url) {
+            feeds.add_feed(title.to_string(), url.to_string());
+        }
+    })?;
+    let added = app.feed_list.items.iter().position(|feed| feed.url == url);
+    app.feed_list.state.select(added);
     app.input.reset();
     app.input_mode = InputMode::Normal;
-    app.show_info(format!("Added {title}."));
+    match health_error {
+        Some(error) => app.show_error(format!(
+            "Added {title}, but could not save its health: {error}."
+        )),
+        None => app.show_info(format!("Added {title}.")),
+    }
     Ok(())
 }
 
@@ -83,14 +109,51 @@ pub(super) async fn open_selection(
                 return Ok(());
             };
             let feed = app.feed_list.items[selected].clone();
-            let parsed = match load_feed(&feed.url).await {
+            if let Some(command) = untrusted_command(&feed.url) {
+                confirm_command(app, command);
+                return Ok(());
+            }
+            if let Some(entries) = synced_entries(&feed.url)? {
+                app.entry_list.items = entries;
+                app.entry_list.state.select_first();
+                app.current_feed = Some(feed);
+                app.offline_since = None;
+                app.notice = None;
+                app.screen = Screen::Feed;
+                app.preview_selection();
+                return Ok(());
+            }
+            let result = load_feed(&feed.url, &app.config).await;
+            // A health.json that cannot be written is reported, not fatal.
+            let health_error = record_health(app, &feed.url, &result).err();
+            let parsed = match result {
                 Ok(parsed) => parsed,
+                Err(LoadFeedError::Fetch(FetchError::Gone)) => {
+                    app.health.reload()?;
+                    app.health.mark_gone(&feed.url)?;
+                    app.confirmation_popup = Some(ConfirmationPopup {
+                        message: format!(
+                            "“{}” no longer exists (HTTP 410). Unsubscribe?",
+                            feed.title
+                        ),
+                        choice: ConfirmationChoice::Cancel,
+                        kind: ConfirmationKind::DeleteFeed,
+                    });
+                    return Ok(());
+                }
+                Err(LoadFeedError::Fetch(error)) if error.is_actionable() => {
+                    app.show_error(format!("Could not load {}: {error}.", feed.title));
+                    return Ok(());
+                }
                 Err(LoadFeedError::Fetch(_)) => {
                     app.show_error(format!("Could not load {}.", feed.title));
                     return Ok(());
                 }
-                Err(LoadFeedError::Parse) => {
-                    app.show_error(format!("Could not parse {} as RSS or Atom.", feed.title));
+                Err(LoadFeedError::Parse(error)) => {
+                    app.show_error(format!(
+                        "Could not parse {} as RSS or Atom ({error}).",
+                        feed.title
+                    ));
                     return Ok(());
                 }
                 Err(LoadFeedError::NoEntries) => {
@@ -98,18 +161,43 @@ pub(super) async fn open_selection(
                     return Ok(());
                 }
             };
-            app.entry_list.items = parsed.entries;
+            let recovered = parsed.document.recovered;
+            app.entry_list.items = parsed.document.entries;
             app.entry_list.state.select_first();
-            app.notice = None;
+            app.offline_since = parsed.offline.as_ref().map(|copy| copy.fetched_at);
+            match (app.offline_since, parsed.moved_to) {
+                (Some(fetched_at), _) => app.show_error(format!(
+                    "Offline: showing the copy of {} saved {}.",
+                    feed.title,
+                    format_age(fetched_at.elapsed().unwrap_or_default())
+                )),
+                (None, Some(moved_to)) => move_feed(app, &feed, moved_to)?,
+                (None, None) if recovered => app.show_error(format!(
+                    "{} is malformed; showing the entries that could be recovered.",
+                    feed.title
+                )),
+                (None, None) => app.notice = None,
+            }
+            if let (Some(error), None) = (health_error, &app.notice) {
+                app.show_error(format!("Could not save the feed's health: {error}."));
+            }
+            // Saving feeds.json may have reloaded the list, so the selection
+            // is looked up again.
+            app.current_feed = app
+                .feed_list
+                .state
+                .selected()
+                .and_then(|selected| app.feed_list.items.get(selected))
+                .cloned()
+                .or(Some(feed));
             app.screen = Screen::Feed;
+            app.preview_selection();
         }
         Screen::Feed => {
             if let Some(selected) = app.entry_list.state.selected() {
                 stop_narration(app, narration);
-                app.current_entry = app.entry_list.items[selected].clone();
-                app.scroll_offset = 0;
-                app.max_scroll = 0;
-                app.screen = Screen::Article;
+                app.history.clear();
+                app.open_article(app.entry_list.items[selected].clone())?;
             }
         }
         Screen::Article => {}
@@ -117,12 +205,84 @@ pub(super) async fn open_selection(
     Ok(())
 }
 
+/// Points a permanently redirected subscription at its new URL. Feeds with
+/// settings in config.json stay where they are, since those settings are
+/// keyed by the old URL and the new one would be fetched without them.
+fn move_feed(app: &mut App, feed: &Feed, to: String) -> AppResult<()> {
+    let from = feed.url.as_str();
+    if app.feed_list.items.iter().any(|feed| feed.url == to) {
+        app.show_info(format!(
+            "This feed moved to {to}, which is already in your list."
+        ));
+        return Ok(());
+    }
+    if app.config.feeds.contains_key(from) {
+        app.show_info(format!(
+            "This feed moved to {to}. Update its URL in feeds.json and config.json."
+        ));
+        return Ok(());
+    }
+    app.feed_list.items = update_feeds(|feeds| feeds.move_feed(from, to.clone()))?;
+    let moved = app.feed_list.items.iter().position(|feed| feed.url == to);
+    app.feed_list.state.select(moved);
+    app.health.reload()?;
+    app.health.rename(from, &to)?;
+    EntryStore::load()?.rename(from, &to)?;
+    let _ = FeedCache::default().remove(from);
+    app.show_info(format!("Feed moved permanently; now following {to}."));
+    Ok(())
+}
+
+/// Trusts the command behind the feed being added or opened, then retries.
+pub(super) async fn run_trusted_command(
+    app: &mut App,
+    narration: &NarrationHandle,
+) -> AppResult<()> {
+    let source = match app.input_mode {
+        InputMode::Editing => app.input.value().trim().to_string(),
+        InputMode::Normal => match app.feed_list.state.selected() {
+            Some(selected) => app.feed_list.items[selected].url.clone(),
+            None => return Ok(()),
+        },
+    };
+    if let Some(command) = FeedSource::parse(&source)
+        .ok()
+        .and_then(|source| source.command().map(str::to_string))
+    {
+        trust(&command)?;
+    }
+
+    match app.input_mode {
+        InputMode::Editing => add_feed(app).await,
+        InputMode::Normal => open_selection(app, narration).await,
+    }
+}
+
+fn untrusted_command(source: &str) -> Option<String> {
+    FeedSource::parse(source)
+        .ok()?
+        .command()
+        .filter(|command| !is_trusted(command))
+        .map(str::to_string)
+}
+
+fn confirm_command(app: &mut App, command: String) {
+    app.confirmation_popup = Some(ConfirmationPopup {
+        message: format!("Run `{command}` to load this feed?"),
+        choice: ConfirmationChoice::Cancel,
+        kind: ConfirmationKind::RunCommand,
+    });
+}
+
 pub(super) fn delete_selected_feed(app: &mut App) -> AppResult<()> {
     let Some(selected) = app.feed_list.state.selected() else {
         return Ok(());
     };
-    let removed = app.feed_list.items.remove(selected);
-    app.feed_list.items.persist()?;
+    let removed = app.feed_list.items[selected].clone();
+    app.feed_list.items = update_feeds(|feeds| feeds.retain(|feed| feed.url != removed.url))?;
+    let _ = FeedCache::default().remove(&removed.url);
+    app.health.reload()?;
+    app.health.remove(&removed.url)?;
 
     if app.feed_list.items.is_empty() {
         app.feed_list.state.select(None);
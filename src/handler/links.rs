@@ -0,0 +1,76 @@
+use crate::app::{App, AppResult, HistoryEntry};
+use crate::external::open_url;
+use crate::feed::{entries::Entry, feeds::Feed};
+use crate::tts::NarrationHandle;
+use crossterm::event::{KeyCode, KeyEvent};
+
+use super::tts::stop_narration;
+
+pub(super) fn handle_link_picker(
+    key: KeyEvent,
+    app: &mut App,
+    narration: &NarrationHandle,
+) -> AppResult<()> {
+    let Some(picker) = app.link_picker.as_mut() else {
+        return Ok(());
+    };
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q' | 'l') => app.link_picker = None,
+        KeyCode::Down | KeyCode::Char('j') => picker.state.select_next(),
+        KeyCode::Up | KeyCode::Char('k') => picker.state.select_previous(),
+        KeyCode::Home => picker.state.select_first(),
+        KeyCode::End => picker.state.select_last(),
+        KeyCode::Enter => {
+            let Some(link) = picker
+                .state
+                .selected()
+                .and_then(|selected| picker.links.get(selected))
+            else {
+                return Ok(());
+            };
+            let url = link.url.clone();
+            app.link_picker = None;
+            if let Some((feed, entry)) = known_article(app, &url) {
+                stop_narration(app, narration);
+                app.remember_position()?;
+                app.history.push(HistoryEntry {
+                    feed: app.current_feed.clone(),
+                    entry: std::mem::take(&mut app.current_entry),
+                    scroll_offset: app.scroll_offset,
+                });
+                app.current_feed = feed;
+                return app.open_article(entry);
+            }
+            match open_url(&url) {
+                Ok(()) => app.show_info(format!("Opened {url}.")),
+                Err(error) => app.show_error(format!("Could not open {url}: {error}.")),
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Another article the link points at, from the listed entries or the
+/// recently read ones, with the feed it belongs to.
+fn known_article(app: &App, url: &str) -> Option<(Option<Feed>, Entry)> {
+    let links_to = |entry: &Entry| {
+        entry.link.as_deref() == Some(url) && entry.key() != app.current_entry.key()
+    };
+    if let Some(entry) = app.entry_list.items.iter().find(|entry| links_to(entry)) {
+        return Some((app.list_feed().cloned(), entry.clone()));
+    }
+    let recent = app
+        .reading
+        .recent()
+        .iter()
+        .find(|recent| links_to(&recent.entry))?;
+    let feed = recent.feed_url.as_deref().and_then(|url| {
+        app.feed_list
+            .items
+            .iter()
+            .find(|feed| feed.url == url)
+            .cloned()
+    });
+    Some((feed, recent.entry.clone()))
+}
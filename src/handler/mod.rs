@@ -1,8 +1,11 @@
 mod confirmation;
 mod editing;
+mod export;
 mod feed_actions;
+mod links;
 mod navigation;
 mod normal;
+mod read_later;
 mod tts;
 
 pub use tts::{handle_narration_event, handle_tts_model_event};
@@ -14,6 +17,7 @@ use tokio::sync::mpsc;
 
 use confirmation::handle_confirmation;
 use editing::handle_editing_mode;
+use links::handle_link_picker;
 use normal::handle_normal_mode;
 
 pub async fn handle_key_events(
@@ -24,13 +28,28 @@ pub async fn handle_key_events(
 ) -> AppResult<()> {
     if key.modifiers.contains(KeyModifiers::CONTROL) && matches!(key.code, KeyCode::Char('c' | 'C'))
     {
-        tts::stop_narration(app, narration);
-        app.quit();
+        app.remember_position()?;
+        tts::quit(app, narration);
+        return Ok(());
+    }
+
+    if app.show_feed_details {
+        if matches!(
+            key.code,
+            KeyCode::Esc | KeyCode::Enter | KeyCode::Char('i' | 'q')
+        ) {
+            app.show_feed_details = false;
+        }
+        return Ok(());
+    }
+
+    if app.link_picker.is_some() {
+        handle_link_picker(key, app, narration)?;
         return Ok(());
     }
 
     if app.confirmation_popup.is_some() {
-        handle_confirmation(key, app, model_tx)?;
+        handle_confirmation(key, app, model_tx, narration).await?;
         return Ok(());
     }
 
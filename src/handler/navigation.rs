@@ -1,4 +1,5 @@
-use crate::app::App;
+use crate::app::{App, AppResult};
+use crate::feed::entries::Entry;
 use crate::screen::Screen;
 use crate::tts::NarrationHandle;
 
@@ -16,17 +17,30 @@ pub(super) enum Edge {
     Last,
 }
 
-pub(super) fn go_back(app: &mut App, narration: &NarrationHandle) {
+/// Leaves the screen, or returns to the article a link was followed from.
+pub(super) fn go_back(app: &mut App, narration: &NarrationHandle) -> AppResult<()> {
     app.notice = None;
     match app.screen {
         Screen::Home => {}
         Screen::Feed => app.screen = Screen::Home,
         Screen::Article => {
             stop_narration(app, narration);
-            app.scroll_offset = 0;
-            app.screen = Screen::Feed;
+            app.remember_position()?;
+            match app.history.pop() {
+                Some(previous) => {
+                    app.current_feed = previous.feed;
+                    app.current_entry = previous.entry;
+                    app.scroll_offset = previous.scroll_offset;
+                    app.code_scroll = 0;
+                }
+                None => {
+                    app.scroll_offset = 0;
+                    app.screen = Screen::Feed;
+                }
+            }
         }
     }
+    Ok(())
 }
 
 pub(super) fn move_selection(app: &mut App, direction: Direction) {
@@ -42,6 +56,7 @@ pub(super) fn move_selection(app: &mut App, direction: Direction) {
             app.scroll_offset = app.scroll_offset.saturating_sub(1);
         }
     }
+    app.preview_selection();
 }
 
 pub(super) fn select_edge(app: &mut App, edge: Edge) {
@@ -53,4 +68,257 @@ pub(super) fn select_edge(app: &mut App, edge: Edge) {
         (Screen::Article, Edge::First) => app.scroll_offset = 0,
         (Screen::Article, Edge::Last) => app.scroll_offset = app.max_scroll,
     }
+    app.preview_selection();
+}
+
+/// Moves focus from the feeds pane to the listed articles.
+pub(super) fn focus_entries(app: &mut App) {
+    if app.entry_list.items.is_empty() {
+        return;
+    }
+    app.notice = None;
+    app.screen = Screen::Feed;
+    app.preview_selection();
+}
+
+/// Opens the entry after or before the open article in the list, keeping
+/// the list's selection on it.
+pub(super) fn step_article(
+    app: &mut App,
+    narration: &NarrationHandle,
+    direction: Direction,
+) -> AppResult<()> {
+    let Some(current) = article_index(app) else {
+        return Ok(());
+    };
+    let next = match direction {
+        Direction::Forward => Some(current + 1).filter(|next| *next < app.entry_list.items.len()),
+        Direction::Backward => current.checked_sub(1),
+    };
+    let Some(next) = next else {
+        app.show_info(match direction {
+            Direction::Forward => "This is the last article.",
+            Direction::Backward => "This is the first article.",
+        });
+        return Ok(());
+    };
+    leave_article(app, narration)?;
+    app.entry_list.state.select(Some(next));
+    app.open_article(app.entry_list.items[next].clone())
+}
+
+/// Opens the next unread entry in the list, or else the first unread one
+/// stored for the feeds after it, wrapping around to the current feed.
+pub(super) fn next_unread(app: &mut App, narration: &NarrationHandle) -> AppResult<()> {
+    app.entries.reload()?;
+    let store = &app.entries;
+    let is_read = |url: &str, entry: &Entry| store.get(url).is_some_and(|feed| feed.is_read(entry));
+    let current_url = app.list_feed().map(|feed| feed.url.clone());
+
+    let start = article_index(app).map_or(0, |current| current + 1);
+    let listed = app
+        .entry_list
+        .items
+        .iter()
+        .enumerate()
+        .skip(start)
+        .find(|(_, entry)| {
+            !current_url
+                .as_deref()
+                .is_some_and(|url| is_read(url, entry))
+        })
+        .map(|(index, _)| index);
+    if let Some(index) = listed {
+        leave_article(app, narration)?;
+        app.entry_list.state.select(Some(index));
+        return app.open_article(app.entry_list.items[index].clone());
+    }
+
+    let feeds = app.feed_list.items.len();
+    let after = current_url
+        .as_deref()
+        .and_then(|url| app.feed_list.items.iter().position(|feed| feed.url == url))
+        .map_or(0, |current| current + 1);
+    let found = (0..feeds)
+        .map(|step| (after + step) % feeds)
+        .find_map(|feed_index| {
+            let url = &app.feed_list.items[feed_index].url;
+            store
+                .entries(url)
+                .iter()
+                .position(|entry| !is_read(url, entry))
+                .map(|entry_index| (feed_index, entry_index))
+        });
+    let Some((feed_index, entry_index)) = found else {
+        app.show_info("No unread articles left.");
+        return Ok(());
+    };
+    leave_article(app, narration)?;
+    let feed = app.feed_list.items[feed_index].clone();
+    app.entry_list.items = app.entries.entries(&feed.url).to_vec();
+    app.entry_list.state.select(Some(entry_index));
+    app.feed_list.state.select(Some(feed_index));
+    app.current_feed = Some(feed);
+    app.offline_since = None;
+    app.open_article(app.entry_list.items[entry_index].clone())
+}
+
+/// Where the open article is in the list, falling back to the selection
+/// when it was reached through a link.
+fn article_index(app: &App) -> Option<usize> {
+    let key = app.current_entry.key();
+    app.entry_list
+        .items
+        .iter()
+        .position(|entry| entry.key() == key)
+        .or(app.entry_list.state.selected())
+}
+
+/// Closes the open article, returning to the list's feed before another
+/// entry of the list is opened.
+fn leave_article(app: &mut App, narration: &NarrationHandle) -> AppResult<()> {
+    app.notice = None;
+    stop_narration(app, narration);
+    app.remember_position()?;
+    app.current_feed = app.list_feed().cloned();
+    app.history.clear();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crossterm::event::{KeyCode, KeyEvent};
+    use tokio::sync::mpsc;
+
+    use crate::app::{App, HistoryEntry, Notice};
+    use crate::feed::{
+        entries::{Entry, FeedDocument},
+        feeds::Feed,
+    };
+    use crate::handler::handle_key_events;
+    use crate::test_support::test_app;
+    use crate::tts::spawn_narration;
+
+    #[tokio::test]
+    async fn steps_through_the_list_and_marks_articles_read() {
+        let mut app = test_app();
+        let feed = app.feed_list.items[0].clone();
+        app.current_feed = Some(feed.clone());
+        app.entry_list.items = entries(&["a", "b", "c"]);
+        app.entry_list.state.select(Some(0));
+        app.open_article(app.entry_list.items[0].clone()).unwrap();
+
+        press(&mut app, 'n').await;
+        press(&mut app, 'n').await;
+        assert_eq!(app.current_entry.key(), "c");
+        assert_eq!(app.entry_list.state.selected(), Some(2));
+        press(&mut app, 'n').await;
+        assert_eq!(app.current_entry.key(), "c");
+        assert!(
+            matches!(&app.notice, Some(Notice::Info(message)) if message == "This is the last article.")
+        );
+        press(&mut app, 'p').await;
+        assert_eq!(app.current_entry.key(), "b");
+        assert_eq!(app.entries.get(&feed.url).unwrap().unread_count(), 0);
+
+        // A configured key wins over the built-in one it replaces.
+        app.config.keys.previous_article = 'k';
+        press(&mut app, 'k').await;
+        assert_eq!(app.current_entry.key(), "a");
+    }
+
+    #[tokio::test]
+    async fn next_unread_moves_on_to_later_feeds_and_wraps_around() {
+        let mut app = test_app();
+        let first = app.feed_list.items[0].clone();
+        let second = Feed {
+            title: "Second feed".to_string(),
+            url: "https://example.org/feed.xml".to_string(),
+        };
+        app.feed_list.items.push(second.clone());
+        store(&mut app, &first.url, &["a", "b"]);
+        store(&mut app, &second.url, &["x"]);
+        app.entries.mark_read(&first.url, "b", true).unwrap();
+        app.current_feed = Some(first.clone());
+        app.entry_list.items = entries(&["a", "b"]);
+        app.open_article(app.entry_list.items[0].clone()).unwrap();
+
+        press(&mut app, 'N').await;
+        assert_eq!(app.current_feed.as_ref().unwrap().url, second.url);
+        assert_eq!(app.current_entry.key(), "x");
+        assert_eq!(app.feed_list.state.selected(), Some(1));
+
+        app.entries.mark_read(&first.url, "a", false).unwrap();
+        press(&mut app, 'N').await;
+        assert_eq!(app.current_feed.as_ref().unwrap().url, first.url);
+        assert_eq!(app.current_entry.key(), "a");
+
+        press(&mut app, 'N').await;
+        assert!(
+            matches!(&app.notice, Some(Notice::Info(message)) if message == "No unread articles left.")
+        );
+    }
+
+    #[tokio::test]
+    async fn steps_through_the_list_feed_after_following_a_link() {
+        let mut app = test_app();
+        let listed = app.feed_list.items[0].clone();
+        let linked = Feed {
+            title: "Linked feed".to_string(),
+            url: "https://example.org/feed.xml".to_string(),
+        };
+        app.feed_list.items.push(linked.clone());
+        app.current_feed = Some(listed.clone());
+        app.entry_list.items = entries(&["a", "b"]);
+        app.entry_list.state.select(Some(0));
+        app.open_article(app.entry_list.items[0].clone()).unwrap();
+
+        // Following a link to a recently read article of another feed.
+        app.history.push(HistoryEntry {
+            feed: app.current_feed.clone(),
+            entry: std::mem::take(&mut app.current_entry),
+            scroll_offset: 0,
+        });
+        app.current_feed = Some(linked.clone());
+        app.open_article(entries(&["x"]).remove(0)).unwrap();
+        assert_eq!(app.entries.entries(&linked.url).len(), 1);
+
+        press(&mut app, 'n').await;
+        assert_eq!(app.current_feed.as_ref().unwrap().url, listed.url);
+        assert_eq!(app.current_entry.key(), "b");
+        assert!(app.history.is_empty());
+        assert_eq!(app.entries.entries(&linked.url).len(), 1);
+        assert_eq!(app.entries.get(&listed.url).unwrap().unread_count(), 0);
+    }
+
+    async fn press(app: &mut App, key: char) {
+        let (model_tx, _) = mpsc::unbounded_channel();
+        let (narration, _) = spawn_narration();
+        handle_key_events(
+            KeyEvent::from(KeyCode::Char(key)),
+            app,
+            &model_tx,
+            &narration,
+        )
+        .await
+        .unwrap();
+    }
+
+    fn entries(ids: &[&str]) -> Vec<Entry> {
+        ids.iter()
+            .map(|id| Entry {
+                id: Some(id.to_string()),
+                title: format!("Post {id}"),
+                ..Entry::default()
+            })
+            .collect()
+    }
+
+    fn store(app: &mut App, url: &str, ids: &[&str]) {
+        let document = FeedDocument {
+            entries: entries(ids),
+            ..FeedDocument::default()
+        };
+        app.entries.merge(url, &document).unwrap();
+    }
 }
@@ -1,13 +1,21 @@
 use crate::app::{
     App, AppResult, ConfirmationChoice, ConfirmationKind, ConfirmationPopup, InputMode,
 };
+use crate::external::Viewer;
 use crate::screen::Screen;
 use crate::tts::NarrationHandle;
 use crossterm::event::{KeyCode, KeyEvent};
 
+use super::export::{export_entry, export_unread};
 use super::feed_actions::open_selection;
-use super::navigation::{go_back, move_selection, select_edge, Direction, Edge};
-use super::tts::{request_tts, stop_narration, toggle_narration};
+use super::navigation::{
+    focus_entries, go_back, move_selection, next_unread, select_edge, step_article, Direction, Edge,
+};
+use super::read_later::send_to_read_later;
+use super::tts::{quit, request_tts, stop_narration, toggle_narration};
+
+/// Columns `<` and `>` move code blocks by.
+const CODE_SCROLL_STEP: u16 = 8;
 
 pub(super) async fn handle_normal_mode(
     key: KeyEvent,
@@ -15,14 +23,34 @@ pub(super) async fn handle_normal_mode(
     narration: &NarrationHandle,
 ) -> AppResult<()> {
     match key.code {
+        // Configured keys come first so they win over the built-in ones.
+        KeyCode::Char(key)
+            if app.screen == Screen::Article && key == app.config.keys.next_article =>
+        {
+            step_article(app, narration, Direction::Forward)?;
+        }
+        KeyCode::Char(key)
+            if app.screen == Screen::Article && key == app.config.keys.previous_article =>
+        {
+            step_article(app, narration, Direction::Backward)?;
+        }
+        KeyCode::Char(key)
+            if app.screen == Screen::Article && key == app.config.keys.next_unread =>
+        {
+            next_unread(app, narration)?;
+        }
         KeyCode::Char('q') => {
-            stop_narration(app, narration);
-            app.quit();
+            app.remember_position()?;
+            quit(app, narration);
         }
         KeyCode::Char('a' | '/') if app.screen == Screen::Home => {
             app.notice = None;
             app.input_mode = InputMode::Editing;
         }
+        KeyCode::Char('r') if app.screen == Screen::Home => app.open_recent(),
+        KeyCode::Char('i') if app.screen == Screen::Home => {
+            app.show_feed_details = app.feed_list.state.selected().is_some();
+        }
         KeyCode::Delete | KeyCode::Backspace if app.screen == Screen::Home => {
             if let Some(selected) = app.feed_list.state.selected() {
                 let title = &app.feed_list.items[selected].title;
@@ -34,7 +62,13 @@ pub(super) async fn handle_normal_mode(
             }
         }
         KeyCode::Char('t' | 'T') => request_tts(app, narration)?,
-        KeyCode::Esc | KeyCode::Left => go_back(app, narration),
+        KeyCode::Esc | KeyCode::Left => go_back(app, narration)?,
+        KeyCode::Tab if app.panes.is_split() => match app.screen {
+            Screen::Home => focus_entries(app),
+            Screen::Feed => open_selection(app, narration).await?,
+            Screen::Article => {}
+        },
+        KeyCode::BackTab if app.panes.is_split() => go_back(app, narration)?,
         KeyCode::Down | KeyCode::Char('j') => move_selection(app, Direction::Forward),
         KeyCode::Up | KeyCode::Char('k') => move_selection(app, Direction::Backward),
         KeyCode::PageDown if app.screen == Screen::Article => {
@@ -49,6 +83,26 @@ pub(super) async fn handle_normal_mode(
         KeyCode::Char('s' | 'S') if app.screen == Screen::Article => {
             stop_narration(app, narration);
         }
+        KeyCode::Char('l') if app.screen == Screen::Article => app.open_link_picker(),
+        KeyCode::Char('w') if app.screen == Screen::Article => {
+            app.code_wrap = !app.code_wrap;
+            app.code_scroll = 0;
+        }
+        KeyCode::Char('>') if app.screen == Screen::Article => {
+            app.code_scroll = app.code_scroll.saturating_add(CODE_SCROLL_STEP);
+        }
+        KeyCode::Char('<') if app.screen == Screen::Article => {
+            app.code_scroll = app.code_scroll.saturating_sub(CODE_SCROLL_STEP);
+        }
+        KeyCode::Char('v') if app.screen == Screen::Article => {
+            app.external_viewer = Some(Viewer::Pager);
+        }
+        KeyCode::Char('e') if app.screen == Screen::Article => {
+            app.external_viewer = Some(Viewer::Editor);
+        }
+        KeyCode::Char('x') if app.screen != Screen::Home => export_entry(app),
+        KeyCode::Char('b') if app.screen != Screen::Home => send_to_read_later(app).await,
+        KeyCode::Char('E') if app.screen == Screen::Feed => export_unread(app)?,
         KeyCode::Home => select_edge(app, Edge::First),
         KeyCode::End => select_edge(app, Edge::Last),
         KeyCode::Enter | KeyCode::Right => open_selection(app, narration).await?,
@@ -0,0 +1,43 @@
+use crate::app::App;
+use crate::read_later::{default_service, send, Bookmark};
+use crate::screen::Screen;
+
+/// Sends the open article, or the one selected in the entry list, to the
+/// default read-later service.
+pub(super) async fn send_to_read_later(app: &mut App) {
+    let entry = match app.screen {
+        Screen::Article => &app.current_entry,
+        Screen::Feed => match app.entry_list.state.selected() {
+            Some(selected) => &app.entry_list.items[selected],
+            None => return,
+        },
+        Screen::Home => return,
+    };
+    let Some(url) = entry.link.as_deref() else {
+        app.show_error("This article has no link to save.");
+        return;
+    };
+    let (name, service) = match default_service(&app.config.read_later) {
+        Ok(service) => service,
+        Err(error) => {
+            app.show_error(format!("Could not save the article: {error}."));
+            return;
+        }
+    };
+    let bookmark = Bookmark {
+        url,
+        title: &entry.title,
+        feed: app
+            .current_feed
+            .as_ref()
+            .map_or("", |feed| feed.title.as_str()),
+    };
+    let message = match send(service, bookmark, &app.config.network).await {
+        Ok(()) => Ok(format!("Saved “{}” to {name}.", entry.title)),
+        Err(error) => Err(format!("Could not save the article to {name}: {error}.")),
+    };
+    match message {
+        Ok(message) => app.show_info(message),
+        Err(message) => app.show_error(message),
+    }
+}
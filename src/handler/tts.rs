@@ -97,7 +97,7 @@ pub(super) fn toggle_narration(app: &mut App, narration: &NarrationHandle) -> Ap
         NarrationUiState::Idle | NarrationUiState::Completed | NarrationUiState::Error => {}
     }
 
-    if app.tts.is_none() {
+    if app.tts.is_none() && !app.daemon_attached {
         if model_ready() {
             match TTS::load() {
                 Ok(tts) => {
@@ -138,6 +138,14 @@ pub(super) fn toggle_narration(app: &mut App, narration: &NarrationHandle) -> Ap
     Ok(())
 }
 
+/// Quits fead, leaving daemon narration playing.
+pub(super) fn quit(app: &mut App, narration: &NarrationHandle) {
+    if !app.daemon_attached {
+        stop_narration(app, narration);
+    }
+    app.quit();
+}
+
 pub(super) fn stop_narration(app: &mut App, narration: &NarrationHandle) {
     if app.narration.is_active() || matches!(app.narration, NarrationUiState::Completed) {
         narration.stop();
@@ -159,8 +167,9 @@ pub(super) fn start_tts_download(
     app.show_info("Downloading Kokoro EN model… 0%");
 
     let tx = model_tx.clone();
+    let network = app.config.network.clone();
     tokio::spawn(async move {
-        let result = download_model(|percent| {
+        let result = download_model(&network, |percent| {
             let _ = tx.send(TtsModelEvent::Progress { percent });
         })
         .await;
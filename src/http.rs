@@ -0,0 +1,147 @@
+use std::{fs, time::Duration};
+
+use reqwest::{Certificate, Client, ClientBuilder, Proxy, Response, StatusCode, Url};
+
+use crate::config::NetworkSettings;
+
+pub const USER_AGENT: &str = concat!("fead/", env!("CARGO_PKG_VERSION"));
+
+/// Proxy value that bypasses both the configured and the system proxy.
+const DIRECT: &str = "direct";
+const API_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A failed call to a sync or read-later server's API. Each module turns it
+/// into its own error.
+#[derive(Debug)]
+pub enum ApiError {
+    InvalidUrl(String),
+    /// The client could not be set up from the network settings.
+    Client(String),
+    Unauthorized,
+    Request(reqwest::Error),
+    HttpStatus(StatusCode),
+}
+
+impl From<reqwest::Error> for ApiError {
+    fn from(error: reqwest::Error) -> Self {
+        Self::Request(error)
+    }
+}
+
+/// Starts a client for `url` with the configured proxy, extra root
+/// certificates and per-host certificate exceptions applied.
+pub fn client_builder(
+    network: &NetworkSettings,
+    url: &Url,
+    proxy: Option<&str>,
+) -> Result<ClientBuilder, String> {
+    let mut builder = Client::builder().user_agent(USER_AGENT);
+
+    match proxy.or(network.proxy.as_deref()) {
+        Some(DIRECT) => builder = builder.no_proxy(),
+        Some(proxy) => {
+            let proxy =
+                Proxy::all(proxy).map_err(|error| format!("invalid proxy {proxy}: {error}"))?;
+            builder = builder.proxy(proxy);
+        }
+        None => {}
+    }
+
+    for path in &network.ca_certificates {
+        let pem = fs::read(path)
+            .map_err(|error| format!("could not read {}: {error}", path.display()))?;
+        let certificates = Certificate::from_pem_bundle(&pem)
+            .map_err(|error| format!("invalid certificate in {}: {error}", path.display()))?;
+        for certificate in certificates {
+            builder = builder.add_root_certificate(certificate);
+        }
+    }
+
+    let insecure = url.host_str().is_some_and(|host| {
+        network
+            .insecure_hosts
+            .iter()
+            .any(|insecure| insecure.eq_ignore_ascii_case(host))
+    });
+    if insecure {
+        builder = builder.tls_danger_accept_invalid_certs(true);
+    }
+
+    Ok(builder)
+}
+
+/// A client for a server's API, giving up on requests after 30 seconds.
+pub fn api_client(network: &NetworkSettings, url: &Url) -> Result<Client, ApiError> {
+    Ok(client_builder(network, url, None)
+        .map_err(ApiError::Client)?
+        .timeout(API_TIMEOUT)
+        .build()?)
+}
+
+/// The configured URL with a trailing slash, so endpoints join below it.
+pub fn base_url(url: &str) -> Result<Url, ApiError> {
+    let mut base = Url::parse(url).map_err(|_| ApiError::InvalidUrl(url.to_string()))?;
+    if !base.path().ends_with('/') {
+        let path = format!("{}/", base.path());
+        base.set_path(&path);
+    }
+    Ok(base)
+}
+
+pub fn endpoint(base: &Url, path: &str) -> Result<Url, ApiError> {
+    base.join(path)
+        .map_err(|_| ApiError::InvalidUrl(format!("{base}{path}")))
+}
+
+/// Passes successful responses through and turns the rest into errors.
+pub fn check(response: Response) -> Result<Response, ApiError> {
+    match response.status() {
+        status if status.is_success() => Ok(response),
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Err(ApiError::Unauthorized),
+        status => Err(ApiError::HttpStatus(status)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::Url;
+
+    use crate::config::NetworkSettings;
+    use crate::test_support::{MockResponse, MockServer};
+
+    use super::client_builder;
+
+    #[tokio::test]
+    async fn routes_requests_through_the_feed_proxy_before_the_global_one() {
+        let proxy = MockServer::start(|_| MockResponse::ok("<rss/>")).await;
+        let network = NetworkSettings {
+            proxy: Some("http://127.0.0.1:9".into()),
+            ..NetworkSettings::default()
+        };
+        let url = Url::parse("http://feeds.example.invalid/feed.xml").unwrap();
+
+        let client = client_builder(&network, &url, Some(&proxy.url("")))
+            .unwrap()
+            .build()
+            .unwrap();
+        let body = client.get(url).send().await.unwrap().text().await.unwrap();
+
+        assert_eq!(body, "<rss/>");
+        assert_eq!(
+            proxy.requests()[0].path,
+            "http://feeds.example.invalid/feed.xml"
+        );
+    }
+
+    #[test]
+    fn reports_unreadable_certificates() {
+        let network = NetworkSettings {
+            ca_certificates: vec!["/nonexistent/corp-ca.pem".into()],
+            ..NetworkSettings::default()
+        };
+        let url = Url::parse("https://example.com/feed.xml").unwrap();
+
+        let error = client_builder(&network, &url, None).unwrap_err();
+        assert!(error.contains("/nonexistent/corp-ca.pem"));
+    }
+}
@@ -0,0 +1,429 @@
+mod protocol;
+
+use std::{
+    collections::HashMap, error::Error, fmt, fs, io, path::PathBuf, sync::Arc, time::Duration,
+};
+
+use futures::StreamExt;
+use image::{DynamicImage, RgbaImage};
+use ratatui::{buffer::Buffer, layout::Rect};
+use reqwest::{StatusCode, Url};
+use tokio::sync::mpsc;
+
+use crate::config::{Config, ImageProtocol, ImageSettings};
+use crate::feed::cache::fnv1a;
+use crate::http::client_builder;
+
+pub use protocol::detect;
+
+const CACHE_DIR: &str = "cache/images";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+/// Cell size assumed when the terminal does not report one, in pixels.
+const DEFAULT_CELL: (u16, u16) = (10, 20);
+
+#[derive(Debug)]
+pub enum ImageError {
+    UnsupportedUrl,
+    Network(String),
+    Request(reqwest::Error),
+    HttpStatus(StatusCode),
+    TooLarge { limit: u64 },
+    Decode(image::ImageError),
+}
+
+impl fmt::Display for ImageError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnsupportedUrl => formatter.write_str("images must use HTTP or HTTPS"),
+            Self::Network(message) => write!(formatter, "network settings: {message}"),
+            Self::Request(error) => write!(formatter, "image request failed: {error}"),
+            Self::HttpStatus(status) => write!(formatter, "image returned HTTP {status}"),
+            Self::TooLarge { limit } => write!(
+                formatter,
+                "image is larger than the {} KiB limit",
+                limit / 1024
+            ),
+            Self::Decode(error) => write!(formatter, "could not decode the image: {error}"),
+        }
+    }
+}
+
+impl Error for ImageError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Request(error) => Some(error),
+            Self::Decode(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for ImageError {
+    fn from(error: reqwest::Error) -> Self {
+        Self::Request(error)
+    }
+}
+
+#[derive(Debug)]
+pub struct LoadedImage {
+    pub url: String,
+    pub result: Result<DynamicImage, ImageError>,
+}
+
+#[derive(Clone, Debug)]
+pub enum ImageState {
+    Loading,
+    Ready(Arc<DynamicImage>),
+    Failed,
+}
+
+/// Images referenced by articles, their download state, and what has been
+/// prepared for the terminal so redraws stay cheap.
+#[derive(Debug)]
+pub struct ImageStore {
+    protocol: ImageProtocol,
+    /// Pixels per terminal cell, width then height.
+    cell: (u16, u16),
+    max_rows: u16,
+    states: HashMap<String, ImageState>,
+    /// Wanted by the last render and not yet requested.
+    pending: Vec<String>,
+    /// Kitty image ids, one per URL.
+    ids: HashMap<String, u32>,
+    /// Kitty ids and the size last sent to the terminal.
+    transmitted: HashMap<u32, (u16, u16)>,
+    /// The picture scaled for half-block drawing, by URL, with its size.
+    cells: HashMap<String, (u16, u16, RgbaImage)>,
+    /// iTerm2, sixel and kitty escape sequences, by URL, with their size.
+    payloads: HashMap<String, (u16, u16, Arc<str>)>,
+}
+
+impl ImageStore {
+    pub fn new(settings: &ImageSettings) -> Self {
+        let protocol = match settings.protocol {
+            _ if !settings.enabled => ImageProtocol::Placeholder,
+            Some(protocol) => protocol,
+            None => detect(),
+        };
+        let cell = crossterm::terminal::window_size()
+            .ok()
+            .filter(|size| size.width > 0 && size.height > 0 && size.columns > 0 && size.rows > 0)
+            .map_or(DEFAULT_CELL, |size| {
+                (
+                    (size.width / size.columns).max(1),
+                    (size.height / size.rows).max(1),
+                )
+            });
+        let max_rows = match protocol {
+            ImageProtocol::Kitty => settings.max_rows.min(protocol::KITTY_MAX_ROWS),
+            _ => settings.max_rows,
+        };
+        Self {
+            protocol,
+            cell,
+            max_rows: max_rows.max(1),
+            states: HashMap::new(),
+            pending: Vec::new(),
+            ids: HashMap::new(),
+            transmitted: HashMap::new(),
+            cells: HashMap::new(),
+            payloads: HashMap::new(),
+        }
+    }
+
+    /// The image's state, asking for it to be downloaded on first sight.
+    /// `None` means it is never shown, only named.
+    pub fn get(&mut self, url: &str) -> Option<&ImageState> {
+        if self.protocol == ImageProtocol::Placeholder {
+            return None;
+        }
+        if !self.states.contains_key(url) {
+            let state = if is_web_url(url) {
+                self.pending.push(url.to_string());
+                ImageState::Loading
+            } else {
+                ImageState::Failed
+            };
+            self.states.insert(url.to_string(), state);
+        }
+        self.states.get(url)
+    }
+
+    pub fn take_pending(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.pending)
+    }
+
+    pub fn finish(&mut self, loaded: LoadedImage) {
+        let state = match loaded.result {
+            Ok(image) => ImageState::Ready(Arc::new(image)),
+            Err(_) => ImageState::Failed,
+        };
+        self.states.insert(loaded.url, state);
+    }
+
+    /// Forgets what the terminal holds, after another program has used it.
+    pub fn reset_terminal(&mut self) {
+        self.transmitted.clear();
+    }
+
+    /// Columns and rows the image takes when drawn at most `max_cols` wide,
+    /// keeping its aspect ratio and never scaling it up.
+    pub fn size_for(&self, image: &DynamicImage, max_cols: u16) -> (u16, u16) {
+        let (cell_width, cell_height) = (f64::from(self.cell.0), f64::from(self.cell.1));
+        let (width, height) = (f64::from(image.width()), f64::from(image.height().max(1)));
+        let mut cols = (width / cell_width)
+            .ceil()
+            .clamp(1.0, f64::from(max_cols.max(1)));
+        let mut rows = (cols * cell_width * height / width.max(1.0) / cell_height).ceil();
+        if rows > f64::from(self.max_rows) {
+            rows = f64::from(self.max_rows);
+            cols = (rows * cell_height * width / height / cell_width)
+                .ceil()
+                .clamp(1.0, f64::from(max_cols.max(1)));
+        }
+        (cols as u16, rows.max(1.0) as u16)
+    }
+
+    /// Draws rows `skip..` of the image, sized `cols` × `rows`, into `area`.
+    /// Protocols that cannot crop fall back to half blocks while the image is
+    /// only partly on screen.
+    pub fn draw(
+        &mut self,
+        url: &str,
+        image: &DynamicImage,
+        (cols, rows): (u16, u16),
+        skip: u16,
+        area: Rect,
+        buf: &mut Buffer,
+    ) {
+        let whole = skip == 0 && area.height >= rows;
+        match self.protocol {
+            ImageProtocol::Kitty => {
+                let next_id = self.ids.len() as u32 + 1;
+                let id = *self.ids.entry(url.to_string()).or_insert(next_id);
+                let transmission = (self.transmitted.get(&id) != Some(&(cols, rows))).then(|| {
+                    self.transmitted.insert(id, (cols, rows));
+                    let cell = self.cell;
+                    self.payload(url, (cols, rows), || {
+                        protocol::kitty_transmission(image, id, cols, rows, cell)
+                    })
+                });
+                protocol::kitty_placeholders(id, cols, skip, area, buf, transmission.as_deref());
+            }
+            ImageProtocol::Iterm2 if whole => {
+                let cell = self.cell;
+                let payload = self.payload(url, (cols, rows), || {
+                    protocol::iterm2(image, cols, rows, cell)
+                });
+                protocol::place(&payload, cols, rows, area, buf);
+            }
+            ImageProtocol::Sixel if whole => {
+                let cell = self.cell;
+                let payload = self.payload(url, (cols, rows), || {
+                    protocol::sixel(image, cols, rows, cell)
+                });
+                if payload.is_empty() {
+                    self.draw_halfblocks(url, image, (cols, rows), skip, area, buf);
+                } else {
+                    protocol::place(&payload, cols, rows, area, buf);
+                }
+            }
+            ImageProtocol::Iterm2 | ImageProtocol::Sixel | ImageProtocol::Halfblocks => {
+                self.draw_halfblocks(url, image, (cols, rows), skip, area, buf);
+            }
+            ImageProtocol::Placeholder => {}
+        }
+    }
+
+    fn draw_halfblocks(
+        &mut self,
+        url: &str,
+        image: &DynamicImage,
+        (cols, rows): (u16, u16),
+        skip: u16,
+        area: Rect,
+        buf: &mut Buffer,
+    ) {
+        let fresh = self
+            .cells
+            .get(url)
+            .is_none_or(|(width, height, _)| (*width, *height) != (cols, rows));
+        if fresh {
+            let scaled = protocol::scale_for_halfblocks(image, cols, rows);
+            self.cells.insert(url.to_string(), (cols, rows, scaled));
+        }
+        if let Some((_, _, scaled)) = self.cells.get(url) {
+            protocol::halfblocks(scaled, skip, area, buf);
+        }
+    }
+
+    fn payload(
+        &mut self,
+        url: &str,
+        size: (u16, u16),
+        encode: impl FnOnce() -> String,
+    ) -> Arc<str> {
+        match self.payloads.get(url) {
+            Some((cols, rows, payload)) if (*cols, *rows) == size => payload.clone(),
+            _ => {
+                let payload: Arc<str> = encode().into();
+                self.payloads
+                    .insert(url.to_string(), (size.0, size.1, payload.clone()));
+                payload
+            }
+        }
+    }
+}
+
+fn is_web_url(url: &str) -> bool {
+    Url::parse(url).is_ok_and(|url| matches!(url.scheme(), "http" | "https"))
+}
+
+/// Downloaded images kept on disk so articles reopen without refetching.
+#[derive(Clone, Debug)]
+pub struct ImageCache {
+    dir: PathBuf,
+}
+
+impl Default for ImageCache {
+    fn default() -> Self {
+        Self::new(CACHE_DIR)
+    }
+}
+
+impl ImageCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn load(&self, url: &str) -> Option<Vec<u8>> {
+        fs::read(self.path_for(url)).ok()
+    }
+
+    pub fn store(&self, url: &str, content: &[u8]) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        fs::write(self.path_for(url), content)
+    }
+
+    fn path_for(&self, url: &str) -> PathBuf {
+        self.dir.join(format!("{:016x}.img", fnv1a(url.as_bytes())))
+    }
+}
+
+/// Fetches an image, from the cache when possible, and decodes it.
+pub async fn load_image(
+    url: &str,
+    config: &Config,
+    cache: &ImageCache,
+) -> Result<DynamicImage, ImageError> {
+    let content = match cache.load(url) {
+        Some(content) => content,
+        None => {
+            let content = download(url, config).await?;
+            let _ = cache.store(url, &content);
+            content
+        }
+    };
+    tokio::task::spawn_blocking(move || image::load_from_memory(&content))
+        .await
+        .map_err(|error| ImageError::Network(error.to_string()))?
+        .map_err(ImageError::Decode)
+}
+
+/// Loads the image in the background and reports back on `sender`.
+pub fn spawn_load(url: String, config: &Config, sender: mpsc::UnboundedSender<LoadedImage>) {
+    let config = config.clone();
+    tokio::spawn(async move {
+        let result = load_image(&url, &config, &ImageCache::default()).await;
+        let _ = sender.send(LoadedImage { url, result });
+    });
+}
+
+async fn download(url: &str, config: &Config) -> Result<Vec<u8>, ImageError> {
+    let url = Url::parse(url).map_err(|_| ImageError::UnsupportedUrl)?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(ImageError::UnsupportedUrl);
+    }
+    let client = client_builder(&config.network, &url, None)
+        .map_err(ImageError::Network)?
+        .timeout(REQUEST_TIMEOUT)
+        .build()?;
+    let response = client.get(url).send().await?;
+    if !response.status().is_success() {
+        return Err(ImageError::HttpStatus(response.status()));
+    }
+
+    let limit = config.images.max_size;
+    if response
+        .content_length()
+        .is_some_and(|length| length > limit)
+    {
+        return Err(ImageError::TooLarge { limit });
+    }
+    let mut content = Vec::new();
+    let mut stream = response.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk?;
+        if (content.len() + chunk.len()) as u64 > limit {
+            return Err(ImageError::TooLarge { limit });
+        }
+        content.extend_from_slice(&chunk);
+    }
+    Ok(content)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use image::{DynamicImage, ImageFormat, RgbaImage};
+
+    use crate::config::{Config, ImageProtocol, ImageSettings};
+    use crate::test_support::{MockResponse, MockServer};
+
+    use super::{load_image, ImageCache, ImageStore};
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let image = DynamicImage::ImageRgba8(RgbaImage::new(width, height));
+        let mut content = Cursor::new(Vec::new());
+        image.write_to(&mut content, ImageFormat::Png).unwrap();
+        content.into_inner()
+    }
+
+    #[tokio::test]
+    async fn downloads_images_once_and_serves_them_from_the_cache() {
+        let body = png(40, 20);
+        let server = MockServer::start(move |_| {
+            MockResponse::ok(body.clone()).header("Content-Type", "image/png")
+        })
+        .await;
+        let dir = std::env::temp_dir().join(format!("fead-images-{}", std::process::id()));
+        let cache = ImageCache::new(&dir);
+        let url = server.url("/chart.png");
+
+        let first = load_image(&url, &Config::default(), &cache).await.unwrap();
+        let second = load_image(&url, &Config::default(), &cache).await.unwrap();
+        assert_eq!((first.width(), first.height()), (40, 20));
+        assert_eq!((second.width(), second.height()), (40, 20));
+        assert_eq!(server.requests().len(), 1);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn sizes_images_to_fit_the_width_and_row_limit() {
+        let mut store = ImageStore::new(&ImageSettings {
+            protocol: Some(ImageProtocol::Halfblocks),
+            max_rows: 10,
+            ..ImageSettings::default()
+        });
+        store.cell = (10, 20);
+
+        let wide = DynamicImage::ImageRgba8(RgbaImage::new(400, 100));
+        assert_eq!(store.size_for(&wide, 80), (40, 5));
+        assert_eq!(store.size_for(&wide, 20), (20, 3));
+        let tall = DynamicImage::ImageRgba8(RgbaImage::new(100, 1000));
+        assert_eq!(store.size_for(&tall, 80), (2, 10));
+        assert!(store.get("file:///etc/passwd").is_some());
+        assert!(store.take_pending().is_empty());
+    }
+}
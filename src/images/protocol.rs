@@ -0,0 +1,265 @@
+use std::{env, fmt::Write as _, io::Cursor, num::NonZeroU16};
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use image::{imageops::FilterType, DynamicImage, ImageFormat, Rgba, RgbaImage};
+use ratatui::{
+    buffer::{Buffer, CellDiffOption},
+    layout::Rect,
+    style::Color,
+};
+
+use crate::config::ImageProtocol;
+
+/// Rows addressable by kitty's placeholder diacritics below.
+pub(super) const KITTY_MAX_ROWS: u16 = 30;
+
+const KITTY_PLACEHOLDER: char = '\u{10EEEE}';
+/// The first row and column diacritics from kitty's `rowcolumn-diacritics.txt`.
+const KITTY_DIACRITICS: [char; KITTY_MAX_ROWS as usize] = [
+    '\u{0305}', '\u{030D}', '\u{030E}', '\u{0310}', '\u{0312}', '\u{033D}', '\u{033E}', '\u{033F}',
+    '\u{0346}', '\u{034A}', '\u{034B}', '\u{034C}', '\u{0350}', '\u{0351}', '\u{0352}', '\u{0357}',
+    '\u{035B}', '\u{0363}', '\u{0364}', '\u{0365}', '\u{0366}', '\u{0367}', '\u{0368}', '\u{0369}',
+    '\u{036A}', '\u{036B}', '\u{036C}', '\u{036D}', '\u{036E}', '\u{036F}',
+];
+const KITTY_CHUNK: usize = 4096;
+
+/// Picks a graphics protocol from what the environment says about the
+/// terminal. Multiplexers get half blocks since they rarely pass graphics on.
+pub fn detect() -> ImageProtocol {
+    detect_from(|name| env::var(name).ok())
+}
+
+fn detect_from(var: impl Fn(&str) -> Option<String>) -> ImageProtocol {
+    let term = var("TERM").unwrap_or_default();
+    let program = var("TERM_PROGRAM").unwrap_or_default();
+    if var("TMUX").is_some() || term.starts_with("screen") {
+        ImageProtocol::Halfblocks
+    } else if var("KITTY_WINDOW_ID").is_some() || term.contains("kitty") || program == "ghostty" {
+        ImageProtocol::Kitty
+    } else if matches!(program.as_str(), "iTerm.app" | "WezTerm")
+        || var("LC_TERMINAL").as_deref() == Some("iTerm2")
+    {
+        ImageProtocol::Iterm2
+    } else if ["foot", "mlterm", "sixel"]
+        .iter()
+        .any(|name| term.contains(name))
+    {
+        ImageProtocol::Sixel
+    } else {
+        ImageProtocol::Halfblocks
+    }
+}
+
+/// Sends the image to kitty as a virtual placement `cols` × `rows` cells
+/// big, to be shown wherever placeholder cells with its id are printed.
+pub(super) fn kitty_transmission(
+    image: &DynamicImage,
+    id: u32,
+    cols: u16,
+    rows: u16,
+    cell: (u16, u16),
+) -> String {
+    let Some(png) = png(&fit(image, cols, rows, cell)) else {
+        return String::new();
+    };
+    let data = BASE64.encode(png);
+    let chunks: Vec<&[u8]> = data.as_bytes().chunks(KITTY_CHUNK).collect();
+    let mut sequence = String::new();
+    for (index, chunk) in chunks.iter().enumerate() {
+        let more = u8::from(index + 1 < chunks.len());
+        let chunk = std::str::from_utf8(chunk).unwrap_or_default();
+        if index == 0 {
+            let _ = write!(
+                sequence,
+                "\x1b_Ga=T,U=1,f=100,q=2,i={id},c={cols},r={rows},m={more};{chunk}\x1b\\"
+            );
+        } else {
+            let _ = write!(sequence, "\x1b_Gm={more};{chunk}\x1b\\");
+        }
+    }
+    sequence
+}
+
+/// Fills `area` with kitty placeholder cells for rows `skip..` of image `id`.
+/// The image id travels in the foreground colour; `transmission` is printed
+/// just before the first cell.
+pub(super) fn kitty_placeholders(
+    id: u32,
+    cols: u16,
+    skip: u16,
+    area: Rect,
+    buf: &mut Buffer,
+    transmission: Option<&str>,
+) {
+    let color = Color::Rgb((id >> 16) as u8, (id >> 8) as u8, id as u8);
+    for row in 0..area.height {
+        let Some(&row_mark) = KITTY_DIACRITICS.get(usize::from(skip + row)) else {
+            break;
+        };
+        for col in 0..cols.min(area.width) {
+            let mut symbol = String::new();
+            let first = row == 0 && col == 0;
+            if let Some(transmission) = transmission.filter(|_| first) {
+                symbol.push_str(transmission);
+            }
+            symbol.push(KITTY_PLACEHOLDER);
+            if col == 0 {
+                symbol.push(row_mark);
+                symbol.push(KITTY_DIACRITICS[0]);
+            }
+            let cell = &mut buf[(area.x + col, area.y + row)];
+            cell.set_symbol(&symbol).set_fg(color);
+            if first && transmission.is_some() {
+                cell.set_diff_option(CellDiffOption::ForcedWidth(NonZeroU16::MIN));
+            }
+        }
+    }
+}
+
+/// iTerm2's inline image sequence, also understood by WezTerm.
+pub(super) fn iterm2(image: &DynamicImage, cols: u16, rows: u16, cell: (u16, u16)) -> String {
+    let Some(png) = png(&fit(image, cols, rows, cell)) else {
+        return String::new();
+    };
+    format!(
+        "\x1b]1337;File=inline=1;size={};width={cols};height={rows};preserveAspectRatio=0:{}\x07",
+        png.len(),
+        BASE64.encode(&png)
+    )
+}
+
+/// The image as sixels, or an empty string when it cannot be encoded.
+pub(super) fn sixel(image: &DynamicImage, cols: u16, rows: u16, cell: (u16, u16)) -> String {
+    let (width, height) = pixels(cols, rows, cell);
+    let rgba = image
+        .resize_exact(width, height, FilterType::Triangle)
+        .into_rgba8();
+    icy_sixel::SixelImage::from_rgba(rgba.into_raw(), width as usize, height as usize)
+        .encode()
+        .unwrap_or_default()
+}
+
+/// Prints an escape sequence that draws an image from the top-left cell of
+/// `area`, keeping the rest of the buffer from drawing over it.
+pub(super) fn place(payload: &str, cols: u16, rows: u16, area: Rect, buf: &mut Buffer) {
+    for row in 0..rows.min(area.height) {
+        for col in 0..cols.min(area.width) {
+            let cell = &mut buf[(area.x + col, area.y + row)];
+            if row == 0 && col == 0 {
+                cell.set_symbol(payload)
+                    .set_diff_option(CellDiffOption::ForcedWidth(NonZeroU16::MIN));
+            } else {
+                cell.set_diff_option(CellDiffOption::Skip);
+            }
+        }
+    }
+}
+
+/// The image at one pixel per column and two per row.
+pub(super) fn scale_for_halfblocks(image: &DynamicImage, cols: u16, rows: u16) -> RgbaImage {
+    image
+        .resize_exact(u32::from(cols), u32::from(rows) * 2, FilterType::Triangle)
+        .into_rgba8()
+}
+
+/// Draws rows `skip..` of an image from [`scale_for_halfblocks`] with upper
+/// half blocks: the top pixel in the foreground, the bottom one behind it.
+pub(super) fn halfblocks(image: &RgbaImage, skip: u16, area: Rect, buf: &mut Buffer) {
+    let cols = image.width().min(u32::from(area.width));
+    for row in 0..area.height {
+        let y = (u32::from(skip) + u32::from(row)) * 2;
+        if y + 1 >= image.height() {
+            break;
+        }
+        for col in 0..cols {
+            let top = color(image.get_pixel(col, y));
+            let bottom = color(image.get_pixel(col, y + 1));
+            buf[(area.x + col as u16, area.y + row)]
+                .set_symbol("▀")
+                .set_fg(top)
+                .set_bg(bottom);
+        }
+    }
+}
+
+/// The pixel over black, since cells have no transparency.
+fn color(&Rgba([red, green, blue, alpha]): &Rgba<u8>) -> Color {
+    let blend = |channel: u8| (u16::from(channel) * u16::from(alpha) / 255) as u8;
+    Color::Rgb(blend(red), blend(green), blend(blue))
+}
+
+fn pixels(cols: u16, rows: u16, (cell_width, cell_height): (u16, u16)) -> (u32, u32) {
+    (
+        u32::from(cols) * u32::from(cell_width),
+        u32::from(rows) * u32::from(cell_height),
+    )
+}
+
+/// Scales the image down to the pixels it will cover, so large pictures are
+/// not sent to the terminal at full size.
+fn fit(image: &DynamicImage, cols: u16, rows: u16, cell: (u16, u16)) -> DynamicImage {
+    let (width, height) = pixels(cols, rows, cell);
+    if image.width() <= width && image.height() <= height {
+        image.clone()
+    } else {
+        image.resize_exact(width, height, FilterType::Triangle)
+    }
+}
+
+fn png(image: &DynamicImage) -> Option<Vec<u8>> {
+    let mut content = Cursor::new(Vec::new());
+    image.write_to(&mut content, ImageFormat::Png).ok()?;
+    Some(content.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{Rgba, RgbaImage};
+    use ratatui::{buffer::Buffer, layout::Rect, style::Color};
+
+    use crate::config::ImageProtocol;
+
+    use super::{detect_from, halfblocks, kitty_placeholders, KITTY_PLACEHOLDER};
+
+    #[test]
+    fn detects_the_protocol_from_the_environment() {
+        let detect = |vars: &[(&str, &str)]| {
+            detect_from(|name| {
+                vars.iter()
+                    .find(|(key, _)| *key == name)
+                    .map(|(_, value)| value.to_string())
+            })
+        };
+        assert_eq!(detect(&[("TERM", "xterm-kitty")]), ImageProtocol::Kitty);
+        assert_eq!(
+            detect(&[("TERM_PROGRAM", "WezTerm")]),
+            ImageProtocol::Iterm2
+        );
+        assert_eq!(detect(&[("TERM", "foot")]), ImageProtocol::Sixel);
+        assert_eq!(
+            detect(&[("TERM", "xterm-kitty"), ("TMUX", "/tmp/tmux")]),
+            ImageProtocol::Halfblocks
+        );
+        assert_eq!(detect(&[]), ImageProtocol::Halfblocks);
+    }
+
+    #[test]
+    fn draws_half_blocks_and_kitty_placeholders() {
+        let mut image = RgbaImage::new(1, 4);
+        image.put_pixel(0, 2, Rgba([255, 0, 0, 255]));
+        image.put_pixel(0, 3, Rgba([0, 0, 255, 255]));
+        let mut buf = Buffer::empty(Rect::new(0, 0, 2, 1));
+        halfblocks(&image, 1, buf.area, &mut buf);
+        assert_eq!(buf[(0, 0)].symbol(), "▀");
+        assert_eq!(buf[(0, 0)].fg, Color::Rgb(255, 0, 0));
+        assert_eq!(buf[(0, 0)].bg, Color::Rgb(0, 0, 255));
+
+        kitty_placeholders(0x01_02_03, 2, 1, buf.area, &mut buf, None);
+        assert_eq!(
+            buf[(0, 0)].symbol(),
+            format!("{KITTY_PLACEHOLDER}\u{030D}\u{0305}")
+        );
+        assert_eq!(buf[(1, 0)].symbol(), KITTY_PLACEHOLDER.to_string());
+        assert_eq!(buf[(1, 0)].fg, Color::Rgb(1, 2, 3));
+    }
+}
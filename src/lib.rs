@@ -1,8 +1,22 @@
 pub mod app;
+pub mod article;
+pub mod cli;
+pub mod config;
+pub mod daemon;
 pub mod event;
+pub mod export;
+pub mod external;
 pub mod feed;
 pub mod handler;
+pub mod http;
+pub mod images;
+pub mod notify;
+pub mod read_later;
 pub mod screen;
+pub mod sync;
+#[cfg(test)]
+mod test_support;
 pub mod tts;
 pub mod tui;
 pub mod ui;
+pub mod websub;
@@ -1,9 +1,18 @@
-use std::io;
+use std::io::{self, Stderr};
 
 use fead::app::{App, AppResult};
+use fead::cli::{parse_args, Command};
+use fead::config::load_config;
+use fead::daemon::{self, client::DaemonClient};
 use fead::event::{Event, EventHandler};
+use fead::export::{markdown, Article};
+use fead::external::Viewer;
+use fead::feed::fetch::{fetch_content, read_stdin};
+use fead::feed::reader::parse_feed;
 use fead::handler::{handle_key_events, handle_narration_event, handle_tts_model_event};
-use fead::tts::{spawn_narration, TtsModelEvent};
+use fead::images::{self, LoadedImage};
+use fead::sync::sync_once;
+use fead::tts::{spawn_narration, spawn_remote_narration, TtsModelEvent};
 use fead::tui::Tui;
 use ratatui::backend::CrosstermBackend;
 use ratatui::Terminal;
@@ -11,21 +20,70 @@ use tokio::sync::mpsc;
 
 #[tokio::main]
 async fn main() -> AppResult<()> {
+    let command = parse_args(std::env::args().skip(1))?;
+    match command {
+        Command::Daemon => return daemon::run(load_config()?).await,
+        Command::Control { method, params } => {
+            let socket = load_config()?.daemon.socket;
+            let mut client = DaemonClient::connect(&socket)
+                .await
+                .map_err(|error| format!("no daemon listening on {}: {error}", socket.display()))?;
+            let result = client.call(method, params).await?;
+            println!("{}", serde_json::to_string_pretty(&result)?);
+            return Ok(());
+        }
+        Command::Sync => {
+            let config = load_config()?;
+            if DaemonClient::connect(&config.daemon.socket).await.is_ok() {
+                return Err("the daemon is running; use `fead ctl sync` instead".into());
+            }
+            println!("{}", sync_once(&config).await?);
+            return Ok(());
+        }
+        Command::Browse | Command::Read(_) => {}
+    }
+
     let mut app = App::new()?;
+    if let Command::Read(source) = command {
+        let content = if source == "-" {
+            read_stdin(app.config.network.max_feed_size).await?
+        } else {
+            fetch_content(&source, &app.config).await?.content
+        };
+        app.open_document(parse_feed(&content)?);
+    }
 
     let backend = CrosstermBackend::new(io::stderr());
     let terminal = Terminal::new(backend)?;
+    app.resize(terminal.size()?.width);
     let events = EventHandler::new();
     let mut tui = Tui::new(terminal, events);
     tui.init()?;
 
     let (model_tx, mut model_rx) = mpsc::unbounded_channel::<TtsModelEvent>();
-    let (narration, mut narration_rx) = spawn_narration();
+    let (narration, mut narration_rx) =
+        match spawn_remote_narration(&app.config.daemon.socket).await {
+            Ok(remote) => {
+                app.daemon_attached = true;
+                remote
+            }
+            Err(_) => spawn_narration(),
+        };
+
+    let (image_tx, mut image_rx) = mpsc::unbounded_channel::<LoadedImage>();
 
     let run_result = async {
         while app.running {
             tui.draw(&mut app)?;
+            for url in app.images.take_pending() {
+                images::spawn_load(url, &app.config, image_tx.clone());
+            }
             tokio::select! {
+                loaded = image_rx.recv() => {
+                    if let Some(loaded) = loaded {
+                        app.images.finish(loaded);
+                    }
+                }
                 event = model_rx.recv() => {
                     if let Some(event) = event {
                         handle_tts_model_event(&mut app, event, &narration);
@@ -38,9 +96,13 @@ async fn main() -> AppResult<()> {
                 }
                 event = tui.events.next() => {
                     match event? {
-                        Event::Mouse(_) | Event::Resize(_, _) => {}
+                        Event::Mouse(_) => {}
+                        Event::Resize(width, _) => app.resize(width),
                         Event::Key(key_event) => {
                             handle_key_events(key_event, &mut app, &model_tx, &narration).await?;
+                            if let Some(viewer) = app.external_viewer.take() {
+                                view_externally(&mut tui, &mut app, viewer).await?;
+                            }
                         }
                     }
                 }
@@ -57,3 +119,32 @@ async fn main() -> AppResult<()> {
 
     Ok(())
 }
+
+/// Suspends the TUI while a pager or editor shows the open article. The
+/// article's scroll position is left as it was.
+async fn view_externally(
+    tui: &mut Tui<CrosstermBackend<Stderr>>,
+    app: &mut App,
+    viewer: Viewer,
+) -> AppResult<()> {
+    let source = app
+        .current_feed
+        .as_ref()
+        .map_or("", |feed| feed.title.as_str());
+    let text = markdown(Article {
+        entry: &app.current_entry,
+        source,
+    });
+
+    tui.suspend().await?;
+    let result = viewer.open(&text).await;
+    tui.resume()?;
+    app.images.reset_terminal();
+
+    match result {
+        Ok(status) if status.success() => {}
+        Ok(status) => app.show_error(format!("`{}` exited with {status}.", viewer.command())),
+        Err(error) => app.show_error(format!("Could not run `{}`: {error}.", viewer.command())),
+    }
+    Ok(())
+}
@@ -0,0 +1,207 @@
+use std::{collections::HashMap, error::Error, fmt};
+
+use serde::Serialize;
+use zbus::{zvariant, Connection};
+
+use crate::config::Config;
+use crate::feed::{
+    command::{run_command, COMMAND_TIMEOUT},
+    entries::Entry,
+    fetch::FetchError,
+};
+
+const APP_NAME: &str = "fead";
+const NOTIFICATIONS_SERVICE: &str = "org.freedesktop.Notifications";
+const NOTIFICATIONS_PATH: &str = "/org/freedesktop/Notifications";
+/// Titles listed in a notification before the rest are summarized.
+const MAX_LISTED_TITLES: usize = 5;
+/// Lets the notification server pick how long to show it.
+const DEFAULT_EXPIRY: i32 = -1;
+
+#[derive(Debug)]
+pub enum NotifyError {
+    Desktop(zbus::Error),
+    Hook { command: String, error: FetchError },
+}
+
+impl fmt::Display for NotifyError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Desktop(error) => write!(formatter, "desktop notification failed: {error}"),
+            Self::Hook { command, error } => write!(formatter, "hook `{command}` failed: {error}"),
+        }
+    }
+}
+
+impl Error for NotifyError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Desktop(error) => Some(error),
+            Self::Hook { error, .. } => Some(error),
+        }
+    }
+}
+
+/// What hook commands read on stdin.
+#[derive(Clone, Debug, Serialize)]
+pub struct NewEntries<'a> {
+    pub feed: &'a str,
+    pub title: &'a str,
+    pub entries: &'a [Entry],
+}
+
+/// Runs the configured actions for entries a refresh or push just found:
+/// a desktop notification when enabled for the feed, then the global hooks
+/// followed by the feed's own. Every action runs even if an earlier one fails.
+pub async fn announce(config: &Config, new: &NewEntries<'_>) -> Vec<NotifyError> {
+    if new.entries.is_empty() {
+        return Vec::new();
+    }
+    let settings = config.feed(new.feed);
+    let mut errors = Vec::new();
+
+    if settings.notify.unwrap_or(config.notifications.desktop) {
+        let (summary, body) = describe(new);
+        if let Err(error) = show_desktop_notification(&summary, &body).await {
+            errors.push(NotifyError::Desktop(error));
+        }
+    }
+
+    let hooks = config.notifications.hooks.iter().chain(&settings.hooks);
+    for command in hooks {
+        if let Err(error) = run_hook(command, new).await {
+            errors.push(NotifyError::Hook {
+                command: command.clone(),
+                error,
+            });
+        }
+    }
+    errors
+}
+
+pub async fn run_hook(command: &str, new: &NewEntries<'_>) -> Result<(), FetchError> {
+    let input = serde_json::to_vec(new).map_err(|error| FetchError::Command(error.into()))?;
+    run_command(command, Some(&input), COMMAND_TIMEOUT).await?;
+    Ok(())
+}
+
+/// The notification's summary and body: the entry title for a single entry,
+/// otherwise a count with the first few titles.
+fn describe(new: &NewEntries<'_>) -> (String, String) {
+    let feed = if new.title.is_empty() {
+        new.feed
+    } else {
+        new.title
+    };
+    if let [entry] = new.entries {
+        return (feed.to_string(), entry.title.clone());
+    }
+
+    let summary = format!("{} new entries in {feed}", new.entries.len());
+    let mut lines: Vec<String> = new
+        .entries
+        .iter()
+        .take(MAX_LISTED_TITLES)
+        .map(|entry| entry.title.clone())
+        .collect();
+    if new.entries.len() > MAX_LISTED_TITLES {
+        lines.push(format!(
+            "…and {} more",
+            new.entries.len() - MAX_LISTED_TITLES
+        ));
+    }
+    (summary, lines.join("\n"))
+}
+
+/// Calls `Notify` on the session bus, as specified by the freedesktop.org
+/// Desktop Notifications interface.
+async fn show_desktop_notification(summary: &str, body: &str) -> Result<(), zbus::Error> {
+    let connection = Connection::session().await?;
+    let actions: Vec<&str> = Vec::new();
+    let hints: HashMap<&str, zvariant::Value<'_>> = HashMap::new();
+    connection
+        .call_method(
+            Some(NOTIFICATIONS_SERVICE),
+            NOTIFICATIONS_PATH,
+            Some(NOTIFICATIONS_SERVICE),
+            "Notify",
+            &(
+                APP_NAME,
+                0u32,
+                "",
+                summary,
+                body,
+                actions,
+                hints,
+                DEFAULT_EXPIRY,
+            ),
+        )
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::Config;
+    use crate::feed::entries::Entry;
+
+    use super::{announce, describe, NewEntries, NotifyError};
+
+    fn entry(title: &str) -> Entry {
+        Entry {
+            id: Some(title.to_lowercase()),
+            title: title.to_string(),
+            ..Entry::default()
+        }
+    }
+
+    #[test]
+    fn summarizes_several_entries_in_one_notification() {
+        let entries: Vec<Entry> = (1..=7).map(|n| entry(&format!("Post {n}"))).collect();
+        let (summary, body) = describe(&NewEntries {
+            feed: "https://example.com/feed.xml",
+            title: "Example",
+            entries: &entries,
+        });
+        assert_eq!(summary, "7 new entries in Example");
+        assert_eq!(body.lines().count(), 6);
+        assert!(body.ends_with("…and 2 more"));
+
+        let (summary, body) = describe(&NewEntries {
+            feed: "https://example.com/feed.xml",
+            title: "",
+            entries: &entries[..1],
+        });
+        assert_eq!(summary, "https://example.com/feed.xml");
+        assert_eq!(body, "Post 1");
+    }
+
+    #[tokio::test]
+    async fn runs_global_and_feed_hooks_with_entries_on_stdin() {
+        let output = std::env::temp_dir().join(format!("fead-hook-{}.json", std::process::id()));
+        let url = "https://example.com/feed.xml";
+        let config: Config = serde_json::from_value(serde_json::json!({
+            "notifications": { "hooks": [format!("cat > {}", output.display())] },
+            "feeds": { url: { "hooks": ["exit 3"] } }
+        }))
+        .unwrap();
+        let entries = [entry("Hello")];
+
+        let errors = announce(
+            &config,
+            &NewEntries {
+                feed: url,
+                title: "Example",
+                entries: &entries,
+            },
+        )
+        .await;
+
+        assert!(matches!(&errors[..], [NotifyError::Hook { command, .. }] if command == "exit 3"));
+        let written: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(&output).unwrap()).unwrap();
+        assert_eq!(written["feed"], url);
+        assert_eq!(written["entries"][0]["title"], "Hello");
+        let _ = std::fs::remove_file(output);
+    }
+}
@@ -0,0 +1,362 @@
+use std::{error::Error, fmt};
+
+use reqwest::{header::CONTENT_TYPE, RequestBuilder, StatusCode, Url};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::config::{NetworkSettings, ReadLaterService, ReadLaterSettings, Secret};
+use crate::http::{api_client, base_url, check, endpoint, ApiError};
+
+#[derive(Debug)]
+pub enum ReadLaterError {
+    NotConfigured,
+    /// Several services are configured and none is the default.
+    NoDefault,
+    UnknownService(String),
+    InvalidUrl(String),
+    Credentials(String),
+    /// The HTTP client could not be set up from the network settings.
+    Client(String),
+    Unauthorized,
+    Request(reqwest::Error),
+    HttpStatus(StatusCode),
+    InvalidResponse(String),
+}
+
+impl fmt::Display for ReadLaterError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotConfigured => formatter.write_str("no read-later service is configured"),
+            Self::NoDefault => formatter.write_str("set read_later.default to pick a service"),
+            Self::UnknownService(name) => write!(formatter, "no read-later service named {name}"),
+            Self::InvalidUrl(url) => write!(formatter, "invalid service URL {url}"),
+            Self::Credentials(message) => write!(formatter, "service credentials: {message}"),
+            Self::Client(message) => {
+                write!(formatter, "could not set up the HTTP client: {message}")
+            }
+            Self::Unauthorized => formatter.write_str("the service rejected the credentials"),
+            Self::Request(error) => write!(formatter, "request failed: {error}"),
+            Self::HttpStatus(status) => write!(formatter, "the service answered {status}"),
+            Self::InvalidResponse(message) => {
+                write!(formatter, "unexpected service response: {message}")
+            }
+        }
+    }
+}
+
+impl Error for ReadLaterError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Request(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for ReadLaterError {
+    fn from(error: reqwest::Error) -> Self {
+        Self::Request(error)
+    }
+}
+
+impl From<ApiError> for ReadLaterError {
+    fn from(error: ApiError) -> Self {
+        match error {
+            ApiError::InvalidUrl(url) => Self::InvalidUrl(url),
+            ApiError::Client(message) => Self::Client(message),
+            ApiError::Unauthorized => Self::Unauthorized,
+            ApiError::Request(error) => Self::Request(error),
+            ApiError::HttpStatus(status) => Self::HttpStatus(status),
+        }
+    }
+}
+
+/// What gets saved: the entry's link and title and the feed it came from.
+#[derive(Clone, Copy, Debug)]
+pub struct Bookmark<'a> {
+    pub url: &'a str,
+    pub title: &'a str,
+    pub feed: &'a str,
+}
+
+/// Picks the default service, or the only one configured.
+pub fn default_service(
+    settings: &ReadLaterSettings,
+) -> Result<(&str, &ReadLaterService), ReadLaterError> {
+    if let Some(name) = &settings.default {
+        return settings
+            .services
+            .get_key_value(name)
+            .map(|(name, service)| (name.as_str(), service))
+            .ok_or_else(|| ReadLaterError::UnknownService(name.clone()));
+    }
+    let mut services = settings.services.iter();
+    match (services.next(), services.next()) {
+        (Some((name, service)), None) => Ok((name.as_str(), service)),
+        (None, _) => Err(ReadLaterError::NotConfigured),
+        (Some(_), Some(_)) => Err(ReadLaterError::NoDefault),
+    }
+}
+
+/// Saves the bookmark to the service.
+pub async fn send(
+    service: &ReadLaterService,
+    bookmark: Bookmark<'_>,
+    network: &NetworkSettings,
+) -> Result<(), ReadLaterError> {
+    match service {
+        ReadLaterService::Wallabag {
+            url,
+            client_id,
+            client_secret,
+            username,
+            password,
+        } => {
+            let base = base_url(url)?;
+            let client = api_client(network, &base)?;
+            let form = [
+                ("grant_type", "password"),
+                ("client_id", client_id.as_str()),
+                ("client_secret", &resolve(client_secret).await?),
+                ("username", username.as_str()),
+                ("password", &resolve(password).await?),
+            ];
+            let response = client
+                .post(endpoint(&base, "oauth/v2/token")?)
+                .form(&form)
+                .send()
+                .await?;
+            let body = check(response)?.bytes().await?;
+            let token: WallabagToken = serde_json::from_slice(&body)
+                .map_err(|error| ReadLaterError::InvalidResponse(error.to_string()))?;
+
+            let request = client
+                .post(endpoint(&base, "api/entries.json")?)
+                .bearer_auth(token.access_token)
+                .form(&[("url", bookmark.url), ("title", bookmark.title)]);
+            send_request(request).await
+        }
+        ReadLaterService::Linkding { url, token } => {
+            let base = base_url(url)?;
+            let request = api_client(network, &base)?
+                .post(endpoint(&base, "api/bookmarks/")?)
+                .header("Authorization", format!("Token {}", resolve(token).await?));
+            let request = with_json(
+                request,
+                json!({ "url": bookmark.url, "title": bookmark.title }),
+            );
+            send_request(request).await
+        }
+        ReadLaterService::Pocket {
+            url,
+            consumer_key,
+            access_token,
+        } => {
+            let base = base_url(url)?;
+            let request = api_client(network, &base)?.post(endpoint(&base, "v3/add")?);
+            let request = with_json(
+                request,
+                json!({
+                    "url": bookmark.url,
+                    "title": bookmark.title,
+                    "consumer_key": resolve(consumer_key).await?,
+                    "access_token": resolve(access_token).await?,
+                }),
+            );
+            send_request(request).await
+        }
+        ReadLaterService::Http {
+            url,
+            headers,
+            body,
+            content_type,
+        } => {
+            let url = fill_template(url, bookmark, Encoding::Form);
+            let target = Url::parse(&url).map_err(|_| ReadLaterError::InvalidUrl(url.clone()))?;
+            let mut request = api_client(network, &target)?
+                .post(target)
+                .header(CONTENT_TYPE, content_type.as_str())
+                .body(fill_template(
+                    body,
+                    bookmark,
+                    Encoding::for_type(content_type),
+                ));
+            for (name, value) in headers {
+                request = request.header(name.as_str(), resolve(value).await?);
+            }
+            send_request(request).await
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Encoding {
+    Json,
+    Form,
+    Raw,
+}
+
+impl Encoding {
+    fn for_type(content_type: &str) -> Self {
+        if content_type.contains("json") {
+            Self::Json
+        } else if content_type.contains("x-www-form-urlencoded") {
+            Self::Form
+        } else {
+            Self::Raw
+        }
+    }
+
+    fn encode(self, value: &str) -> String {
+        match self {
+            // The template supplies the quotes.
+            Self::Json => {
+                let quoted = Value::from(value).to_string();
+                quoted[1..quoted.len() - 1].to_string()
+            }
+            Self::Form => form_urlencoded::byte_serialize(value.as_bytes()).collect(),
+            Self::Raw => value.to_string(),
+        }
+    }
+}
+
+fn fill_template(template: &str, bookmark: Bookmark<'_>, encoding: Encoding) -> String {
+    template
+        .replace("{url}", &encoding.encode(bookmark.url))
+        .replace("{title}", &encoding.encode(bookmark.title))
+        .replace("{feed}", &encoding.encode(bookmark.feed))
+}
+
+#[derive(Deserialize)]
+struct WallabagToken {
+    access_token: String,
+}
+
+async fn resolve(secret: &Secret) -> Result<String, ReadLaterError> {
+    secret.resolve().await.map_err(ReadLaterError::Credentials)
+}
+
+fn with_json(request: RequestBuilder, body: Value) -> RequestBuilder {
+    request
+        .header(CONTENT_TYPE, "application/json")
+        .body(body.to_string())
+}
+
+async fn send_request(request: RequestBuilder) -> Result<(), ReadLaterError> {
+    check(request.send().await?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use crate::config::{NetworkSettings, ReadLaterService, ReadLaterSettings, Secret};
+    use crate::test_support::{MockResponse, MockServer};
+
+    use super::{default_service, send, Bookmark, ReadLaterError};
+
+    const BOOKMARK: Bookmark<'static> = Bookmark {
+        url: "https://example.com/post?a=1&b=2",
+        title: "Say \"hello\"",
+        feed: "Example",
+    };
+
+    #[tokio::test]
+    async fn posts_bookmarks_to_each_kind_of_service() {
+        let server = MockServer::start(|request| {
+            let body = String::from_utf8_lossy(&request.body);
+            match (request.path.as_str(), request.header("authorization")) {
+                ("/wallabag/oauth/v2/token", _) if body.contains("password=hunter2") => {
+                    MockResponse::ok(r#"{"access_token": "wb-token", "token_type": "bearer"}"#)
+                }
+                ("/wallabag/api/entries.json", Some("Bearer wb-token")) => MockResponse::ok("{}"),
+                ("/linkding/api/bookmarks/", Some("Token ld-token")) => {
+                    MockResponse::status(201).body("{}")
+                }
+                ("/hook", Some("Bearer hook-token")) => MockResponse::ok(""),
+                _ => MockResponse::status(401),
+            }
+        })
+        .await;
+        let network = NetworkSettings::default();
+
+        let wallabag = ReadLaterService::Wallabag {
+            url: server.url("/wallabag"),
+            client_id: "client".to_string(),
+            client_secret: Secret::Value("client-secret".to_string()),
+            username: "reader".to_string(),
+            password: Secret::Value("hunter2".to_string()),
+        };
+        send(&wallabag, BOOKMARK, &network).await.unwrap();
+
+        let linkding = ReadLaterService::Linkding {
+            url: server.url("/linkding/"),
+            token: Secret::Value("ld-token".to_string()),
+        };
+        send(&linkding, BOOKMARK, &network).await.unwrap();
+
+        let hook = ReadLaterService::Http {
+            url: server.url("/hook"),
+            headers: BTreeMap::from([(
+                "Authorization".to_string(),
+                Secret::Value("Bearer hook-token".to_string()),
+            )]),
+            body: r#"{"link": "{url}", "name": "{title} ({feed})"}"#.to_string(),
+            content_type: "application/json".to_string(),
+        };
+        send(&hook, BOOKMARK, &network).await.unwrap();
+
+        let requests = server.requests();
+        let saved = String::from_utf8_lossy(&requests[1].body).to_string();
+        assert!(saved.contains("url=https%3A%2F%2Fexample.com%2Fpost%3Fa%3D1%26b%3D2"));
+        let linked: serde_json::Value = serde_json::from_slice(&requests[2].body).unwrap();
+        assert_eq!(linked["title"], "Say \"hello\"");
+        let hooked: serde_json::Value = serde_json::from_slice(&requests[3].body).unwrap();
+        assert_eq!(hooked["link"], BOOKMARK.url);
+        assert_eq!(hooked["name"], "Say \"hello\" (Example)");
+
+        let rejected = ReadLaterService::Linkding {
+            url: server.url("/linkding/"),
+            token: Secret::Value("wrong".to_string()),
+        };
+        assert!(matches!(
+            send(&rejected, BOOKMARK, &network).await,
+            Err(ReadLaterError::Unauthorized)
+        ));
+
+        let broken = NetworkSettings {
+            ca_certificates: vec!["/nonexistent/ca.pem".into()],
+            ..NetworkSettings::default()
+        };
+        let error = send(&linkding, BOOKMARK, &broken).await.unwrap_err();
+        assert!(matches!(error, ReadLaterError::Client(_)));
+        assert!(error
+            .to_string()
+            .starts_with("could not set up the HTTP client"));
+    }
+
+    #[test]
+    fn picks_the_default_or_only_service() {
+        let linkding = || ReadLaterService::Linkding {
+            url: "https://links.example.com".to_string(),
+            token: Secret::Value("token".to_string()),
+        };
+        let mut settings = ReadLaterSettings::default();
+        assert!(matches!(
+            default_service(&settings),
+            Err(ReadLaterError::NotConfigured)
+        ));
+
+        settings.services.insert("links".to_string(), linkding());
+        assert_eq!(default_service(&settings).unwrap().0, "links");
+
+        settings.services.insert("work".to_string(), linkding());
+        assert!(matches!(
+            default_service(&settings),
+            Err(ReadLaterError::NoDefault)
+        ));
+        settings.default = Some("work".to_string());
+        assert_eq!(default_service(&settings).unwrap().0, "work");
+    }
+}
@@ -4,3 +4,30 @@ pub enum Screen {
     Feed,
     Article,
 }
+
+/// How many screens are drawn side by side, picked from the terminal width.
+/// When split, `Screen` names the pane that has focus.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Panes {
+    /// One screen at a time.
+    #[default]
+    Single,
+    /// The focused list beside the pane to its right.
+    Two,
+    /// Feeds, articles and the article preview.
+    Three,
+}
+
+impl Panes {
+    pub fn for_width(width: u16) -> Self {
+        match width {
+            0..100 => Self::Single,
+            100..160 => Self::Two,
+            _ => Self::Three,
+        }
+    }
+
+    pub fn is_split(self) -> bool {
+        self != Self::Single
+    }
+}
@@ -0,0 +1,326 @@
+use chrono::DateTime;
+use reqwest::{Client, Url};
+use serde::{de::DeserializeOwned, Deserialize, Deserializer};
+use serde_json::Value;
+
+use crate::config::{NetworkSettings, SyncSettings};
+use crate::feed::entries::{ContentKind, Entry, EntryContent};
+
+use crate::http::{api_client, check};
+
+use super::{Change, Flag, RemoteItem, Snapshot, Subscription, SyncBackend, SyncError, SyncFuture};
+
+/// Fever returns items older than `max_id`, newest first, so paging starts
+/// above any real id.
+const NEWEST: i64 = i64::MAX;
+
+/// A client for the Fever API. Fever has no calls for managing
+/// subscriptions, so those changes are reported as unsupported.
+pub struct Fever {
+    client: Client,
+    url: Url,
+    api_key: String,
+}
+
+impl Fever {
+    /// Checks the API key, the MD5 of `username:password`, against the server.
+    pub async fn login(
+        settings: &SyncSettings,
+        network: &NetworkSettings,
+    ) -> Result<Self, SyncError> {
+        let url =
+            Url::parse(&settings.url).map_err(|_| SyncError::InvalidUrl(settings.url.clone()))?;
+        let client = api_client(network, &url)?;
+        let password = match &settings.password {
+            Some(password) => password.resolve().await.map_err(SyncError::Credentials)?,
+            None => return Err(SyncError::Credentials("sync.password is not set".into())),
+        };
+        let api_key = format!(
+            "{:x}",
+            md5::compute(format!("{}:{password}", settings.username))
+        );
+        let fever = Self {
+            client,
+            url,
+            api_key,
+        };
+        fever.call::<Value>(&[], &[]).await?;
+        Ok(fever)
+    }
+
+    pub async fn snapshot(&self, max_items: usize) -> Result<Snapshot, SyncError> {
+        let feeds: FeedList = self.call(&["feeds"], &[]).await?;
+        let subscriptions = feeds
+            .feeds
+            .into_iter()
+            .map(|feed| Subscription {
+                id: feed.id,
+                title: feed.title,
+                url: feed.url,
+            })
+            .collect();
+
+        let mut items: Vec<RemoteItem> = Vec::new();
+        let mut max_id = NEWEST.to_string();
+        while items.len() < max_items {
+            let page: ItemList = self
+                .call(&["items"], &[("max_id", max_id.as_str())])
+                .await?;
+            let Some(oldest) = page
+                .items
+                .iter()
+                .filter_map(|item| item.id.parse::<i64>().ok())
+                .min()
+            else {
+                break;
+            };
+            items.extend(page.items.into_iter().map(RawItem::into_remote));
+            max_id = oldest.to_string();
+        }
+        items.truncate(max_items);
+
+        Ok(Snapshot {
+            subscriptions,
+            items,
+        })
+    }
+
+    /// Marks items one at a time, as the API requires.
+    pub async fn push(&self, changes: &[Change]) -> Result<(), SyncError> {
+        let mut first_error = None;
+        for change in changes {
+            let result = match change {
+                Change::Flag { item, flag, value } => {
+                    let state = match (flag, value) {
+                        (Flag::Read, true) => "read",
+                        (Flag::Read, false) => "unread",
+                        (Flag::Starred, true) => "saved",
+                        (Flag::Starred, false) => "unsaved",
+                    };
+                    self.call::<Value>(&[], &[("mark", "item"), ("as", state), ("id", item)])
+                        .await
+                        .map(drop)
+                }
+                Change::Subscribe { .. } => Err(SyncError::Unsupported("add subscriptions")),
+                Change::Unsubscribe { .. } => Err(SyncError::Unsupported("remove subscriptions")),
+            };
+            first_error = first_error.or(result.err());
+        }
+        first_error.map_or(Ok(()), Err)
+    }
+
+    /// Posts to `?api&<flags>` with the API key and `form`, checking that the
+    /// server accepted the key.
+    async fn call<T: DeserializeOwned>(
+        &self,
+        flags: &[&str],
+        form: &[(&str, &str)],
+    ) -> Result<T, SyncError> {
+        let mut url = self.url.clone();
+        let query = std::iter::once("api")
+            .chain(flags.iter().copied())
+            .collect::<Vec<_>>()
+            .join("&");
+        url.set_query(Some(&query));
+        let mut body = vec![("api_key", self.api_key.as_str())];
+        body.extend_from_slice(form);
+
+        let response = self.client.post(url).form(&body).send().await?;
+        let body = check(response)?.bytes().await?;
+        let reply: Value = serde_json::from_slice(&body)
+            .map_err(|error| SyncError::InvalidResponse(error.to_string()))?;
+        if !truthy(reply.get("auth").unwrap_or(&Value::Null)) {
+            return Err(SyncError::Unauthorized);
+        }
+        serde_json::from_value(reply).map_err(|error| SyncError::InvalidResponse(error.to_string()))
+    }
+}
+
+impl SyncBackend for Fever {
+    fn snapshot(&self, max_items: usize) -> SyncFuture<'_, Snapshot> {
+        Box::pin(self.snapshot(max_items))
+    }
+
+    fn push<'a>(&'a self, changes: &'a [Change]) -> SyncFuture<'a, ()> {
+        Box::pin(self.push(changes))
+    }
+
+    fn manages_subscriptions(&self) -> bool {
+        false
+    }
+}
+
+/// Servers disagree on whether ids and flags are numbers, strings or booleans.
+fn truthy(value: &Value) -> bool {
+    match value {
+        Value::Bool(value) => *value,
+        Value::Number(number) => number.as_i64().is_some_and(|number| number != 0),
+        Value::String(text) => !matches!(text.as_str(), "" | "0" | "false"),
+        _ => false,
+    }
+}
+
+fn flag<'de, D: Deserializer<'de>>(deserializer: D) -> Result<bool, D::Error> {
+    Ok(truthy(&Value::deserialize(deserializer)?))
+}
+
+fn id<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    Ok(match Value::deserialize(deserializer)? {
+        Value::String(id) => id,
+        other => other.to_string(),
+    })
+}
+
+#[derive(Deserialize)]
+struct FeedList {
+    #[serde(default)]
+    feeds: Vec<RawFeed>,
+}
+
+#[derive(Deserialize)]
+struct RawFeed {
+    #[serde(deserialize_with = "id")]
+    id: String,
+    #[serde(default)]
+    title: String,
+    url: String,
+}
+
+#[derive(Deserialize)]
+struct ItemList {
+    #[serde(default)]
+    items: Vec<RawItem>,
+}
+
+#[derive(Deserialize)]
+struct RawItem {
+    #[serde(deserialize_with = "id")]
+    id: String,
+    #[serde(deserialize_with = "id")]
+    feed_id: String,
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    html: String,
+    #[serde(default)]
+    author: String,
+    url: Option<String>,
+    #[serde(default, deserialize_with = "flag")]
+    is_read: bool,
+    #[serde(default, deserialize_with = "flag")]
+    is_saved: bool,
+    created_on_time: Option<i64>,
+}
+
+impl RawItem {
+    fn into_remote(self) -> RemoteItem {
+        let title = if self.title.trim().is_empty() {
+            "Untitled article".to_string()
+        } else {
+            self.title
+        };
+        RemoteItem {
+            feed: self.feed_id,
+            entry: Entry {
+                id: Some(self.id),
+                title,
+                link: self.url.filter(|url| !url.is_empty()),
+                author: (!self.author.trim().is_empty()).then_some(self.author),
+                summary: None,
+                content: (!self.html.trim().is_empty()).then_some(EntryContent {
+                    value: self.html,
+                    kind: ContentKind::Html,
+                }),
+                published: self
+                    .created_on_time
+                    .and_then(|seconds| DateTime::from_timestamp(seconds, 0))
+                    .map(|date| date.to_rfc3339()),
+            },
+            read: self.is_read,
+            starred: self.is_saved,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::{NetworkSettings, Secret, SyncSettings};
+    use crate::sync::{Change, Flag, SyncError};
+    use crate::test_support::{MockResponse, MockServer};
+
+    use super::Fever;
+
+    // md5("reader:secret")
+    const API_KEY: &str = "api_key=d86e2552797d076a6178f7b038ad6b69";
+
+    #[tokio::test]
+    async fn pages_items_and_marks_them_through_the_fever_api() {
+        let server = MockServer::start(|request| {
+            let body = String::from_utf8_lossy(&request.body);
+            if !body.starts_with(API_KEY) {
+                return MockResponse::ok(r#"{"api_version": 3, "auth": 0}"#);
+            }
+            let reply = match request.path.split_once('?').map(|(_, query)| query) {
+                Some("api&feeds") => {
+                    r#"{"auth": 1, "feeds": [{"id": 7, "title": "Example", "url": "https://example.com/feed.xml"}]}"#
+                }
+                Some("api&items") if body.contains("max_id=9223372036854775807") => {
+                    r#"{"auth": 1, "items": [
+                        {"id": 31, "feed_id": 7, "title": "Newer", "html": "<p>2</p>", "is_read": 1, "is_saved": 0, "created_on_time": 1752517200},
+                        {"id": "30", "feed_id": "7", "title": "Older", "html": "", "is_read": 0, "is_saved": 1}
+                    ]}"#
+                }
+                Some("api&items") => r#"{"auth": 1, "items": []}"#,
+                _ => r#"{"auth": 1}"#,
+            };
+            MockResponse::ok(reply)
+        })
+        .await;
+        let settings = SyncSettings {
+            url: server.url("/fever/"),
+            username: "reader".to_string(),
+            password: Some(Secret::Value("secret".to_string())),
+            ..SyncSettings::default()
+        };
+
+        let fever = Fever::login(&settings, &NetworkSettings::default())
+            .await
+            .unwrap();
+        let snapshot = fever.snapshot(100).await.unwrap();
+        assert_eq!(snapshot.subscriptions[0].id, "7");
+        let titles: Vec<_> = snapshot
+            .items
+            .iter()
+            .map(|item| (item.entry.title.as_str(), item.read, item.starred))
+            .collect();
+        assert_eq!(titles, [("Newer", true, false), ("Older", false, true)]);
+        assert_eq!(snapshot.items[1].feed, "7");
+
+        let result = fever
+            .push(&[
+                Change::Flag {
+                    item: "30".to_string(),
+                    flag: Flag::Read,
+                    value: true,
+                },
+                Change::Subscribe {
+                    url: "https://other.example/feed.xml".to_string(),
+                    title: "Other".to_string(),
+                },
+            ])
+            .await;
+        assert!(matches!(result, Err(SyncError::Unsupported(_))));
+        assert!(server.requests().iter().any(|request| {
+            String::from_utf8_lossy(&request.body).ends_with("mark=item&as=read&id=30")
+        }));
+
+        let wrong = SyncSettings {
+            password: Some(Secret::Value("wrong".to_string())),
+            ..settings
+        };
+        assert!(matches!(
+            Fever::login(&wrong, &NetworkSettings::default()).await,
+            Err(SyncError::Unauthorized)
+        ));
+    }
+}
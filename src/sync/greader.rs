@@ -0,0 +1,420 @@
+use chrono::DateTime;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::Deserialize;
+
+use crate::config::{NetworkSettings, SyncSettings};
+use crate::feed::entries::{ContentKind, Entry, EntryContent};
+
+use crate::http::{api_client, base_url, check, endpoint};
+
+use super::{Change, Flag, RemoteItem, Snapshot, Subscription, SyncBackend, SyncError, SyncFuture};
+
+const READING_LIST: &str = "user/-/state/com.google/reading-list";
+const READ_TAG: &str = "user/-/state/com.google/read";
+const STARRED_TAG: &str = "user/-/state/com.google/starred";
+/// Items requested per page of stream contents.
+const PAGE_SIZE: usize = 250;
+/// Item ids sent in one edit-tag request.
+const EDIT_BATCH: usize = 100;
+
+/// A client for servers speaking the Google Reader API, such as FreshRSS and
+/// Miniflux.
+pub struct GoogleReader {
+    client: Client,
+    base: Url,
+    auth: String,
+}
+
+impl GoogleReader {
+    /// Signs in through `accounts/ClientLogin` relative to the configured URL.
+    pub async fn login(
+        settings: &SyncSettings,
+        network: &NetworkSettings,
+    ) -> Result<Self, SyncError> {
+        let base = base_url(&settings.url)?;
+        let client = api_client(network, &base)?;
+        let password = match &settings.password {
+            Some(password) => password.resolve().await.map_err(SyncError::Credentials)?,
+            None => return Err(SyncError::Credentials("sync.password is not set".into())),
+        };
+
+        let response = client
+            .post(endpoint(&base, "accounts/ClientLogin")?)
+            .form(&[
+                ("Email", settings.username.as_str()),
+                ("Passwd", password.as_str()),
+            ])
+            .send()
+            .await?;
+        let body = check(response)?.text().await?;
+        let auth = body
+            .lines()
+            .find_map(|line| line.strip_prefix("Auth="))
+            .ok_or_else(|| SyncError::InvalidResponse("login reply has no Auth token".into()))?;
+
+        Ok(Self {
+            client,
+            base,
+            auth: auth.trim().to_string(),
+        })
+    }
+
+    /// Fetches the subscription list and up to `max_items` of the newest items.
+    pub async fn snapshot(&self, max_items: usize) -> Result<Snapshot, SyncError> {
+        let list: SubscriptionList = self
+            .get("reader/api/0/subscription/list", &[("output", "json")])
+            .await?;
+        let subscriptions = list
+            .subscriptions
+            .into_iter()
+            .map(|subscription| Subscription {
+                url: subscription.url.unwrap_or_else(|| {
+                    subscription
+                        .id
+                        .strip_prefix("feed/")
+                        .unwrap_or(&subscription.id)
+                        .to_string()
+                }),
+                id: subscription.id,
+                title: subscription.title,
+            })
+            .collect();
+
+        let mut items = Vec::new();
+        let mut continuation: Option<String> = None;
+        while items.len() < max_items {
+            let count = (max_items - items.len()).min(PAGE_SIZE).to_string();
+            let mut query = vec![("output", "json"), ("n", count.as_str())];
+            if let Some(continuation) = &continuation {
+                query.push(("c", continuation.as_str()));
+            }
+            let page: StreamContents = self
+                .get(
+                    &format!("reader/api/0/stream/contents/{READING_LIST}"),
+                    &query,
+                )
+                .await?;
+            let fetched = page.items.len();
+            items.extend(page.items.into_iter().map(RawItem::into_remote));
+            continuation = page.continuation.filter(|next| !next.is_empty());
+            if fetched == 0 || continuation.is_none() {
+                break;
+            }
+        }
+        items.truncate(max_items);
+
+        Ok(Snapshot {
+            subscriptions,
+            items,
+        })
+    }
+
+    /// Sends flag changes with `edit-tag` and subscription changes with
+    /// `subscription/quickadd` and `subscription/edit`.
+    pub async fn push(&self, changes: &[Change]) -> Result<(), SyncError> {
+        if changes.is_empty() {
+            return Ok(());
+        }
+        let token = self
+            .authorized(self.client.get(endpoint(&self.base, "reader/api/0/token")?))
+            .send()
+            .await?;
+        let token = check(token)?.text().await?.trim().to_string();
+        let mut first_error = None;
+
+        for flag in [Flag::Read, Flag::Starred] {
+            for value in [true, false] {
+                let tag = match flag {
+                    Flag::Read => READ_TAG,
+                    Flag::Starred => STARRED_TAG,
+                };
+                let action = if value { "a" } else { "r" };
+                for batch in Change::flagged(changes, flag, value).chunks(EDIT_BATCH) {
+                    let mut form = vec![("T", token.as_str()), (action, tag)];
+                    form.extend(batch.iter().map(|id| ("i", *id)));
+                    let result = self.post("reader/api/0/edit-tag", &form).await;
+                    first_error = first_error.or(result.err());
+                }
+            }
+        }
+
+        for change in changes {
+            let result = match change {
+                Change::Flag { .. } => continue,
+                Change::Subscribe { url, .. } => {
+                    let form = [("T", token.as_str()), ("quickadd", url.as_str())];
+                    self.post("reader/api/0/subscription/quickadd", &form).await
+                }
+                Change::Unsubscribe { id, .. } => {
+                    let form = [
+                        ("T", token.as_str()),
+                        ("ac", "unsubscribe"),
+                        ("s", id.as_str()),
+                    ];
+                    self.post("reader/api/0/subscription/edit", &form).await
+                }
+            };
+            first_error = first_error.or(result.err());
+        }
+        first_error.map_or(Ok(()), Err)
+    }
+
+    async fn post(&self, path: &str, form: &[(&str, &str)]) -> Result<(), SyncError> {
+        let request = self.client.post(endpoint(&self.base, path)?).form(form);
+        let response = self.authorized(request).send().await?;
+        check(response)?;
+        Ok(())
+    }
+
+    async fn get<T: for<'de> Deserialize<'de>>(
+        &self,
+        path: &str,
+        query: &[(&str, &str)],
+    ) -> Result<T, SyncError> {
+        let mut url = endpoint(&self.base, path)?;
+        url.query_pairs_mut().extend_pairs(query);
+        let response = self.authorized(self.client.get(url)).send().await?;
+        let body = check(response)?.bytes().await?;
+        serde_json::from_slice(&body).map_err(|error| SyncError::InvalidResponse(error.to_string()))
+    }
+
+    fn authorized(&self, request: RequestBuilder) -> RequestBuilder {
+        request.header(
+            reqwest::header::AUTHORIZATION,
+            format!("GoogleLogin auth={}", self.auth),
+        )
+    }
+}
+
+impl SyncBackend for GoogleReader {
+    fn snapshot(&self, max_items: usize) -> SyncFuture<'_, Snapshot> {
+        Box::pin(self.snapshot(max_items))
+    }
+
+    fn push<'a>(&'a self, changes: &'a [Change]) -> SyncFuture<'a, ()> {
+        Box::pin(self.push(changes))
+    }
+}
+
+/// Servers name states for the signed-in user as `user/-/…` or with the
+/// numeric user id in place of `-`.
+fn has_state(categories: &[String], state: &str) -> bool {
+    let suffix = format!("/state/com.google/{state}");
+    categories
+        .iter()
+        .any(|category| category.starts_with("user/") && category.ends_with(&suffix))
+}
+
+#[derive(Deserialize)]
+struct SubscriptionList {
+    #[serde(default)]
+    subscriptions: Vec<RawSubscription>,
+}
+
+#[derive(Deserialize)]
+struct RawSubscription {
+    id: String,
+    #[serde(default)]
+    title: String,
+    url: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct StreamContents {
+    #[serde(default)]
+    items: Vec<RawItem>,
+    continuation: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RawItem {
+    id: String,
+    #[serde(default)]
+    title: String,
+    published: Option<i64>,
+    author: Option<String>,
+    #[serde(default)]
+    canonical: Vec<RawLink>,
+    #[serde(default)]
+    alternate: Vec<RawLink>,
+    summary: Option<RawContent>,
+    content: Option<RawContent>,
+    #[serde(default)]
+    categories: Vec<String>,
+    origin: RawOrigin,
+}
+
+#[derive(Deserialize)]
+struct RawLink {
+    href: String,
+}
+
+#[derive(Deserialize)]
+struct RawContent {
+    content: String,
+}
+
+#[derive(Deserialize)]
+struct RawOrigin {
+    #[serde(rename = "streamId")]
+    stream_id: String,
+}
+
+impl RawItem {
+    fn into_remote(self) -> RemoteItem {
+        let read = has_state(&self.categories, "read");
+        let starred = has_state(&self.categories, "starred");
+        let html = |content: RawContent| {
+            (!content.content.trim().is_empty()).then_some(EntryContent {
+                value: content.content,
+                kind: ContentKind::Html,
+            })
+        };
+        let title = if self.title.trim().is_empty() {
+            "Untitled article".to_string()
+        } else {
+            self.title
+        };
+
+        RemoteItem {
+            feed: self.origin.stream_id,
+            entry: Entry {
+                id: Some(self.id),
+                title,
+                link: self
+                    .canonical
+                    .into_iter()
+                    .chain(self.alternate)
+                    .map(|link| link.href)
+                    .next(),
+                author: self.author.filter(|author| !author.trim().is_empty()),
+                summary: self.summary.and_then(html),
+                content: self.content.and_then(html),
+                published: self
+                    .published
+                    .and_then(|seconds| DateTime::from_timestamp(seconds, 0))
+                    .map(|date| date.to_rfc3339()),
+            },
+            read,
+            starred,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::Url;
+
+    use crate::config::{NetworkSettings, Secret, SyncSettings};
+    use crate::test_support::{MockResponse, MockServer};
+
+    use super::super::{Change, Flag};
+    use super::GoogleReader;
+
+    const SUBSCRIPTIONS: &str = r#"{"subscriptions": [
+        {"id": "feed/12", "title": "Example", "url": "https://example.com/feed.xml"}
+    ]}"#;
+    const CONTENTS: &str = r#"{"items": [
+        {
+            "id": "tag:google.com,2005:reader/item/000000000000001a",
+            "title": "Hello",
+            "published": 1752517200,
+            "alternate": [{"href": "https://example.com/hello"}],
+            "summary": {"content": "<p>Hi</p>"},
+            "categories": ["user/-/state/com.google/reading-list", "user/1/state/com.google/read"],
+            "origin": {"streamId": "feed/12", "title": "Example"}
+        }
+    ]}"#;
+
+    #[tokio::test]
+    async fn logs_in_fetches_a_snapshot_and_pushes_changes() {
+        let server = MockServer::start(|request| {
+            let path = request.path.split('?').next().unwrap_or_default();
+            match path {
+                "/api/greader.php/accounts/ClientLogin" => {
+                    let body = String::from_utf8_lossy(&request.body);
+                    if body.contains("Passwd=secret") {
+                        MockResponse::ok("SID=x\nLSID=x\nAuth=reader/token\n")
+                    } else {
+                        MockResponse::status(403)
+                    }
+                }
+                _ if request.header("authorization") != Some("GoogleLogin auth=reader/token") => {
+                    MockResponse::status(401)
+                }
+                "/api/greader.php/reader/api/0/subscription/list" => {
+                    MockResponse::ok(SUBSCRIPTIONS)
+                }
+                "/api/greader.php/reader/api/0/stream/contents/user/-/state/com.google/reading-list" => {
+                    MockResponse::ok(CONTENTS)
+                }
+                "/api/greader.php/reader/api/0/token" => MockResponse::ok("edit-token\n"),
+                "/api/greader.php/reader/api/0/edit-tag" => MockResponse::ok("OK"),
+                _ => MockResponse::status(404),
+            }
+        })
+        .await;
+        let settings = SyncSettings {
+            url: server.url("/api/greader.php"),
+            username: "reader".to_string(),
+            password: Some(Secret::Value("secret".to_string())),
+            ..SyncSettings::default()
+        };
+
+        let reader = GoogleReader::login(&settings, &NetworkSettings::default())
+            .await
+            .unwrap();
+        let snapshot = reader.snapshot(10).await.unwrap();
+        assert_eq!(
+            snapshot.subscriptions[0].url,
+            "https://example.com/feed.xml"
+        );
+        let item = &snapshot.items[0];
+        assert_eq!(item.feed, "feed/12");
+        assert!(item.read && !item.starred);
+        assert_eq!(
+            item.entry.link.as_deref(),
+            Some("https://example.com/hello")
+        );
+        assert_eq!(
+            item.entry.published.as_deref(),
+            Some("2025-07-14T18:20:00+00:00")
+        );
+
+        let id = "tag:google.com,2005:reader/item/000000000000001a";
+        reader
+            .push(&[Change::Flag {
+                item: id.to_string(),
+                flag: Flag::Starred,
+                value: true,
+            }])
+            .await
+            .unwrap();
+        let edit = server
+            .requests()
+            .into_iter()
+            .find(|request| request.path.ends_with("/edit-tag"))
+            .unwrap();
+        let body = String::from_utf8_lossy(&edit.body);
+        let form: Vec<(String, String)> = Url::parse(&format!("http://localhost/?{body}"))
+            .unwrap()
+            .query_pairs()
+            .into_owned()
+            .collect();
+        assert!(form.contains(&("T".to_string(), "edit-token".to_string())));
+        assert!(form.contains(&(
+            "a".to_string(),
+            "user/-/state/com.google/starred".to_string()
+        )));
+        assert!(form.contains(&("i".to_string(), id.to_string())));
+
+        let wrong = SyncSettings {
+            password: Some(Secret::Value("wrong".to_string())),
+            ..settings
+        };
+        assert!(matches!(
+            GoogleReader::login(&wrong, &NetworkSettings::default()).await,
+            Err(super::SyncError::Unauthorized)
+        ));
+    }
+}
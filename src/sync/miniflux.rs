@@ -0,0 +1,358 @@
+use reqwest::{header::CONTENT_TYPE, Client, RequestBuilder, Url};
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::{json, Value};
+
+use crate::config::{NetworkSettings, SyncSettings};
+use crate::feed::entries::{ContentKind, Entry, EntryContent};
+
+use crate::http::{api_client, base_url, check, endpoint};
+
+use super::{Change, Flag, RemoteItem, Snapshot, Subscription, SyncBackend, SyncError, SyncFuture};
+
+/// Entries requested per page.
+const PAGE_SIZE: usize = 250;
+
+/// A client for Miniflux's REST API, signed in with an API token or with the
+/// username and password.
+pub struct Miniflux {
+    client: Client,
+    base: Url,
+    credentials: Credentials,
+}
+
+enum Credentials {
+    Token(String),
+    Basic { username: String, password: String },
+}
+
+impl Miniflux {
+    /// Resolves the credentials and checks them with `/v1/me`.
+    pub async fn connect(
+        settings: &SyncSettings,
+        network: &NetworkSettings,
+    ) -> Result<Self, SyncError> {
+        let base = base_url(&settings.url)?;
+        let client = api_client(network, &base)?;
+        let credentials = match (&settings.token, &settings.password) {
+            (Some(token), _) => {
+                Credentials::Token(token.resolve().await.map_err(SyncError::Credentials)?)
+            }
+            (None, Some(password)) => Credentials::Basic {
+                username: settings.username.clone(),
+                password: password.resolve().await.map_err(SyncError::Credentials)?,
+            },
+            (None, None) => {
+                return Err(SyncError::Credentials(
+                    "set sync.token or sync.password".into(),
+                ))
+            }
+        };
+        let miniflux = Self {
+            client,
+            base,
+            credentials,
+        };
+        miniflux.get::<Value>("v1/me", &[]).await?;
+        Ok(miniflux)
+    }
+
+    pub async fn snapshot(&self, max_items: usize) -> Result<Snapshot, SyncError> {
+        let feeds: Vec<RawFeed> = self.get("v1/feeds", &[]).await?;
+        let subscriptions = feeds
+            .into_iter()
+            .map(|feed| Subscription {
+                id: feed.id.to_string(),
+                title: feed.title,
+                url: feed.feed_url,
+            })
+            .collect();
+
+        let mut items = Vec::new();
+        while items.len() < max_items {
+            let limit = (max_items - items.len()).min(PAGE_SIZE).to_string();
+            let offset = items.len().to_string();
+            let page: EntryPage = self
+                .get(
+                    "v1/entries",
+                    &[
+                        ("order", "id"),
+                        ("direction", "desc"),
+                        ("limit", &limit),
+                        ("offset", &offset),
+                    ],
+                )
+                .await?;
+            let fetched = page.entries.len();
+            items.extend(page.entries.into_iter().map(RawEntry::into_remote));
+            if fetched == 0 || items.len() >= page.total {
+                break;
+            }
+        }
+        items.truncate(max_items);
+
+        Ok(Snapshot {
+            subscriptions,
+            items,
+        })
+    }
+
+    /// Updates read state in bulk; stars and subscriptions one at a time.
+    pub async fn push(&self, changes: &[Change]) -> Result<(), SyncError> {
+        let mut first_error = None;
+        for (value, status) in [(true, "read"), (false, "unread")] {
+            let ids: Vec<i64> = Change::flagged(changes, Flag::Read, value)
+                .into_iter()
+                .filter_map(|id| id.parse().ok())
+                .collect();
+            if ids.is_empty() {
+                continue;
+            }
+            let request = self.client.put(endpoint(&self.base, "v1/entries")?);
+            let request = with_json(request, json!({ "entry_ids": ids, "status": status }));
+            first_error = first_error.or(self.send(request).await.err());
+        }
+
+        for change in changes {
+            let request = match change {
+                Change::Flag {
+                    flag: Flag::Read, ..
+                } => continue,
+                // Miniflux only toggles bookmarks; the change is only queued
+                // when the server still has the opposite value.
+                Change::Flag {
+                    item,
+                    flag: Flag::Starred,
+                    ..
+                } => self.client.put(endpoint(
+                    &self.base,
+                    &format!("v1/entries/{item}/bookmark"),
+                )?),
+                Change::Subscribe { url, .. } => {
+                    let category = match self.default_category().await {
+                        Ok(category) => category,
+                        Err(error) => {
+                            first_error = first_error.or(Some(error));
+                            continue;
+                        }
+                    };
+                    with_json(
+                        self.client.post(endpoint(&self.base, "v1/feeds")?),
+                        json!({ "feed_url": url, "category_id": category }),
+                    )
+                }
+                Change::Unsubscribe { id, .. } => self
+                    .client
+                    .delete(endpoint(&self.base, &format!("v1/feeds/{id}"))?),
+            };
+            first_error = first_error.or(self.send(request).await.err());
+        }
+        first_error.map_or(Ok(()), Err)
+    }
+
+    /// New feeds go in the first category, as the API requires one.
+    async fn default_category(&self) -> Result<i64, SyncError> {
+        let categories: Vec<RawCategory> = self.get("v1/categories", &[]).await?;
+        categories
+            .first()
+            .map(|category| category.id)
+            .ok_or_else(|| SyncError::InvalidResponse("the account has no categories".into()))
+    }
+
+    async fn get<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, &str)],
+    ) -> Result<T, SyncError> {
+        let mut url = endpoint(&self.base, path)?;
+        if !query.is_empty() {
+            url.query_pairs_mut().extend_pairs(query);
+        }
+        let response = self.authorized(self.client.get(url)).send().await?;
+        let body = check(response)?.bytes().await?;
+        serde_json::from_slice(&body).map_err(|error| SyncError::InvalidResponse(error.to_string()))
+    }
+
+    async fn send(&self, request: RequestBuilder) -> Result<(), SyncError> {
+        check(self.authorized(request).send().await?)?;
+        Ok(())
+    }
+
+    fn authorized(&self, request: RequestBuilder) -> RequestBuilder {
+        match &self.credentials {
+            Credentials::Token(token) => request.header("X-Auth-Token", token),
+            Credentials::Basic { username, password } => {
+                request.basic_auth(username, Some(password))
+            }
+        }
+    }
+}
+
+fn with_json(request: RequestBuilder, body: Value) -> RequestBuilder {
+    request
+        .header(CONTENT_TYPE, "application/json")
+        .body(body.to_string())
+}
+
+impl SyncBackend for Miniflux {
+    fn snapshot(&self, max_items: usize) -> SyncFuture<'_, Snapshot> {
+        Box::pin(self.snapshot(max_items))
+    }
+
+    fn push<'a>(&'a self, changes: &'a [Change]) -> SyncFuture<'a, ()> {
+        Box::pin(self.push(changes))
+    }
+}
+
+#[derive(Deserialize)]
+struct RawFeed {
+    id: i64,
+    #[serde(default)]
+    title: String,
+    feed_url: String,
+}
+
+#[derive(Deserialize)]
+struct RawCategory {
+    id: i64,
+}
+
+#[derive(Deserialize)]
+struct EntryPage {
+    #[serde(default)]
+    total: usize,
+    #[serde(default)]
+    entries: Vec<RawEntry>,
+}
+
+#[derive(Deserialize)]
+struct RawEntry {
+    id: i64,
+    feed_id: i64,
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    url: String,
+    #[serde(default)]
+    content: String,
+    #[serde(default)]
+    author: String,
+    published_at: Option<String>,
+    #[serde(default)]
+    status: String,
+    #[serde(default)]
+    starred: bool,
+}
+
+impl RawEntry {
+    fn into_remote(self) -> RemoteItem {
+        let title = if self.title.trim().is_empty() {
+            "Untitled article".to_string()
+        } else {
+            self.title
+        };
+        RemoteItem {
+            feed: self.feed_id.to_string(),
+            entry: Entry {
+                id: Some(self.id.to_string()),
+                title,
+                link: (!self.url.is_empty()).then_some(self.url),
+                author: (!self.author.trim().is_empty()).then_some(self.author),
+                summary: None,
+                content: (!self.content.trim().is_empty()).then_some(EntryContent {
+                    value: self.content,
+                    kind: ContentKind::Html,
+                }),
+                published: self.published_at,
+            },
+            read: self.status == "read",
+            starred: self.starred,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::{NetworkSettings, Secret, SyncSettings};
+    use crate::sync::{Change, Flag};
+    use crate::test_support::{MockResponse, MockServer};
+
+    use super::Miniflux;
+
+    #[tokio::test]
+    async fn reads_entries_and_pushes_changes_through_the_rest_api() {
+        let server = MockServer::start(|request| {
+            if request.header("x-auth-token") != Some("token") {
+                return MockResponse::status(401);
+            }
+            let path = request.path.split('?').next().unwrap_or_default();
+            match (request.method.as_str(), path) {
+                ("GET", "/v1/me") => MockResponse::ok(r#"{"id": 1}"#),
+                ("GET", "/v1/feeds") => MockResponse::ok(
+                    r#"[{"id": 4, "title": "Example", "feed_url": "https://example.com/feed.xml"}]"#,
+                ),
+                ("GET", "/v1/entries") => MockResponse::ok(
+                    r#"{"total": 1, "entries": [{"id": 88, "feed_id": 4, "title": "Hello",
+                        "url": "https://example.com/hello", "content": "<p>Hi</p>",
+                        "published_at": "2025-07-14T18:20:00Z", "status": "unread", "starred": true}]}"#,
+                ),
+                ("GET", "/v1/categories") => MockResponse::ok(r#"[{"id": 2, "title": "All"}]"#),
+                ("PUT" | "POST" | "DELETE", _) => MockResponse::status(204),
+                _ => MockResponse::status(404),
+            }
+        })
+        .await;
+        let settings = SyncSettings {
+            url: server.url("/"),
+            token: Some(Secret::Value("token".to_string())),
+            ..SyncSettings::default()
+        };
+
+        let miniflux = Miniflux::connect(&settings, &NetworkSettings::default())
+            .await
+            .unwrap();
+        let snapshot = miniflux.snapshot(50).await.unwrap();
+        assert_eq!(snapshot.subscriptions[0].id, "4");
+        let item = &snapshot.items[0];
+        assert_eq!(item.feed, "4");
+        assert_eq!(item.entry.id.as_deref(), Some("88"));
+        assert!(!item.read && item.starred);
+
+        miniflux
+            .push(&[
+                Change::Flag {
+                    item: "88".to_string(),
+                    flag: Flag::Read,
+                    value: true,
+                },
+                Change::Subscribe {
+                    url: "https://other.example/feed.xml".to_string(),
+                    title: "Other".to_string(),
+                },
+                Change::Unsubscribe {
+                    id: "4".to_string(),
+                    url: "https://example.com/feed.xml".to_string(),
+                },
+            ])
+            .await
+            .unwrap();
+        let writes: Vec<_> = server
+            .requests()
+            .into_iter()
+            .filter(|request| request.method != "GET")
+            .map(|request| {
+                (
+                    request.method,
+                    request.path,
+                    String::from_utf8_lossy(&request.body).to_string(),
+                )
+            })
+            .collect();
+        assert_eq!(writes[0].1, "/v1/entries");
+        assert!(writes[0].2.contains(r#""status":"read""#));
+        assert!(writes[1].2.contains(r#""category_id":2"#));
+        assert_eq!(
+            (writes[2].0.as_str(), writes[2].1.as_str()),
+            ("DELETE", "/v1/feeds/4")
+        );
+    }
+}
@@ -0,0 +1,708 @@
+pub mod fever;
+pub mod greader;
+pub mod miniflux;
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    error::Error,
+    fmt, fs, io,
+    path::PathBuf,
+};
+
+use futures::future::BoxFuture;
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+
+use crate::app::AppResult;
+use crate::config::{Config, SyncBackendKind};
+use crate::feed::{
+    entries::{Entry, FeedDocument},
+    feeds::{load_feeds, Feed, FeedsManager},
+    source::FeedSource,
+    store::EntryStore,
+};
+use crate::http::ApiError;
+
+use fever::Fever;
+use greader::GoogleReader;
+use miniflux::Miniflux;
+
+const SYNC_STATE_FILE: &str = "sync.json";
+
+#[derive(Debug)]
+pub enum SyncError {
+    NotConfigured,
+    InvalidUrl(String),
+    Credentials(String),
+    /// The HTTP client could not be set up from the network settings.
+    Client(String),
+    Unauthorized,
+    Request(reqwest::Error),
+    HttpStatus(StatusCode),
+    InvalidResponse(String),
+    /// The server's API has no way to make this change.
+    Unsupported(&'static str),
+}
+
+impl fmt::Display for SyncError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotConfigured => formatter.write_str("no sync server is configured"),
+            Self::InvalidUrl(url) => write!(formatter, "invalid sync server URL {url}"),
+            Self::Credentials(message) => write!(formatter, "sync credentials: {message}"),
+            Self::Client(message) => {
+                write!(formatter, "could not set up the HTTP client: {message}")
+            }
+            Self::Unauthorized => formatter.write_str("the sync server rejected the credentials"),
+            Self::Request(error) => write!(formatter, "sync request failed: {error}"),
+            Self::HttpStatus(status) => write!(formatter, "sync server answered {status}"),
+            Self::InvalidResponse(message) => {
+                write!(formatter, "unexpected sync server response: {message}")
+            }
+            Self::Unsupported(action) => write!(formatter, "the sync server cannot {action}"),
+        }
+    }
+}
+
+impl Error for SyncError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Request(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for SyncError {
+    fn from(error: reqwest::Error) -> Self {
+        Self::Request(error)
+    }
+}
+
+impl From<ApiError> for SyncError {
+    fn from(error: ApiError) -> Self {
+        match error {
+            ApiError::InvalidUrl(url) => Self::InvalidUrl(url),
+            ApiError::Client(message) => Self::Client(message),
+            ApiError::Unauthorized => Self::Unauthorized,
+            ApiError::Request(error) => Self::Request(error),
+            ApiError::HttpStatus(status) => Self::HttpStatus(status),
+        }
+    }
+}
+
+/// A feed the account is subscribed to on the server.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Subscription {
+    /// The server's id for the feed, which items refer to.
+    pub id: String,
+    pub title: String,
+    pub url: String,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RemoteItem {
+    /// Id of the [`Subscription`] the item belongs to.
+    pub feed: String,
+    /// The entry, with the server's item id as its id.
+    pub entry: Entry,
+    pub read: bool,
+    pub starred: bool,
+}
+
+/// Subscriptions and the newest items, as the server sees them.
+#[derive(Clone, Debug, Default)]
+pub struct Snapshot {
+    pub subscriptions: Vec<Subscription>,
+    pub items: Vec<RemoteItem>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Flag {
+    Read,
+    Starred,
+}
+
+/// A local edit the server has not seen yet.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Change {
+    Flag {
+        item: String,
+        flag: Flag,
+        value: bool,
+    },
+    Subscribe {
+        url: String,
+        title: String,
+    },
+    /// `id` is the server's id for the subscription.
+    Unsubscribe {
+        id: String,
+        url: String,
+    },
+}
+
+impl Change {
+    /// Items whose `flag` should become `value`.
+    pub fn flagged(changes: &[Change], flag: Flag, value: bool) -> Vec<&str> {
+        changes
+            .iter()
+            .filter_map(|change| match change {
+                Change::Flag {
+                    item,
+                    flag: changed,
+                    value: to,
+                } if *changed == flag && *to == value => Some(item.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+pub type SyncFuture<'a, T> = BoxFuture<'a, Result<T, SyncError>>;
+
+/// A server that holds the subscriptions, entries and flags fead syncs with.
+/// [`reconcile`] merges its [`Snapshot`] into the local feeds and entries, and
+/// the edits made locally since the last sync go back through [`Self::push`].
+pub trait SyncBackend: Send + Sync {
+    /// Subscriptions and up to `max_items` of the newest items.
+    fn snapshot(&self, max_items: usize) -> SyncFuture<'_, Snapshot>;
+
+    /// Applies local changes on the server. Every change is attempted; the
+    /// first error is returned.
+    fn push<'a>(&'a self, changes: &'a [Change]) -> SyncFuture<'a, ()>;
+
+    /// Whether [`Change::Subscribe`] and [`Change::Unsubscribe`] can be
+    /// pushed. When not, local feeds stay local and the server's
+    /// subscriptions are kept here.
+    fn manages_subscriptions(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Flags {
+    pub read: bool,
+    pub starred: bool,
+}
+
+/// The feeds and flags both sides agreed on at the end of the last sync, kept
+/// in `sync.json`. Comparing against them tells which side changed an item.
+#[derive(Clone, Debug, Default)]
+pub struct SyncState {
+    path: PathBuf,
+    agreed: Agreed,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct Agreed {
+    /// URLs of the feeds the server provides.
+    feeds: BTreeSet<String>,
+    items: BTreeMap<String, Flags>,
+}
+
+impl SyncState {
+    pub fn load() -> AppResult<Self> {
+        Self::load_from(SYNC_STATE_FILE)
+    }
+
+    pub fn load_from(path: impl Into<PathBuf>) -> AppResult<Self> {
+        let path = path.into();
+        let agreed = match fs::read_to_string(&path) {
+            Ok(contents) if contents.trim().is_empty() => Agreed::default(),
+            Ok(contents) => serde_json::from_str(&contents)?,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Agreed::default(),
+            Err(error) => return Err(error.into()),
+        };
+        Ok(Self { path, agreed })
+    }
+
+    /// A state that is never written to disk.
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    /// Whether the feed's entries come from the sync server rather than from
+    /// fetching the feed itself.
+    pub fn is_synced(&self, url: &str) -> bool {
+        self.agreed.feeds.contains(url)
+    }
+
+    fn persist(&self) -> AppResult<()> {
+        if self.path.as_os_str().is_empty() {
+            return Ok(());
+        }
+        let json = serde_json::to_string_pretty(&self.agreed)?;
+        fs::write(&self.path, format!("{json}\n"))?;
+        Ok(())
+    }
+}
+
+/// What [`reconcile`] changed locally and what still has to reach the server.
+#[derive(Clone, Debug, Default)]
+pub struct Reconciled {
+    pub added_feeds: Vec<Feed>,
+    /// Feeds unsubscribed on the server and dropped locally.
+    pub removed_feeds: Vec<Feed>,
+    /// Entries not stored before, by feed URL.
+    pub new_entries: Vec<(String, Vec<Entry>)>,
+    /// Flags taken from the server.
+    pub pulled: usize,
+    pub changes: Vec<Change>,
+}
+
+impl fmt::Display for Reconciled {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let entries: usize = self.new_entries.iter().map(|(_, new)| new.len()).sum();
+        write!(
+            formatter,
+            "{} feeds added, {} removed, {entries} new entries, {} updates pulled, {} pushed",
+            self.added_feeds.len(),
+            self.removed_feeds.len(),
+            self.pulled,
+            self.changes.len()
+        )
+    }
+}
+
+/// Merges a server snapshot into the local feeds and entries. Items are
+/// stored under their feed's URL. Subscriptions and read or starred flags take
+/// the server's side unless only the local side changed since the last sync,
+/// in which case the local edit becomes a [`Change`]. Subscription changes are
+/// only made when the server `manages_subscriptions`.
+pub fn reconcile(
+    snapshot: &Snapshot,
+    feeds: &mut Vec<Feed>,
+    store: &mut EntryStore,
+    state: &mut SyncState,
+    manages_subscriptions: bool,
+) -> AppResult<Reconciled> {
+    let mut reconciled = Reconciled::default();
+    let mut unsubscribed = BTreeSet::new();
+    for subscription in &snapshot.subscriptions {
+        if feeds.iter().any(|feed| feed.url == subscription.url) {
+            continue;
+        }
+        if manages_subscriptions && state.is_synced(&subscription.url) {
+            unsubscribed.insert(subscription.id.as_str());
+            reconciled.changes.push(Change::Unsubscribe {
+                id: subscription.id.clone(),
+                url: subscription.url.clone(),
+            });
+        } else {
+            feeds.add_feed(subscription.title.clone(), subscription.url.clone());
+            reconciled.added_feeds.push(Feed {
+                title: subscription.title.clone(),
+                url: subscription.url.clone(),
+            });
+        }
+    }
+    let remote: BTreeSet<&str> = snapshot
+        .subscriptions
+        .iter()
+        .map(|subscription| subscription.url.as_str())
+        .collect();
+    let (kept, local_only): (Vec<Feed>, Vec<Feed>) = feeds
+        .drain(..)
+        .partition(|feed| remote.contains(feed.url.as_str()) || !is_web_feed(&feed.url));
+    *feeds = kept;
+    for feed in local_only {
+        if state.is_synced(&feed.url) {
+            store.remove(&feed.url)?;
+            reconciled.removed_feeds.push(feed);
+        } else {
+            if manages_subscriptions {
+                reconciled.changes.push(Change::Subscribe {
+                    url: feed.url.clone(),
+                    title: feed.title.clone(),
+                });
+            }
+            feeds.push(feed);
+        }
+    }
+
+    let mut by_feed: HashMap<&str, Vec<&RemoteItem>> = HashMap::new();
+    for item in &snapshot.items {
+        by_feed.entry(item.feed.as_str()).or_default().push(item);
+    }
+
+    let mut agreed = BTreeMap::new();
+    for subscription in &snapshot.subscriptions {
+        if unsubscribed.contains(subscription.id.as_str()) {
+            continue;
+        }
+        let Some(items) = by_feed.get(subscription.id.as_str()) else {
+            continue;
+        };
+        let url = &subscription.url;
+        let document = FeedDocument {
+            title: subscription.title.clone(),
+            entries: items.iter().map(|item| item.entry.clone()).collect(),
+            recovered: false,
+        };
+        let added = store.merge(url, &document)?;
+        if !added.is_empty() {
+            reconciled.new_entries.push((url.clone(), added));
+        }
+
+        let stored = store.get(url).cloned().unwrap_or_default();
+        let mut read = stored.read.clone();
+        let mut starred = stored.starred.clone();
+        for item in items {
+            let key = item.entry.key().to_string();
+            let base = state.agreed.items.get(&key).copied();
+            let flags = Flags {
+                read: resolve(
+                    &mut read,
+                    &key,
+                    item.read,
+                    base.map(|base| base.read),
+                    Flag::Read,
+                    &mut reconciled,
+                ),
+                starred: resolve(
+                    &mut starred,
+                    &key,
+                    item.starred,
+                    base.map(|base| base.starred),
+                    Flag::Starred,
+                    &mut reconciled,
+                ),
+            };
+            agreed.insert(key, flags);
+        }
+        store.set_flags(url, read, starred)?;
+    }
+
+    state.agreed = Agreed {
+        feeds: snapshot
+            .subscriptions
+            .iter()
+            .map(|subscription| subscription.url.clone())
+            .collect(),
+        items: agreed,
+    };
+    state.persist()?;
+    Ok(reconciled)
+}
+
+/// Only feeds fetched over HTTP can be subscribed to on a server.
+fn is_web_feed(url: &str) -> bool {
+    matches!(FeedSource::parse(url), Ok(FeedSource::Http(_)))
+}
+
+/// Settles one flag of one item in `local`. The server's value is returned
+/// as the agreed one even when a local edit is queued, so an edit that fails
+/// to reach the server is sent again on the next sync.
+fn resolve(
+    local: &mut BTreeSet<String>,
+    key: &str,
+    remote: bool,
+    base: Option<bool>,
+    flag: Flag,
+    reconciled: &mut Reconciled,
+) -> bool {
+    let current = local.contains(key);
+    if base == Some(remote) && current != remote {
+        reconciled.changes.push(Change::Flag {
+            item: key.to_string(),
+            flag,
+            value: current,
+        });
+        return remote;
+    }
+    if current != remote {
+        reconciled.pulled += 1;
+        if remote {
+            local.insert(key.to_string());
+        } else {
+            local.remove(key);
+        }
+    }
+    remote
+}
+
+/// Signs in to the configured server.
+pub async fn connect(config: &Config) -> Result<Box<dyn SyncBackend>, SyncError> {
+    let settings = config.sync.as_ref().ok_or(SyncError::NotConfigured)?;
+    Ok(match settings.backend {
+        SyncBackendKind::GoogleReader => {
+            Box::new(GoogleReader::login(settings, &config.network).await?)
+        }
+        SyncBackendKind::Fever => Box::new(Fever::login(settings, &config.network).await?),
+        SyncBackendKind::Miniflux => Box::new(Miniflux::connect(settings, &config.network).await?),
+    })
+}
+
+/// Entries stored for a feed the sync server provides, so it opens without
+/// a network request and while offline. `None` for other feeds.
+pub fn synced_entries(url: &str) -> AppResult<Option<Vec<Entry>>> {
+    if !SyncState::load()?.is_synced(url) {
+        return Ok(None);
+    }
+    let entries = EntryStore::load()?.entries(url).to_vec();
+    Ok((!entries.is_empty()).then_some(entries))
+}
+
+/// Runs one full sync against the configured server using the files in the
+/// working directory.
+pub async fn sync_once(config: &Config) -> AppResult<Reconciled> {
+    let server = connect(config).await?;
+    let max_items = config
+        .sync
+        .as_ref()
+        .map_or(0, |settings| settings.max_items);
+    let snapshot = server.snapshot(max_items).await?;
+
+    let mut feeds = load_feeds()?;
+    let mut store = EntryStore::load()?;
+    let mut state = SyncState::load()?;
+    let reconciled = reconcile(
+        &snapshot,
+        &mut feeds,
+        &mut store,
+        &mut state,
+        server.manages_subscriptions(),
+    )?;
+    if !reconciled.added_feeds.is_empty() || !reconciled.removed_feeds.is_empty() {
+        feeds.persist()?;
+    }
+    server.push(&reconciled.changes).await?;
+    Ok(reconciled)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::feed::{
+        entries::Entry,
+        feeds::{load_feeds_from, update_feeds_at, Feed, FeedsManager},
+        store::EntryStore,
+    };
+
+    use super::{reconcile, Change, Flag, RemoteItem, Snapshot, Subscription, SyncState};
+
+    fn item(id: &str, read: bool, starred: bool) -> RemoteItem {
+        RemoteItem {
+            feed: "feed/1".to_string(),
+            entry: Entry {
+                id: Some(id.to_string()),
+                title: id.to_string(),
+                ..Entry::default()
+            },
+            read,
+            starred,
+        }
+    }
+
+    fn snapshot(items: Vec<RemoteItem>) -> Snapshot {
+        Snapshot {
+            subscriptions: vec![Subscription {
+                id: "feed/1".to_string(),
+                title: "Example".to_string(),
+                url: "https://example.com/feed.xml".to_string(),
+            }],
+            items,
+        }
+    }
+
+    #[test]
+    fn reconciles_read_state_in_both_directions() {
+        let url = "https://example.com/feed.xml";
+        let mut feeds: Vec<Feed> = Vec::new();
+        let mut store = EntryStore::in_memory();
+        let mut state = SyncState::in_memory();
+
+        let first = snapshot(vec![item("a", false, false), item("b", true, false)]);
+        let reconciled = reconcile(&first, &mut feeds, &mut store, &mut state, true).unwrap();
+        assert_eq!(feeds[0].url, url);
+        assert_eq!(reconciled.new_entries[0].1.len(), 2);
+        assert!(reconciled.changes.is_empty());
+        assert!(store.mark_read(url, "a", true).unwrap());
+
+        // "a" was read here, "b" was marked unread and "c" starred on the server.
+        let second = snapshot(vec![
+            item("a", false, false),
+            item("b", false, false),
+            item("c", false, true),
+        ]);
+        let reconciled = reconcile(&second, &mut feeds, &mut store, &mut state, true).unwrap();
+        assert_eq!(
+            reconciled.changes,
+            [Change::Flag {
+                item: "a".to_string(),
+                flag: Flag::Read,
+                value: true,
+            }]
+        );
+        assert_eq!(reconciled.pulled, 2);
+        let stored = store.get(url).unwrap();
+        assert!(stored.read.contains("a") && !stored.read.contains("b"));
+        assert!(stored.starred.contains("c"));
+        assert_eq!(feeds.len(), 1);
+        assert!(state.is_synced(url));
+    }
+
+    #[test]
+    fn reconciles_subscriptions_in_both_directions() {
+        let mut feeds: Vec<Feed> = Vec::new();
+        let mut store = EntryStore::in_memory();
+        let mut state = SyncState::in_memory();
+        reconcile(
+            &snapshot(Vec::new()),
+            &mut feeds,
+            &mut store,
+            &mut state,
+            true,
+        )
+        .unwrap();
+
+        // The synced feed was deleted here; two were added, one of them a command.
+        feeds.clear();
+        feeds.push(Feed {
+            title: "Local".to_string(),
+            url: "https://local.example/feed.xml".to_string(),
+        });
+        feeds.push(Feed {
+            title: "Script".to_string(),
+            url: "exec:./feed.sh".to_string(),
+        });
+        let reconciled = reconcile(
+            &snapshot(Vec::new()),
+            &mut feeds,
+            &mut store,
+            &mut state,
+            true,
+        )
+        .unwrap();
+        assert_eq!(
+            reconciled.changes,
+            [
+                Change::Unsubscribe {
+                    id: "feed/1".to_string(),
+                    url: "https://example.com/feed.xml".to_string(),
+                },
+                Change::Subscribe {
+                    url: "https://local.example/feed.xml".to_string(),
+                    title: "Local".to_string(),
+                },
+            ]
+        );
+
+        // Once the server dropped it too, a feed it used to provide goes away here.
+        feeds.push(Feed {
+            title: "Example".to_string(),
+            url: "https://example.com/feed.xml".to_string(),
+        });
+        let mut only_local = snapshot(Vec::new());
+        only_local.subscriptions[0].url = "https://local.example/feed.xml".to_string();
+        let reconciled = reconcile(&only_local, &mut feeds, &mut store, &mut state, true).unwrap();
+        assert_eq!(reconciled.removed_feeds[0].title, "Example");
+        let urls: Vec<_> = feeds.iter().map(|feed| feed.url.as_str()).collect();
+        assert_eq!(urls, ["exec:./feed.sh", "https://local.example/feed.xml"]);
+    }
+
+    #[test]
+    fn keeps_feeds_a_sync_added_behind_the_tuis_back() {
+        let path = std::env::temp_dir().join(format!("fead-feeds-{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let mut store = EntryStore::in_memory();
+        let mut state = SyncState::in_memory();
+        let local = Feed {
+            title: "Local".to_string(),
+            url: "https://local.example/feed.xml".to_string(),
+        };
+
+        // The TUI loaded its list before the daemon's sync added the server's feed.
+        update_feeds_at(&path, |feeds| feeds.push(local.clone())).unwrap();
+        let stale = load_feeds_from(&path).unwrap();
+        let mut feeds = load_feeds_from(&path).unwrap();
+        reconcile(
+            &snapshot(Vec::new()),
+            &mut feeds,
+            &mut store,
+            &mut state,
+            true,
+        )
+        .unwrap();
+        update_feeds_at(&path, |saved| *saved = feeds).unwrap();
+
+        // Adding and deleting feeds in the TUI keeps it.
+        let added = update_feeds_at(&path, |feeds| {
+            feeds.add_feed(
+                "New".to_string(),
+                "https://new.example/feed.xml".to_string(),
+            );
+        })
+        .unwrap();
+        assert_eq!(added.len(), stale.len() + 2);
+        let mut feeds =
+            update_feeds_at(&path, |feeds| feeds.retain(|feed| feed.url != local.url)).unwrap();
+
+        let reconciled = reconcile(
+            &snapshot(Vec::new()),
+            &mut feeds,
+            &mut store,
+            &mut state,
+            true,
+        )
+        .unwrap();
+        assert!(!reconciled
+            .changes
+            .iter()
+            .any(|change| matches!(change, Change::Unsubscribe { .. })));
+        let urls: Vec<_> = feeds.iter().map(|feed| feed.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            [
+                "https://example.com/feed.xml",
+                "https://new.example/feed.xml"
+            ]
+        );
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn keeps_subscriptions_when_the_server_cannot_change_them() {
+        let mut feeds: Vec<Feed> = Vec::new();
+        let mut store = EntryStore::in_memory();
+        let mut state = SyncState::in_memory();
+        reconcile(
+            &snapshot(Vec::new()),
+            &mut feeds,
+            &mut store,
+            &mut state,
+            false,
+        )
+        .unwrap();
+
+        // The synced feed was deleted here and another added.
+        feeds.clear();
+        feeds.push(Feed {
+            title: "Local".to_string(),
+            url: "https://local.example/feed.xml".to_string(),
+        });
+        let reconciled = reconcile(
+            &snapshot(Vec::new()),
+            &mut feeds,
+            &mut store,
+            &mut state,
+            false,
+        )
+        .unwrap();
+        assert!(reconciled.changes.is_empty());
+        assert_eq!(
+            reconciled.added_feeds[0].url,
+            "https://example.com/feed.xml"
+        );
+        let urls: Vec<_> = feeds.iter().map(|feed| feed.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            [
+                "https://example.com/feed.xml",
+                "https://local.example/feed.xml"
+            ]
+        );
+    }
+}
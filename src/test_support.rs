@@ -0,0 +1,236 @@
+use std::sync::{Arc, Mutex};
+
+use ratatui::widgets::ListState;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    task::JoinHandle,
+};
+use tui_input::Input;
+
+use crate::{
+    app::{App, EntryList, FeedList, InputMode},
+    article::highlight::Highlighter,
+    config::{Config, ImageProtocol, ImageSettings},
+    feed::{
+        entries::Entry, feeds::Feed, health::HealthStore, reading::ReadingStore, store::EntryStore,
+    },
+    images::ImageStore,
+    screen::{Panes, Screen},
+};
+
+#[derive(Clone, Debug)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub path: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl RecordedRequest {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct MockResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl MockResponse {
+    pub fn ok(body: impl Into<Vec<u8>>) -> Self {
+        Self::status(200).body(body)
+    }
+
+    pub fn status(status: u16) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body: Vec::new(),
+        }
+    }
+
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn body(mut self, body: impl Into<Vec<u8>>) -> Self {
+        self.body = body.into();
+        self
+    }
+}
+
+type Handler = dyn Fn(&RecordedRequest) -> MockResponse + Send + Sync;
+
+/// A minimal HTTP/1.1 server that answers each connection with one response.
+pub struct MockServer {
+    base: String,
+    requests: Arc<Mutex<Vec<RecordedRequest>>>,
+    task: JoinHandle<()>,
+}
+
+impl MockServer {
+    pub async fn start(
+        handler: impl Fn(&RecordedRequest) -> MockResponse + Send + Sync + 'static,
+    ) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let handler: Arc<Handler> = Arc::new(handler);
+        let recorded = Arc::clone(&requests);
+        let task = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let handler = Arc::clone(&handler);
+                let recorded = Arc::clone(&recorded);
+                tokio::spawn(async move {
+                    let _ = serve(stream, handler, recorded).await;
+                });
+            }
+        });
+        Self {
+            base,
+            requests,
+            task,
+        }
+    }
+
+    pub fn url(&self, path: &str) -> String {
+        format!("{}{path}", self.base)
+    }
+
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn serve(
+    mut stream: TcpStream,
+    handler: Arc<Handler>,
+    recorded: Arc<Mutex<Vec<RecordedRequest>>>,
+) -> std::io::Result<()> {
+    let request = read_request(&mut stream).await?;
+    let response = handler(&request);
+    recorded.lock().unwrap().push(request);
+    write_response(&mut stream, &response).await
+}
+
+async fn read_request(stream: &mut TcpStream) -> std::io::Result<RecordedRequest> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0; 4096];
+    let header_end = loop {
+        let read = stream.read(&mut chunk).await?;
+        if read == 0 {
+            return Err(std::io::ErrorKind::UnexpectedEof.into());
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+        if let Some(end) = buffer.windows(4).position(|window| window == b"\r\n\r\n") {
+            break end + 4;
+        }
+    };
+
+    let head = String::from_utf8_lossy(&buffer[..header_end]).to_string();
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next().unwrap_or_default().split(' ');
+    let method = request_line.next().unwrap_or_default().to_string();
+    let path = request_line.next().unwrap_or_default().to_string();
+    let headers = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+        .collect::<Vec<_>>();
+    let mut request = RecordedRequest {
+        method,
+        path,
+        headers,
+        body: buffer[header_end..].to_vec(),
+    };
+
+    let length = request
+        .header("content-length")
+        .and_then(|length| length.parse::<usize>().ok())
+        .unwrap_or_default();
+    while request.body.len() < length {
+        let read = stream.read(&mut chunk).await?;
+        if read == 0 {
+            break;
+        }
+        request.body.extend_from_slice(&chunk[..read]);
+    }
+    Ok(request)
+}
+
+async fn write_response(stream: &mut TcpStream, response: &MockResponse) -> std::io::Result<()> {
+    let mut head = format!("HTTP/1.1 {} Mock\r\n", response.status);
+    for (name, value) in &response.headers {
+        head.push_str(&format!("{name}: {value}\r\n"));
+    }
+    head.push_str(&format!(
+        "Content-Length: {}\r\nConnection: close\r\n\r\n",
+        response.body.len()
+    ));
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(&response.body).await?;
+    stream.shutdown().await
+}
+
+/// An app on the home screen with one feed and nothing written to disk.
+pub fn test_app() -> App {
+    let mut feed_state = ListState::default();
+    feed_state.select_first();
+    App {
+        running: true,
+        config: Config::default(),
+        screen: Screen::Home,
+        panes: Panes::Single,
+        input: Input::default(),
+        input_mode: InputMode::Normal,
+        feed_list: FeedList {
+            items: vec![Feed {
+                title: "Example feed".to_string(),
+                url: "https://example.com/feed.xml".to_string(),
+            }],
+            state: feed_state,
+        },
+        health: HealthStore::in_memory(),
+        entries: EntryStore::in_memory(),
+        entry_list: EntryList {
+            items: Vec::new(),
+            state: ListState::default(),
+        },
+        current_feed: None,
+        current_entry: Entry::default(),
+        history: Vec::new(),
+        reading: ReadingStore::in_memory(),
+        offline_since: None,
+        scroll_offset: 0,
+        max_scroll: 0,
+        confirmation_popup: None,
+        show_feed_details: false,
+        notice: None,
+        tts: None,
+        tts_downloading: false,
+        narration: crate::tts::NarrationUiState::Idle,
+        daemon_attached: false,
+        link_picker: None,
+        external_viewer: None,
+        highlighter: Highlighter::default(),
+        code_wrap: false,
+        code_scroll: 0,
+        images: ImageStore::new(&ImageSettings {
+            protocol: Some(ImageProtocol::Placeholder),
+            ..ImageSettings::default()
+        }),
+    }
+}
@@ -3,7 +3,9 @@ mod narration;
 mod text;
 
 pub use model::{download_model, model_dir, model_ready, TtsModelEvent};
-pub use narration::{spawn_narration, NarrationEvent, NarrationHandle, NarrationUiState};
+pub use narration::{
+    spawn_narration, spawn_remote_narration, NarrationEvent, NarrationHandle, NarrationUiState,
+};
 pub use text::{build_narration_units, NarrationTextError, NarrationUnit};
 
 use std::fmt;
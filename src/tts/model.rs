@@ -1,13 +1,16 @@
 use std::{
-    env, fs,
+    fs,
     path::{Path, PathBuf},
     time::Duration,
 };
 
 use futures::StreamExt;
-use reqwest::Client;
+use reqwest::Url;
 use tokio::io::AsyncWriteExt;
 
+use crate::config::NetworkSettings;
+use crate::http::client_builder;
+
 const URL: &str =
     "https://github.com/k2-fsa/sherpa-onnx/releases/download/tts-models/kokoro-en-v0_19.tar.bz2";
 
@@ -31,7 +34,10 @@ pub fn model_ready() -> bool {
         && dir.join("espeak-ng-data").is_dir()
 }
 
-pub async fn download_model(mut on_progress: impl FnMut(u8) + Send) -> Result<(), String> {
+pub async fn download_model(
+    network: &NetworkSettings,
+    mut on_progress: impl FnMut(u8) + Send,
+) -> Result<(), String> {
     if model_ready() {
         on_progress(100);
         return Ok(());
@@ -46,7 +52,7 @@ pub async fn download_model(mut on_progress: impl FnMut(u8) + Send) -> Result<()
     }
 
     let archive = models.join("kokoro-en-v0_19.tar.bz2.part");
-    download_archive(&archive, &mut on_progress).await?;
+    download_archive(network, &archive, &mut on_progress).await?;
     on_progress(99);
 
     let archive_path = archive.clone();
@@ -72,17 +78,18 @@ pub async fn download_model(mut on_progress: impl FnMut(u8) + Send) -> Result<()
 }
 
 async fn download_archive(
+    network: &NetworkSettings,
     path: &Path,
     on_progress: &mut (impl FnMut(u8) + Send),
 ) -> Result<(), String> {
-    let client = Client::builder()
+    let url = Url::parse(URL).map_err(|e| e.to_string())?;
+    let client = client_builder(network, &url, None)?
         .connect_timeout(Duration::from_secs(30))
         .timeout(Duration::from_secs(60 * 30))
-        .user_agent(concat!("fead/", env!("CARGO_PKG_VERSION")))
         .build()
         .map_err(|e| e.to_string())?;
 
-    let response = client.get(URL).send().await.map_err(|e| e.to_string())?;
+    let response = client.get(url).send().await.map_err(|e| e.to_string())?;
     if !response.status().is_success() {
         return Err(format!("download failed: HTTP {}", response.status()));
     }
@@ -1,14 +1,20 @@
 use std::collections::VecDeque;
 use std::num::NonZero;
+use std::path::Path;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 
 use rodio::buffer::SamplesBuffer;
 use rodio::{DeviceSinkBuilder, MixerDeviceSink, Player};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
 use sherpa_onnx::GenerationConfig;
 use tokio::sync::mpsc;
 
+use crate::daemon::client::{ClientError, DaemonClient};
+use crate::daemon::protocol::{NARRATION, PAUSE, PLAY, STOP, WATCH};
+
 use super::text::NarrationUnit;
 use super::TTS;
 
@@ -17,7 +23,8 @@ const START_AFTER: usize = 2;
 const DEFAULT_SAMPLE_RATE: u32 = 24_000;
 const POLL_INTERVAL: Duration = Duration::from_millis(100);
 
-#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
 pub enum NarrationUiState {
     #[default]
     Idle,
@@ -73,7 +80,8 @@ impl NarrationUiState {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum NarrationEvent {
     State(NarrationUiState),
     Error(String),
@@ -148,6 +156,57 @@ pub fn spawn_narration() -> (NarrationHandle, mpsc::UnboundedReceiver<NarrationE
     (NarrationHandle { cmd_tx }, event_rx)
 }
 
+/// Hands narration to a running daemon so playback outlives this process.
+/// Commands become control-socket calls and the daemon's narration events are
+/// relayed back; shutting the handle down leaves the daemon playing.
+pub async fn spawn_remote_narration(
+    socket: &Path,
+) -> Result<(NarrationHandle, mpsc::UnboundedReceiver<NarrationEvent>), ClientError> {
+    let mut commands = DaemonClient::connect(socket).await?;
+    let mut watcher = DaemonClient::connect(socket).await?;
+    let current = watcher.call(WATCH, Value::Null).await?;
+
+    let (cmd_tx, mut cmd_rx) = mpsc::unbounded_channel();
+    let (event_tx, event_rx) = mpsc::unbounded_channel();
+    if let Ok(state) = serde_json::from_value(current) {
+        emit(&event_tx, NarrationEvent::State(state));
+    }
+
+    let relay_tx = event_tx.clone();
+    tokio::spawn(async move {
+        while let Ok(notification) = watcher.next_notification().await {
+            if notification.method != NARRATION {
+                continue;
+            }
+            if let Ok(event) = serde_json::from_value(notification.params) {
+                emit(&relay_tx, event);
+            }
+        }
+        emit(
+            &relay_tx,
+            NarrationEvent::Error("Lost connection to the daemon.".into()),
+        );
+    });
+
+    tokio::spawn(async move {
+        while let Some(cmd) = cmd_rx.recv().await {
+            let outcome = match cmd {
+                Command::Play { units } => commands.call(PLAY, json!({ "units": units })).await,
+                Command::TogglePause => commands.call(PAUSE, Value::Null).await,
+                Command::Stop => commands.call(STOP, Value::Null).await,
+                Command::SetEngine(_) => continue,
+                Command::Shutdown => break,
+            };
+            if let Err(error) = outcome {
+                emit(&event_tx, NarrationEvent::Error(error.to_string()));
+                emit(&event_tx, NarrationEvent::State(NarrationUiState::Error));
+            }
+        }
+    });
+
+    Ok((NarrationHandle { cmd_tx }, event_rx))
+}
+
 async fn run_controller(
     mut cmd_rx: mpsc::UnboundedReceiver<Command>,
     event_tx: mpsc::UnboundedSender<NarrationEvent>,
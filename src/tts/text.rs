@@ -1,3 +1,4 @@
+use serde::{Deserialize, Serialize};
 use unicode_segmentation::UnicodeSegmentation;
 
 use crate::feed::entries::{ContentKind, EntryContent};
@@ -6,7 +7,8 @@ use crate::feed::reader::html_to_plain_text;
 const MAX_UNIT_CHARS: usize = 400;
 const SILENCE_MS: u32 = 350;
 
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum NarrationUnit {
     Speech(String),
     Silence { ms: u32 },
@@ -24,20 +24,40 @@ where
     }
 
     pub fn init(&mut self) -> AppResult<()> {
-        terminal::enable_raw_mode()?;
-        crossterm::execute!(io::stderr(), EnterAlternateScreen, EnableMouseCapture)?;
+        self.enter()?;
 
         let panic_hook = panic::take_hook();
         panic::set_hook(Box::new(move |panic| {
             let _ = Self::reset();
             panic_hook(panic);
         }));
+        Ok(())
+    }
 
+    fn enter(&mut self) -> AppResult<()> {
+        terminal::enable_raw_mode()?;
+        crossterm::execute!(io::stderr(), EnterAlternateScreen, EnableMouseCapture)?;
         self.terminal.hide_cursor()?;
         self.terminal.clear()?;
         Ok(())
     }
 
+    /// Hands the terminal to another program, such as a pager, until
+    /// [`Tui::resume`].
+    pub async fn suspend(&mut self) -> AppResult<()> {
+        self.events.pause().await;
+        Self::reset()?;
+        self.terminal.show_cursor()?;
+        Ok(())
+    }
+
+    /// Takes the terminal back and redraws from scratch on the next draw.
+    pub fn resume(&mut self) -> AppResult<()> {
+        self.enter()?;
+        self.events.resume();
+        Ok(())
+    }
+
     pub fn draw(&mut self, app: &mut App) -> AppResult<()> {
         self.terminal.draw(|frame| ui::render(app, frame))?;
         Ok(())
@@ -1,10 +1,15 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use image::DynamicImage;
 use ratatui::widgets::ListState;
 use ratatui::{
     layout::{Alignment, Constraint, Layout, Rect},
     style::{Color, Modifier, Style},
-    text::{Line, Text},
+    text::{Line, Span, Text},
     widgets::{
-        Block, BorderType, Borders, Clear, HighlightSpacing, List, ListItem, Paragraph, Wrap,
+        Block, BorderType, Borders, Clear, HighlightSpacing, List, ListItem, Padding, Paragraph,
+        Scrollbar, ScrollbarOrientation, ScrollbarState, Wrap,
     },
     Frame,
 };
@@ -12,7 +17,13 @@ use tui_markdown::from_str;
 
 use crate::{
     app::{App, ConfirmationChoice, ConfirmationKind, InputMode, Notice},
-    screen::Screen,
+    article::{self, table::TableStyle, LinkKind, Segment},
+    feed::{
+        cache::format_age,
+        schedule::{format_interval, format_until},
+    },
+    images::ImageState,
+    screen::{Panes, Screen},
 };
 
 const TEXT: Color = Color::Cyan;
@@ -20,6 +31,10 @@ const ACCENT: Color = Color::Yellow;
 const SURFACE: Color = Color::Black;
 const DANGER: Color = Color::Red;
 const SUCCESS: Color = Color::Green;
+/// Columns taken by the rule and gap left of code blocks.
+const CODE_INDENT: u16 = 2;
+/// Columns kept right of the article for its scrollbar.
+const SCROLLBAR_WIDTH: u16 = 2;
 
 pub fn render(app: &mut App, frame: &mut Frame) {
     frame.render_widget(Block::new().style(Style::new().bg(SURFACE)), frame.area());
@@ -30,6 +45,33 @@ pub fn render(app: &mut App, frame: &mut Frame) {
         .constraints([Constraint::Min(1)])
         .split(frame.area())[0];
 
+    let previewing = previewing(app);
+    let header = header_text(app, previewing);
+    if app.panes.is_split() {
+        render_panes(app, frame, area, header, previewing);
+    } else {
+        render_single(app, frame, area, header, previewing);
+    }
+
+    if app.show_feed_details {
+        render_feed_details(app, frame);
+    }
+    if app.link_picker.is_some() {
+        render_link_picker(app, frame);
+    }
+    if app.confirmation_popup.is_some() {
+        render_confirmation(app, frame);
+    }
+}
+
+/// One screen at a time, for narrow terminals.
+fn render_single(
+    app: &mut App,
+    frame: &mut Frame,
+    area: Rect,
+    header: Text<'static>,
+    previewing: bool,
+) {
     let sections = match app.screen {
         Screen::Home => Layout::vertical([
             Constraint::Length(3),
@@ -39,14 +81,13 @@ pub fn render(app: &mut App, frame: &mut Frame) {
         ])
         .split(area),
         Screen::Feed | Screen::Article => Layout::vertical([
-            Constraint::Length(3),
+            Constraint::Length(header.height() as u16 + 2),
             Constraint::Min(3),
             Constraint::Length(1),
         ])
         .split(area),
     };
 
-    render_header(app, frame, sections[0]);
     match app.screen {
         Screen::Home => {
             render_input(app, frame, sections[1]);
@@ -62,27 +103,164 @@ pub fn render(app: &mut App, frame: &mut Frame) {
             render_status(app, frame, sections[2]);
         }
     }
+    // Drawn after the body, which settles how far the article scrolls.
+    render_header(app, frame, sections[0], header, previewing);
+}
 
-    if app.confirmation_popup.is_some() {
-        render_confirmation(app, frame);
+/// Feeds, articles and the article side by side. Two panes show the focused
+/// list and the pane to its right; the focused pane's border is highlighted.
+fn render_panes(
+    app: &mut App,
+    frame: &mut Frame,
+    area: Rect,
+    header: Text<'static>,
+    previewing: bool,
+) {
+    let [header_area, body, status] = Layout::vertical([
+        Constraint::Length(header.height() as u16 + 2),
+        Constraint::Min(3),
+        Constraint::Length(1),
+    ])
+    .areas(area);
+    let panes = visible_panes(app);
+    let constraints = panes.iter().map(|pane| match (app.panes, pane) {
+        (_, Screen::Article) => Constraint::Fill(1),
+        (Panes::Three, Screen::Home) => Constraint::Percentage(20),
+        (Panes::Three, _) => Constraint::Percentage(30),
+        _ => Constraint::Percentage(40),
+    });
+    let columns = Layout::horizontal(constraints).spacing(1).split(body);
+
+    for (pane, column) in panes.into_iter().zip(columns.iter().copied()) {
+        match pane {
+            Screen::Home => {
+                let [input, list] =
+                    Layout::vertical([Constraint::Length(3), Constraint::Min(3)]).areas(column);
+                render_input(app, frame, input);
+                render_feed_list(app, frame, list);
+            }
+            Screen::Feed => render_article_list(app, frame, column),
+            Screen::Article => {
+                let block = pane_block(app, Screen::Article, "Article").padding(Padding::left(1));
+                if previewing {
+                    let inner = block.inner(column);
+                    frame.render_widget(block, column);
+                    render_article(app, frame, inner);
+                } else {
+                    render_empty(
+                        frame,
+                        column,
+                        block,
+                        "Nothing to preview",
+                        "Select an article to read it here.",
+                    );
+                }
+            }
+        }
     }
+    render_status(app, frame, status);
+    render_header(app, frame, header_area, header, previewing);
 }
 
-fn render_header(app: &App, frame: &mut Frame, area: Rect) {
-    let context = match app.screen {
-        Screen::Home => "Choose a feed or enter a new one to get started.",
-        Screen::Feed => "Choose an article to read.",
-        Screen::Article => app.current_entry.title.as_str(),
-    };
+/// The panes drawn side by side, left to right.
+fn visible_panes(app: &App) -> Vec<Screen> {
+    match (app.panes, app.screen) {
+        (Panes::Single, screen) => vec![screen],
+        (Panes::Two, Screen::Home) => vec![Screen::Home, Screen::Feed],
+        (Panes::Two, _) => vec![Screen::Feed, Screen::Article],
+        (Panes::Three, _) => vec![Screen::Home, Screen::Feed, Screen::Article],
+    }
+}
+
+/// Whether an article is on screen, opened or in the preview pane.
+fn previewing(app: &App) -> bool {
+    match app.screen {
+        Screen::Article => true,
+        _ if !visible_panes(app).contains(&Screen::Article) => false,
+        _ => !app.current_entry.title.is_empty() || app.current_entry.body().is_some(),
+    }
+}
+
+fn header_text(app: &App, previewing: bool) -> Text<'static> {
+    let entry = &app.current_entry;
+    match app.screen {
+        Screen::Home if !previewing => {
+            Text::raw("Choose a feed or enter a new one to get started.")
+        }
+        Screen::Feed if !previewing => Text::raw("Choose an article to read."),
+        _ => {
+            let mut lines = vec![Line::styled(
+                entry.title.clone(),
+                Style::new().add_modifier(Modifier::BOLD),
+            )];
+            let date = entry.published.as_deref().map(|published| {
+                DateTime::parse_from_rfc3339(published)
+                    .map(|date| date.format("%-d %B %Y").to_string())
+                    .unwrap_or_else(|_| published.to_string())
+            });
+            let byline = [
+                app.article_feed().map(|feed| feed.title.clone()),
+                entry.author.clone(),
+                date,
+            ]
+            .into_iter()
+            .flatten()
+            .filter(|part| !part.trim().is_empty())
+            .collect::<Vec<_>>()
+            .join("  ·  ");
+            if !byline.is_empty() {
+                lines.push(Line::styled(byline, Style::new().fg(ACCENT)));
+            }
+            let words = article::word_count(entry);
+            if words > 0 {
+                lines.push(Line::styled(
+                    format!(
+                        "{} words  ·  {} min read",
+                        format_count(words),
+                        article::reading_minutes(words)
+                    ),
+                    Style::new().fg(Color::White),
+                ));
+            }
+            if let Some(link) = &entry.link {
+                lines.push(Line::styled(
+                    link.clone(),
+                    Style::new().fg(Color::White).add_modifier(Modifier::DIM),
+                ));
+            }
+            Text::from(lines)
+        }
+    }
+}
+
+fn render_header(
+    app: &App,
+    frame: &mut Frame,
+    area: Rect,
+    context: Text<'static>,
+    previewing: bool,
+) {
+    let mut block = Block::new()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .title("Fead")
+        .title_alignment(Alignment::Center);
+    if let Some(fetched_at) = app.offline_since.filter(|_| app.screen != Screen::Home) {
+        let age = format_age(fetched_at.elapsed().unwrap_or_default());
+        block = block.title(
+            Line::styled(format!(" offline copy · {age} "), Style::new().fg(DANGER))
+                .right_aligned(),
+        );
+    }
+    if previewing && app.max_scroll > 0 {
+        let progress = u32::from(app.scroll_offset) * 100 / u32::from(app.max_scroll);
+        block = block.title_bottom(
+            Line::styled(format!(" {progress}% "), Style::new().fg(ACCENT)).right_aligned(),
+        );
+    }
     frame.render_widget(
         Paragraph::new(context)
-            .block(
-                Block::new()
-                    .borders(Borders::ALL)
-                    .border_type(BorderType::Rounded)
-                    .title("Fead")
-                    .title_alignment(Alignment::Center),
-            )
+            .block(block)
             .style(Style::new().fg(TEXT).bg(SURFACE))
             .alignment(Alignment::Center),
         area,
@@ -127,24 +305,47 @@ fn render_input(app: &App, frame: &mut Frame, area: Rect) {
 }
 
 fn render_feed_list(app: &mut App, frame: &mut Frame, area: Rect) {
+    let block = pane_block(app, Screen::Home, "Feeds");
+    let health = &app.health;
     let items = app
         .feed_list
         .items
         .iter()
-        .map(|feed| ListItem::new(Line::from(feed.title.as_str())))
+        .map(|feed| {
+            let badge = match health.get(&feed.url) {
+                Some(record) if record.gone => Some("✖ "),
+                Some(record) if record.is_failing() => Some("⚠ "),
+                _ => None,
+            };
+            let line = match badge {
+                Some(badge) => Line::from(vec![
+                    Span::styled(badge, Style::new().fg(DANGER)),
+                    Span::raw(feed.title.as_str()),
+                ]),
+                None => Line::from(feed.title.as_str()),
+            };
+            ListItem::new(line)
+        })
         .collect::<Vec<_>>();
     render_list(
         frame,
         area,
         items,
         &mut app.feed_list.state,
-        "Feeds",
+        block,
         "No feeds yet",
         "Press a and paste an RSS or Atom URL.",
     );
 }
 
 fn render_article_list(app: &mut App, frame: &mut Frame, area: Rect) {
+    let block = pane_block(app, Screen::Feed, "Articles");
+    // Beside the feeds, the list stays empty until a feed is opened.
+    let (empty_title, empty_body) = if app.screen == Screen::Home {
+        ("No feed open", "Open a feed to list its articles.")
+    } else {
+        ("No articles", "This feed did not return any entries.")
+    };
     let items = app
         .entry_list
         .items
@@ -156,9 +357,9 @@ fn render_article_list(app: &mut App, frame: &mut Frame, area: Rect) {
         area,
         items,
         &mut app.entry_list.state,
-        "Articles",
-        "No articles",
-        "This feed did not return any entries.",
+        block,
+        empty_title,
+        empty_body,
     );
 }
 
@@ -167,17 +368,17 @@ fn render_list(
     area: Rect,
     items: Vec<ListItem<'_>>,
     state: &mut ListState,
-    block_title: &'static str,
+    block: Block<'static>,
     empty_title: &str,
     empty_body: &str,
 ) {
     if items.is_empty() {
-        render_empty(frame, area, block_title, empty_title, empty_body);
+        render_empty(frame, area, block, empty_title, empty_body);
         return;
     }
 
     let list = List::new(items)
-        .block(list_block(block_title))
+        .block(block)
         .style(Style::new().fg(TEXT))
         .highlight_style(Style::new().fg(ACCENT).add_modifier(Modifier::BOLD))
         .highlight_symbol("> ")
@@ -185,30 +386,172 @@ fn render_list(
     frame.render_stateful_widget(list, area, state);
 }
 
+/// A laid-out piece of the article and the rows it takes.
+enum ArticleBlock<'a> {
+    Text(Text<'a>),
+    Code {
+        text: Text<'static>,
+        wrap: bool,
+    },
+    Table(Vec<Line<'static>>),
+    Picture {
+        url: &'a str,
+        picture: Arc<DynamicImage>,
+        size: (u16, u16),
+    },
+    Placeholder(Line<'a>),
+}
+
+impl ArticleBlock<'_> {
+    fn rows(&self, width: u16) -> usize {
+        match self {
+            Self::Text(text) => wrapped_line_count(text, width),
+            Self::Code { text, wrap: true } => {
+                wrapped_line_count(text, width.saturating_sub(CODE_INDENT).max(1))
+            }
+            Self::Code { text, .. } => text.lines.len(),
+            Self::Table(lines) => lines.len(),
+            Self::Picture { size, .. } => size.1 as usize,
+            Self::Placeholder(_) => 1,
+        }
+    }
+}
+
 fn render_article(app: &mut App, frame: &mut Frame, area: Rect) {
-    let Some(content) = app.current_entry.body().cloned() else {
+    if app.current_entry.body().is_none() {
         frame.render_widget(
             Paragraph::new("This article has no readable content.").style(Style::new().fg(TEXT)),
             area,
         );
         app.update_article_viewport(0, area.height);
         return;
-    };
-    let markdown;
-    let text = if content.kind.is_markup() {
-        markdown = htmd::convert(&content.value).unwrap_or_else(|_| content.value.clone());
-        from_str(&markdown)
-    } else {
-        Text::raw(content.value.as_str())
-    };
-    let line_count = wrapped_line_count(&text, area.width.max(1));
-    let paragraph = Paragraph::new(text)
-        .style(Style::new().fg(TEXT))
-        .wrap(Wrap { trim: true })
-        .scroll((app.scroll_offset, 0));
+    }
+    let [area, scrollbar_area] =
+        Layout::horizontal([Constraint::Min(1), Constraint::Length(SCROLLBAR_WIDTH)]).areas(area);
+    let content = article::parse(&app.current_entry);
+    let width = area.width.max(1);
+    let blocks: Vec<ArticleBlock<'_>> = content
+        .segments
+        .iter()
+        .map(|segment| match segment {
+            Segment::Text(text) if content.markdown => ArticleBlock::Text(from_str(text)),
+            Segment::Text(text) => ArticleBlock::Text(Text::raw(text.as_str())),
+            Segment::Code(block) => ArticleBlock::Code {
+                text: Text::from(app.highlighter.highlight(block).as_ref().clone()),
+                wrap: app.code_wrap,
+            },
+            Segment::Table(table) => ArticleBlock::Table(table.layout(
+                width,
+                TableStyle {
+                    border: Style::new().fg(Color::DarkGray),
+                    header: Style::new().fg(ACCENT).add_modifier(Modifier::BOLD),
+                    cell: Style::new().fg(TEXT),
+                },
+            )),
+            Segment::Image(image) => match app.images.get(&image.url).cloned() {
+                Some(ImageState::Ready(picture)) => ArticleBlock::Picture {
+                    url: &image.url,
+                    size: app.images.size_for(&picture, width),
+                    picture,
+                },
+                state => {
+                    let mut label = image.placeholder();
+                    if matches!(state, Some(ImageState::Loading)) {
+                        label.push_str(" (loading)");
+                    }
+                    ArticleBlock::Placeholder(Line::styled(
+                        label,
+                        Style::new().fg(TEXT).add_modifier(Modifier::DIM),
+                    ))
+                }
+            },
+        })
+        .collect();
 
+    // Blocks are separated by a blank row, as paragraphs are.
+    let rows: Vec<usize> = blocks.iter().map(|block| block.rows(width)).collect();
+    let line_count = rows.iter().sum::<usize>() + blocks.len().saturating_sub(1);
     app.update_article_viewport(line_count, area.height);
-    frame.render_widget(paragraph, area);
+    let widest_code = blocks
+        .iter()
+        .filter_map(|block| match block {
+            ArticleBlock::Code { text, wrap: false } => Some(text.width()),
+            _ => None,
+        })
+        .max()
+        .unwrap_or(0);
+    let code_width = width.saturating_sub(CODE_INDENT) as usize;
+    app.code_scroll = app
+        .code_scroll
+        .min(widest_code.saturating_sub(code_width) as u16);
+
+    let (first, last) = (
+        app.scroll_offset as usize,
+        app.scroll_offset as usize + area.height as usize,
+    );
+    let mut top = 0;
+    for (block, rows) in blocks.into_iter().zip(rows) {
+        let bottom = top + rows;
+        let (start, end) = (top.max(first), bottom.min(last));
+        if start < end {
+            let skip = (start - top) as u16;
+            let target = Rect::new(
+                area.x,
+                area.y + (start - first) as u16,
+                area.width,
+                (end - start) as u16,
+            );
+            match block {
+                ArticleBlock::Text(text) => frame.render_widget(
+                    Paragraph::new(text)
+                        .style(Style::new().fg(TEXT))
+                        .wrap(Wrap { trim: true })
+                        .scroll((skip, 0)),
+                    target,
+                ),
+                ArticleBlock::Code { text, wrap } => {
+                    let mut paragraph = Paragraph::new(text)
+                        .block(
+                            Block::new()
+                                .borders(Borders::LEFT)
+                                .border_style(Style::new().fg(Color::DarkGray))
+                                .padding(Padding::left(CODE_INDENT - 1)),
+                        )
+                        .style(Style::new().fg(TEXT));
+                    paragraph = if wrap {
+                        paragraph.wrap(Wrap { trim: false }).scroll((skip, 0))
+                    } else {
+                        paragraph.scroll((skip, app.code_scroll))
+                    };
+                    frame.render_widget(paragraph, target);
+                }
+                ArticleBlock::Table(lines) => {
+                    frame.render_widget(Paragraph::new(lines).scroll((skip, 0)), target);
+                }
+                ArticleBlock::Picture { url, picture, size } => {
+                    app.images
+                        .draw(url, &picture, size, skip, target, frame.buffer_mut());
+                }
+                ArticleBlock::Placeholder(line) => frame.render_widget(line, target),
+            }
+        }
+        top = bottom + 1;
+    }
+
+    if app.max_scroll > 0 {
+        let mut state = ScrollbarState::new(app.max_scroll as usize)
+            .position(app.scroll_offset as usize)
+            .viewport_content_length(area.height as usize);
+        frame.render_stateful_widget(
+            Scrollbar::new(ScrollbarOrientation::VerticalRight)
+                .begin_symbol(None)
+                .end_symbol(None)
+                .style(Style::new().fg(Color::DarkGray))
+                .thumb_style(Style::new().fg(ACCENT)),
+            scrollbar_area,
+            &mut state,
+        );
+    }
 }
 
 fn render_status(app: &App, frame: &mut Frame, area: Rect) {
@@ -227,19 +570,19 @@ fn render_status(app: &App, frame: &mut Frame, area: Rect) {
     } else if let Some(status) = narration_status {
         (status, Style::new().fg(SUCCESS))
     } else {
-        (help_for(app.screen).to_string(), Style::new().fg(Color::White))
+        (help_for(app), Style::new().fg(Color::White))
     };
     frame.render_widget(Paragraph::new(text).style(style), area);
 }
 
-fn render_empty(frame: &mut Frame, area: Rect, block_title: &'static str, title: &str, body: &str) {
+fn render_empty(frame: &mut Frame, area: Rect, block: Block<'static>, title: &str, body: &str) {
     let text = vec![
         Line::styled(title, Style::new().fg(TEXT).add_modifier(Modifier::BOLD)),
         Line::styled(body, Style::new().fg(ACCENT)),
     ];
     frame.render_widget(
         Paragraph::new(text)
-            .block(list_block(block_title))
+            .block(block)
             .alignment(Alignment::Center),
         area,
     );
@@ -252,6 +595,7 @@ fn render_confirmation(app: &App, frame: &mut Frame) {
     let (title, accept_label, accent) = match popup.kind {
         ConfirmationKind::DeleteFeed => ("Delete Feed", "Delete", DANGER),
         ConfirmationKind::DownloadTtsModel => ("Download TTS", "Download", ACCENT),
+        ConfirmationKind::RunCommand => ("Run Command", "Run", DANGER),
     };
     let area = centered_fixed(frame.area(), 62, 12);
     frame.render_widget(Clear, area);
@@ -333,6 +677,151 @@ fn render_confirmation(app: &App, frame: &mut Frame) {
     );
 }
 
+fn render_feed_details(app: &App, frame: &mut Frame) {
+    let Some(feed) = app
+        .feed_list
+        .state
+        .selected()
+        .and_then(|selected| app.feed_list.items.get(selected))
+    else {
+        return;
+    };
+    let health = app.health.get(&feed.url).cloned().unwrap_or_default();
+    let since = |time: Option<DateTime<Utc>>| {
+        time.map_or_else(
+            || "never".to_string(),
+            |time| format_age((Utc::now() - time).to_std().unwrap_or_default()),
+        )
+    };
+    let label = |text: &str| Span::styled(format!("{text}: "), Style::new().fg(ACCENT));
+
+    let mut lines = vec![
+        Line::from(vec![label("Feed"), Span::raw(feed.title.as_str())]),
+        Line::from(vec![label("URL"), Span::raw(feed.url.as_str())]),
+        Line::from(vec![
+            label("Last success"),
+            Span::raw(since(health.last_success)),
+        ]),
+    ];
+    if health.gone {
+        lines.push(Line::from(Span::styled(
+            "The server reports this feed is gone (HTTP 410).",
+            Style::new().fg(DANGER),
+        )));
+    }
+    if health.is_failing() {
+        lines.push(Line::from(vec![
+            label("Failures"),
+            Span::styled(
+                format!("{} in a row", health.consecutive_failures),
+                Style::new().fg(DANGER),
+            ),
+        ]));
+    }
+    if let Some(error) = &health.last_error {
+        lines.push(Line::from(vec![
+            label("Last error"),
+            Span::raw(format!("{} ({error})", since(health.last_error_at))),
+        ]));
+    }
+    if let Some(schedule) = health.schedule.filter(|_| !health.gone) {
+        lines.push(Line::from(vec![
+            label("Next refresh"),
+            Span::raw(format!(
+                "{} ({}, {})",
+                format_until((schedule.next - Utc::now()).to_std().unwrap_or_default()),
+                format_interval(schedule.interval()),
+                schedule.source
+            )),
+        ]));
+    }
+
+    let area = centered_fixed(frame.area(), 72, 12);
+    frame.render_widget(Clear, area);
+    let block = Block::new()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::new().fg(if health.is_failing() { DANGER } else { ACCENT }))
+        .style(Style::new().bg(SURFACE))
+        .title("Feed Details")
+        .title_alignment(Alignment::Center)
+        .title_bottom(Line::from(" Esc close ").centered());
+    frame.render_widget(
+        Paragraph::new(lines)
+            .block(block)
+            .style(Style::new().fg(TEXT))
+            .wrap(Wrap { trim: true }),
+        area,
+    );
+}
+
+fn render_link_picker(app: &mut App, frame: &mut Frame) {
+    let Some(picker) = app.link_picker.as_mut() else {
+        return;
+    };
+    let area = centered_fixed(frame.area(), 80, 16);
+    frame.render_widget(Clear, area);
+    let block = Block::new()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::new().fg(ACCENT))
+        .style(Style::new().bg(SURFACE))
+        .title("Links")
+        .title_alignment(Alignment::Center)
+        .title_bottom(Line::from(" Enter open  ·  Esc close ").centered());
+    if picker.links.is_empty() {
+        frame.render_widget(
+            Paragraph::new("This article has no links.")
+                .block(block)
+                .style(Style::new().fg(TEXT))
+                .alignment(Alignment::Center),
+            area,
+        );
+        return;
+    }
+
+    let items = picker
+        .links
+        .iter()
+        .map(|link| {
+            let label = if link.label.trim().is_empty() {
+                link.url.as_str()
+            } else {
+                link.label.trim()
+            };
+            let mut spans = Vec::new();
+            if link.kind == LinkKind::Image {
+                spans.push(Span::styled("image: ", Style::new().fg(ACCENT)));
+            }
+            spans.push(Span::raw(label));
+            ListItem::new(vec![
+                Line::from(spans),
+                Line::styled(
+                    format!("  {}", link.url),
+                    Style::new().fg(Color::White).add_modifier(Modifier::DIM),
+                ),
+            ])
+        })
+        .collect::<Vec<_>>();
+    let list = List::new(items)
+        .block(block)
+        .style(Style::new().fg(TEXT))
+        .highlight_style(Style::new().fg(ACCENT).add_modifier(Modifier::BOLD))
+        .highlight_symbol("> ")
+        .highlight_spacing(HighlightSpacing::Always);
+    frame.render_stateful_widget(list, area, &mut picker.state);
+}
+
+/// A pane's block, its border highlighted when it has focus beside others.
+fn pane_block(app: &App, pane: Screen, title: &'static str) -> Block<'static> {
+    let block = list_block(title);
+    if app.panes.is_split() && app.screen == pane {
+        block.border_style(Style::new().fg(ACCENT))
+    } else {
+        block
+    }
+}
+
 fn list_block(title: &'static str) -> Block<'static> {
     Block::new()
         .borders(Borders::ALL)
@@ -341,18 +830,42 @@ fn list_block(title: &'static str) -> Block<'static> {
         .title_alignment(Alignment::Center)
 }
 
-fn help_for(screen: Screen) -> &'static str {
-    match screen {
+fn help_for(app: &App) -> String {
+    let help = match app.screen {
         Screen::Home => {
-            "↑/↓ move  ·  Enter open  ·  a add  ·  Backspace delete  ·  t TTS  ·  q quit"
+            "↑/↓ move  ·  Enter open  ·  a add  ·  r recent  ·  i details  ·  Backspace delete  ·  t TTS  ·  q quit".to_string()
+        }
+        Screen::Feed => {
+            "↑/↓ move  ·  Enter open  ·  x save  ·  b read later  ·  E EPUB of unread  ·  Esc back  ·  t TTS  ·  q quit".to_string()
         }
-        Screen::Feed => "↑/↓ move  ·  Enter open  ·  Esc back  ·  t TTS  ·  q quit",
         Screen::Article => {
-            "↑/↓ scroll  ·  Space play/pause  ·  s stop  ·  Esc back  ·  t TTS  ·  q quit"
+            let keys = &app.config.keys;
+            format!(
+                "↑/↓ scroll  ·  {}/{} next/previous  ·  {} next unread  ·  Space play/pause  ·  s stop  ·  w wrap code  ·  < > scroll code  ·  l links  ·  v pager  ·  e editor  ·  x save  ·  b read later  ·  Esc back  ·  t TTS  ·  q quit",
+                keys.next_article, keys.previous_article, keys.next_unread
+            )
         }
+    };
+    if app.panes.is_split() {
+        format!("Tab/Shift-Tab switch pane  ·  {help}")
+    } else {
+        help
     }
 }
 
+/// `1234567` as `1,234,567`.
+fn format_count(count: usize) -> String {
+    let digits = count.to_string();
+    let mut formatted = String::new();
+    for (index, digit) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index).is_multiple_of(3) {
+            formatted.push(',');
+        }
+        formatted.push(digit);
+    }
+    formatted
+}
+
 fn wrapped_line_count(text: &Text<'_>, width: u16) -> usize {
     let width = width as usize;
     text.lines
@@ -374,19 +887,18 @@ fn centered_fixed(area: Rect, preferred_width: u16, preferred_height: u16) -> Re
 
 #[cfg(test)]
 mod tests {
-    use ratatui::{backend::TestBackend, widgets::ListState, Terminal};
-    use tui_input::Input;
+    use std::time::{Duration, SystemTime};
+
+    use ratatui::{backend::TestBackend, Terminal};
 
     use crate::{
-        app::{
-            App, ConfirmationChoice, ConfirmationKind, ConfirmationPopup, EntryList, FeedList,
-            InputMode,
-        },
+        app::{ConfirmationChoice, ConfirmationKind, ConfirmationPopup},
         feed::{
             entries::{ContentKind, Entry, EntryContent},
-            feeds::Feed,
+            schedule::{RefreshHints, RefreshSchedule},
         },
-        screen::Screen,
+        screen::{Panes, Screen},
+        test_support::test_app,
     };
 
     use super::{render, ACCENT};
@@ -461,34 +973,188 @@ mod tests {
             .any(|line| line.contains("# literal * text _and_ `code`")));
     }
 
-    fn test_app() -> App {
-        let mut feed_state = ListState::default();
-        feed_state.select_first();
-        App {
-            running: true,
-            screen: Screen::Home,
-            input: Input::default(),
-            input_mode: InputMode::Normal,
-            feed_list: FeedList {
-                items: vec![Feed {
-                    title: "Example feed".to_string(),
-                    url: "https://example.com/feed.xml".to_string(),
-                }],
-                state: feed_state,
-            },
-            entry_list: EntryList {
-                items: Vec::new(),
-                state: ListState::default(),
-            },
-            current_entry: Entry::default(),
-            scroll_offset: 0,
-            max_scroll: 0,
-            confirmation_popup: None,
-            notice: None,
-            tts: None,
-            tts_downloading: false,
-            narration: crate::tts::NarrationUiState::Idle,
-        }
+    #[test]
+    fn shows_image_placeholders_and_lists_links() {
+        let mut app = test_app();
+        app.screen = Screen::Article;
+        app.current_entry = Entry {
+            title: "Pictures".to_string(),
+            link: Some("https://example.com/posts/1".to_string()),
+            content: Some(EntryContent {
+                value: r#"<p>Before.</p><p><img src="chart.png" alt="Sales chart"></p>
+                    <p>See <a href="/more">more</a>.</p>"#
+                    .to_string(),
+                kind: ContentKind::Html,
+            }),
+            ..Entry::default()
+        };
+        let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+
+        terminal.draw(|frame| render(&mut app, frame)).unwrap();
+        let lines = buffer_lines(terminal.backend().buffer());
+        let before = lines
+            .iter()
+            .position(|line| line.contains("Before."))
+            .unwrap();
+        let image = lines
+            .iter()
+            .position(|line| line.contains("[image: Sales chart]"))
+            .unwrap();
+        assert_eq!(image, before + 2);
+
+        app.open_link_picker();
+        terminal.draw(|frame| render(&mut app, frame)).unwrap();
+        let lines = buffer_lines(terminal.backend().buffer());
+        assert!(lines.iter().any(|line| line.contains("image: Sales chart")));
+        assert!(lines
+            .iter()
+            .any(|line| line.contains("https://example.com/more")));
+    }
+
+    #[test]
+    fn shows_article_metadata_and_reading_progress() {
+        let mut app = test_app();
+        app.screen = Screen::Article;
+        app.current_feed = Some(app.feed_list.items[0].clone());
+        app.current_entry = Entry {
+            title: "Long read".to_string(),
+            link: Some("https://example.com/long-read".to_string()),
+            author: Some("Ada Lovelace".to_string()),
+            published: Some("2025-07-14T18:20:00+00:00".to_string()),
+            content: Some(EntryContent {
+                value: "word word\n".repeat(600),
+                kind: ContentKind::Text,
+            }),
+            ..Entry::default()
+        };
+        let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+
+        terminal.draw(|frame| render(&mut app, frame)).unwrap();
+        let lines = buffer_lines(terminal.backend().buffer());
+        assert!(lines.iter().any(|line| line.contains("Long read")));
+        assert!(lines
+            .iter()
+            .any(|line| line.contains("Example feed  ·  Ada Lovelace  ·  14 July 2025")));
+        assert!(lines
+            .iter()
+            .any(|line| line.contains("1,200 words  ·  6 min read")));
+        assert!(lines
+            .iter()
+            .any(|line| line.contains("https://example.com/long-read")));
+        assert!(lines.iter().any(|line| line.contains(" 0% ")));
+
+        app.scroll_offset = app.max_scroll;
+        terminal.draw(|frame| render(&mut app, frame)).unwrap();
+        let lines = buffer_lines(terminal.backend().buffer());
+        assert!(lines.iter().any(|line| line.contains(" 100% ")));
+    }
+
+    #[test]
+    fn marks_offline_copies_with_their_age_in_the_header() {
+        let mut app = test_app();
+        app.screen = Screen::Feed;
+        app.offline_since = Some(SystemTime::now() - Duration::from_secs(3 * 3_600));
+        let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+
+        terminal.draw(|frame| render(&mut app, frame)).unwrap();
+
+        let lines = buffer_lines(terminal.backend().buffer());
+        assert!(lines[..4]
+            .iter()
+            .any(|line| line.contains("offline copy · 3h ago")));
+    }
+
+    #[test]
+    fn flags_failing_feeds_and_shows_their_error_history() {
+        let mut app = test_app();
+        app.health
+            .record_failure("https://example.com/feed.xml", "feed returned HTTP 503")
+            .unwrap();
+        let mut terminal = Terminal::new(TestBackend::new(100, 24)).unwrap();
+
+        terminal.draw(|frame| render(&mut app, frame)).unwrap();
+        let lines = buffer_lines(terminal.backend().buffer());
+        assert!(lines.iter().any(|line| line.contains("⚠ Example feed")));
+
+        app.show_feed_details = true;
+        terminal.draw(|frame| render(&mut app, frame)).unwrap();
+        let lines = buffer_lines(terminal.backend().buffer());
+        assert!(lines.iter().any(|line| line.contains("1 in a row")));
+        assert!(lines
+            .iter()
+            .any(|line| line.contains("feed returned HTTP 503")));
+    }
+
+    #[test]
+    fn shows_the_next_scheduled_refresh_in_feed_details() {
+        let mut app = test_app();
+        let url = "https://example.com/feed.xml";
+        let hints = RefreshHints {
+            feed_interval: Some(Duration::from_secs(2 * 3_600)),
+            ..RefreshHints::default()
+        };
+        let schedule = RefreshSchedule::plan(
+            &hints,
+            &app.config.feed(url),
+            &app.config.refresh,
+            chrono::Utc::now(),
+        );
+        app.health.schedule(url, schedule).unwrap();
+        app.show_feed_details = true;
+        let mut terminal = Terminal::new(TestBackend::new(100, 24)).unwrap();
+
+        terminal.draw(|frame| render(&mut app, frame)).unwrap();
+        let lines = buffer_lines(terminal.backend().buffer());
+        assert!(lines
+            .iter()
+            .any(|line| line.contains("Next refresh: in 1h (every 2h, feed hint)")));
+    }
+
+    #[test]
+    fn previews_the_selected_article_beside_the_lists_on_wide_terminals() {
+        let mut app = test_app();
+        app.current_feed = Some(app.feed_list.items[0].clone());
+        app.entry_list.items = ["First post", "Second post"]
+            .map(|title| Entry {
+                title: title.to_string(),
+                content: Some(EntryContent {
+                    value: format!("Body of the {}.", title.to_lowercase()),
+                    kind: ContentKind::Text,
+                }),
+                ..Entry::default()
+            })
+            .to_vec();
+        app.entry_list.state.select(Some(1));
+        app.screen = Screen::Feed;
+        app.resize(180);
+        assert_eq!(app.panes, Panes::Three);
+        let mut terminal = Terminal::new(TestBackend::new(180, 30)).unwrap();
+
+        terminal.draw(|frame| render(&mut app, frame)).unwrap();
+        let buffer = terminal.backend().buffer();
+        let lines = buffer_lines(buffer);
+        let find = |needle: &str| {
+            lines.iter().enumerate().find_map(|(row, line)| {
+                let column = line[..line.find(needle)?].chars().count();
+                Some((column as u16, row as u16))
+            })
+        };
+        let (feeds, _) = find("> Example feed").unwrap();
+        let (entries, _) = find("> Second post").unwrap();
+        let (preview, _) = find("Body of the second post.").unwrap();
+        assert!(feeds < entries && entries < preview);
+        let (articles_column, articles_row) = find("Articles").unwrap();
+        assert_eq!(buffer[(articles_column - 1, articles_row)].fg, ACCENT);
+
+        app.resize(120);
+        app.screen = Screen::Home;
+        terminal.draw(|frame| render(&mut app, frame)).unwrap();
+        let lines = buffer_lines(terminal.backend().buffer());
+        assert!(lines.iter().any(|line| line.contains("Feeds")));
+        assert!(!lines.iter().any(|line| line.contains("Body of the")));
+
+        app.resize(80);
+        assert_eq!(app.panes, Panes::Single);
     }
 
     fn buffer_lines(buffer: &ratatui::buffer::Buffer) -> Vec<String> {
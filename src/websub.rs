@@ -0,0 +1,769 @@
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    error::Error,
+    fmt, io,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use chrono::{DateTime, TimeDelta, Utc};
+use hmac::{digest::KeyInit, Hmac, Mac};
+use reqwest::{StatusCode, Url};
+use sha1::Sha1;
+use sha2::{Sha256, Sha384, Sha512};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+    sync::mpsc,
+    task::JoinHandle,
+};
+
+use crate::app::AppResult;
+use crate::config::{NetworkSettings, WebSubSettings};
+use crate::feed::{cache::fnv1a, entries::Entry, reader::parse_feed, store::EntryStore};
+use crate::http::client_builder;
+
+const CALLBACK_SEGMENT: &str = "websub/";
+const HUB_TIMEOUT: Duration = Duration::from_secs(15);
+/// Leases are renewed once less than this much of them remains.
+const RENEWAL_MARGIN: Duration = Duration::from_secs(24 * 60 * 60);
+/// Requests the hub never verified are retried after this long.
+const PENDING_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+/// Longest lease believed, and the one assumed when the hub names none.
+const MAX_LEASE: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+/// Random bytes in each subscription's `hub.secret`.
+const SECRET_BYTES: usize = 32;
+const MAX_HEADER_LINES: usize = 100;
+/// Longest request, header or chunk-size line read from a callback request.
+const MAX_LINE: usize = 8 * 1024;
+
+/// A hub advertised by a feed, and the topic URL to subscribe to there.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Hub {
+    pub hub: String,
+    pub topic: String,
+}
+
+/// Finds `rel="hub"` and `rel="self"` links in an Atom or RSS document. The
+/// topic defaults to the URL the feed was fetched from.
+pub fn discover_hub(content: &[u8], feed_url: &str) -> Option<Hub> {
+    let text = String::from_utf8_lossy(content);
+    let mut hub = None;
+    let mut topic = None;
+    for tag in link_tags(&text) {
+        let (Some(rel), Some(href)) = (attribute(tag, "rel"), attribute(tag, "href")) else {
+            continue;
+        };
+        for relation in rel.split_whitespace() {
+            match relation.to_ascii_lowercase().as_str() {
+                "hub" => hub = hub.or_else(|| Some(href.clone())),
+                "self" => topic = topic.or_else(|| Some(href.clone())),
+                _ => {}
+            }
+        }
+    }
+    Some(Hub {
+        hub: hub?,
+        topic: topic.unwrap_or_else(|| feed_url.to_string()),
+    })
+}
+
+fn link_tags(text: &str) -> impl Iterator<Item = &str> {
+    text.split('<').skip(1).filter_map(|rest| {
+        let tag = &rest[..rest.find('>')?];
+        let name = tag.split_whitespace().next()?;
+        (name.rsplit(':').next() == Some("link")).then_some(tag)
+    })
+}
+
+fn attribute(tag: &str, name: &str) -> Option<String> {
+    let mut rest = tag;
+    while let Some(index) = rest.find(name) {
+        let preceded_by_space = rest[..index].ends_with(char::is_whitespace);
+        rest = &rest[index + name.len()..];
+        let Some(value) = rest.trim_start().strip_prefix('=') else {
+            continue;
+        };
+        if !preceded_by_space {
+            continue;
+        }
+        let value = value.trim_start();
+        let quote = value.chars().next().filter(|c| matches!(c, '"' | '\''))?;
+        let value = &value[1..];
+        let end = value.find(quote)?;
+        return Some(value[..end].replace("&amp;", "&"));
+    }
+    None
+}
+
+#[derive(Debug)]
+pub enum WebSubError {
+    Bind(io::Error),
+    InvalidUrl(String),
+    Network(String),
+    Request(reqwest::Error),
+    Rejected(StatusCode),
+}
+
+impl fmt::Display for WebSubError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Bind(error) => write!(formatter, "could not start the callback server: {error}"),
+            Self::InvalidUrl(url) => write!(formatter, "{url} is not a valid URL"),
+            Self::Network(message) => formatter.write_str(message),
+            Self::Request(_) => formatter.write_str("hub request failed"),
+            Self::Rejected(status) => write!(formatter, "hub answered HTTP {}", status.as_u16()),
+        }
+    }
+}
+
+impl Error for WebSubError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Bind(error) => Some(error),
+            Self::Request(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for WebSubError {
+    fn from(error: reqwest::Error) -> Self {
+        Self::Request(error)
+    }
+}
+
+/// Feed content a hub delivered to the callback server.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PushedContent {
+    pub feed_url: String,
+    pub content: Vec<u8>,
+}
+
+/// Parses pushed content and adds its entries to the store, returning the
+/// entries that were new.
+pub fn ingest(store: &mut EntryStore, pushed: &PushedContent) -> AppResult<Vec<Entry>> {
+    let document = parse_feed(&pushed.content)?;
+    store.merge(&pushed.feed_url, &document)
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Mode {
+    Subscribe,
+    Unsubscribe,
+}
+
+impl Mode {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Subscribe => "subscribe",
+            Self::Unsubscribe => "unsubscribe",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Subscription {
+    feed_url: String,
+    hub: Hub,
+    /// A request the hub has not verified yet, and when it was sent.
+    pending: Option<(Mode, DateTime<Utc>)>,
+    expires_at: Option<DateTime<Utc>>,
+    /// Shared with the hub, which signs every push with it.
+    secret: String,
+}
+
+/// Subscriptions keyed by the id at the end of their callback URL.
+type Subscriptions = Arc<Mutex<BTreeMap<String, Subscription>>>;
+
+/// Subscribes to hubs and runs the HTTP server they call back.
+pub struct Subscriber {
+    network: NetworkSettings,
+    callback_base: Url,
+    lease: Duration,
+    local_addr: SocketAddr,
+    subscriptions: Subscriptions,
+    task: JoinHandle<()>,
+}
+
+impl Subscriber {
+    /// Starts the callback server. Content pushed by hubs arrives on the
+    /// returned channel.
+    pub async fn start(
+        settings: &WebSubSettings,
+        network: &NetworkSettings,
+    ) -> Result<(Self, mpsc::UnboundedReceiver<PushedContent>), WebSubError> {
+        let listener = TcpListener::bind(&settings.listen)
+            .await
+            .map_err(WebSubError::Bind)?;
+        let local_addr = listener.local_addr().map_err(WebSubError::Bind)?;
+        let callback_base = settings
+            .callback_url
+            .clone()
+            .unwrap_or_else(|| format!("http://{local_addr}/"));
+        let callback_base = Url::parse(&callback_base)
+            .map_err(|_| WebSubError::InvalidUrl(callback_base.clone()))?;
+
+        let subscriptions = Subscriptions::default();
+        let (pushed_tx, pushed_rx) = mpsc::unbounded_channel();
+        let max_body = network.max_feed_size;
+        let served = Arc::clone(&subscriptions);
+        let task = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let subscriptions = Arc::clone(&served);
+                let pushed_tx = pushed_tx.clone();
+                tokio::spawn(async move {
+                    let _ = serve(stream, &subscriptions, &pushed_tx, max_body).await;
+                });
+            }
+        });
+
+        let subscriber = Self {
+            network: network.clone(),
+            callback_base,
+            lease: Duration::from_secs(settings.lease_seconds),
+            local_addr,
+            subscriptions,
+            task,
+        };
+        Ok((subscriber, pushed_rx))
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Whether the topic has no verified subscription, or its lease is about
+    /// to run out.
+    pub fn needs_subscription(&self, topic: &str, now: DateTime<Utc>) -> bool {
+        let subscriptions = self.subscriptions.lock().unwrap();
+        let Some(subscription) = subscriptions.get(&callback_id(topic)) else {
+            return true;
+        };
+        match (subscription.pending, subscription.expires_at) {
+            (Some((_, requested_at)), _) => requested_at + PENDING_TIMEOUT < now,
+            (None, Some(expires_at)) => expires_at < now + RENEWAL_MARGIN,
+            (None, None) => true,
+        }
+    }
+
+    /// Asks the hub to push updates for `feed_url`. The subscription becomes
+    /// active once the hub verifies it against the callback server.
+    pub async fn subscribe(&self, feed_url: &str, hub: &Hub) -> Result<(), WebSubError> {
+        let id = callback_id(&hub.topic);
+        let secret = new_secret()?;
+        {
+            let mut subscriptions = self.subscriptions.lock().unwrap();
+            let expires_at = subscriptions.get(&id).and_then(|known| known.expires_at);
+            subscriptions.insert(
+                id.clone(),
+                Subscription {
+                    feed_url: feed_url.to_string(),
+                    hub: hub.clone(),
+                    pending: Some((Mode::Subscribe, Utc::now())),
+                    expires_at,
+                    secret: secret.clone(),
+                },
+            );
+        }
+        let result = self.request(Mode::Subscribe, &id, hub, Some(&secret)).await;
+        if result.is_err() {
+            self.clear_pending(&id);
+        }
+        result
+    }
+
+    pub async fn unsubscribe(&self, topic: &str) -> Result<(), WebSubError> {
+        let id = callback_id(topic);
+        let hub = {
+            let mut subscriptions = self.subscriptions.lock().unwrap();
+            let Some(subscription) = subscriptions.get_mut(&id) else {
+                return Ok(());
+            };
+            subscription.pending = Some((Mode::Unsubscribe, Utc::now()));
+            subscription.hub.clone()
+        };
+        let result = self.request(Mode::Unsubscribe, &id, &hub, None).await;
+        if result.is_err() {
+            self.clear_pending(&id);
+        }
+        result
+    }
+
+    async fn request(
+        &self,
+        mode: Mode,
+        id: &str,
+        hub: &Hub,
+        secret: Option<&str>,
+    ) -> Result<(), WebSubError> {
+        let hub_url = Url::parse(&hub.hub).map_err(|_| WebSubError::InvalidUrl(hub.hub.clone()))?;
+        let callback = self
+            .callback_base
+            .join(&format!("{CALLBACK_SEGMENT}{id}"))
+            .map_err(|_| WebSubError::InvalidUrl(self.callback_base.to_string()))?;
+        let client = client_builder(&self.network, &hub_url, None)
+            .map_err(WebSubError::Network)?
+            .timeout(HUB_TIMEOUT)
+            .build()?;
+
+        let lease = self.lease.as_secs().to_string();
+        let mut form = vec![
+            ("hub.mode", mode.as_str()),
+            ("hub.topic", hub.topic.as_str()),
+            ("hub.callback", callback.as_str()),
+            ("hub.lease_seconds", lease.as_str()),
+        ];
+        if let Some(secret) = secret {
+            form.push(("hub.secret", secret));
+        }
+        let response = client.post(hub_url).form(&form).send().await?;
+        if !response.status().is_success() {
+            return Err(WebSubError::Rejected(response.status()));
+        }
+        Ok(())
+    }
+
+    fn clear_pending(&self, id: &str) {
+        let mut subscriptions = self.subscriptions.lock().unwrap();
+        if let Some(subscription) = subscriptions.get_mut(id) {
+            subscription.pending = None;
+            if subscription.expires_at.is_none() {
+                subscriptions.remove(id);
+            }
+        }
+    }
+}
+
+impl Drop for Subscriber {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+fn callback_id(topic: &str) -> String {
+    format!("{:016x}", fnv1a(topic.as_bytes()))
+}
+
+fn new_secret() -> Result<String, WebSubError> {
+    let mut bytes = [0; SECRET_BYTES];
+    getrandom::fill(&mut bytes)
+        .map_err(|error| WebSubError::Network(format!("could not create a secret: {error}")))?;
+    Ok(hex::encode(bytes))
+}
+
+/// Checks an `X-Hub-Signature` header of the form `sha256=<hex>` against the
+/// body signed with the subscription's secret.
+fn is_signed(secret: &str, signature: Option<&str>, body: &[u8]) -> bool {
+    fn verify<M: Mac + KeyInit>(secret: &str, body: &[u8], expected: &[u8]) -> bool {
+        let Ok(mut mac) = <M as KeyInit>::new_from_slice(secret.as_bytes()) else {
+            return false;
+        };
+        mac.update(body);
+        mac.verify_slice(expected).is_ok()
+    }
+
+    let Some((method, signature)) = signature.and_then(|signature| signature.split_once('='))
+    else {
+        return false;
+    };
+    let Ok(expected) = hex::decode(signature.trim()) else {
+        return false;
+    };
+    match method.trim().to_ascii_lowercase().as_str() {
+        "sha1" => verify::<Hmac<Sha1>>(secret, body, &expected),
+        "sha256" => verify::<Hmac<Sha256>>(secret, body, &expected),
+        "sha384" => verify::<Hmac<Sha384>>(secret, body, &expected),
+        "sha512" => verify::<Hmac<Sha512>>(secret, body, &expected),
+        _ => false,
+    }
+}
+
+struct CallbackRequest {
+    method: String,
+    target: Url,
+    /// The `X-Hub-Signature` header.
+    signature: Option<String>,
+    body: Vec<u8>,
+}
+
+struct CallbackResponse {
+    status: StatusCode,
+    body: String,
+}
+
+impl CallbackResponse {
+    fn status(status: StatusCode) -> Self {
+        Self {
+            status,
+            body: String::new(),
+        }
+    }
+}
+
+async fn serve(
+    stream: TcpStream,
+    subscriptions: &Subscriptions,
+    pushed: &mpsc::UnboundedSender<PushedContent>,
+    max_body: u64,
+) -> io::Result<()> {
+    let mut stream = BufReader::new(stream);
+    let response = match read_request(&mut stream, max_body).await {
+        Ok(request) => respond(&request, subscriptions, pushed),
+        Err(error) if error.kind() == io::ErrorKind::FileTooLarge => {
+            CallbackResponse::status(StatusCode::PAYLOAD_TOO_LARGE)
+        }
+        Err(_) => CallbackResponse::status(StatusCode::BAD_REQUEST),
+    };
+
+    let head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        response.status.as_u16(),
+        response.status.canonical_reason().unwrap_or_default(),
+        response.body.len()
+    );
+    let stream = stream.get_mut();
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(response.body.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+async fn read_request(
+    stream: &mut BufReader<TcpStream>,
+    max_body: u64,
+) -> io::Result<CallbackRequest> {
+    let invalid = || io::Error::from(io::ErrorKind::InvalidData);
+    let mut line = String::new();
+    read_line(stream, &mut line).await?;
+    let mut request_line = line.split_whitespace();
+    let method = request_line.next().ok_or_else(invalid)?.to_string();
+    let target = Url::parse("http://callback.invalid")
+        .and_then(|base| base.join(request_line.next().unwrap_or_default()))
+        .map_err(|_| invalid())?;
+
+    let mut content_length = None;
+    let mut chunked = false;
+    let mut signature = None;
+    for _ in 0..MAX_HEADER_LINES {
+        line.clear();
+        read_line(stream, &mut line).await?;
+        let Some((name, value)) = line.trim_end().split_once(':') else {
+            break;
+        };
+        let value = value.trim();
+        if name.eq_ignore_ascii_case("content-length") {
+            content_length = Some(value.parse::<u64>().map_err(|_| invalid())?);
+        } else if name.eq_ignore_ascii_case("transfer-encoding") {
+            chunked = value.eq_ignore_ascii_case("chunked");
+        } else if name.eq_ignore_ascii_case("x-hub-signature") {
+            signature = Some(value.to_string());
+        }
+    }
+
+    let too_large = || io::Error::from(io::ErrorKind::FileTooLarge);
+    let mut body = Vec::new();
+    if chunked {
+        loop {
+            line.clear();
+            read_line(stream, &mut line).await?;
+            let size = line.trim().split(';').next().unwrap_or_default();
+            let size = u64::from_str_radix(size, 16).map_err(|_| invalid())?;
+            if size == 0 {
+                break;
+            }
+            if body.len() as u64 + size > max_body {
+                return Err(too_large());
+            }
+            let start = body.len();
+            body.resize(start + size as usize, 0);
+            stream.read_exact(&mut body[start..]).await?;
+            let mut crlf = [0; 2];
+            stream.read_exact(&mut crlf).await?;
+        }
+    } else if let Some(length) = content_length {
+        if length > max_body {
+            return Err(too_large());
+        }
+        body.resize(length as usize, 0);
+        stream.read_exact(&mut body).await?;
+    }
+
+    Ok(CallbackRequest {
+        method,
+        target,
+        signature,
+        body,
+    })
+}
+
+/// Reads one line into `line`, refusing lines longer than [`MAX_LINE`].
+async fn read_line(stream: &mut BufReader<TcpStream>, line: &mut String) -> io::Result<()> {
+    let read = (&mut *stream)
+        .take(MAX_LINE as u64 + 1)
+        .read_line(line)
+        .await?;
+    if read > MAX_LINE {
+        return Err(io::Error::from(io::ErrorKind::InvalidData));
+    }
+    Ok(())
+}
+
+fn respond(
+    request: &CallbackRequest,
+    subscriptions: &Subscriptions,
+    pushed: &mpsc::UnboundedSender<PushedContent>,
+) -> CallbackResponse {
+    let Some((_, id)) = request.target.path().rsplit_once(CALLBACK_SEGMENT) else {
+        return CallbackResponse::status(StatusCode::NOT_FOUND);
+    };
+    let mut subscriptions = subscriptions.lock().unwrap();
+    match request.method.as_str() {
+        "GET" => {
+            let query: BTreeMap<_, _> = request.target.query_pairs().collect();
+            verify(&mut subscriptions, id, &query)
+        }
+        "POST" => match subscriptions.get(id) {
+            // Unsigned or forged content is dropped. The hub still gets a
+            // success, as WebSub asks, so it learns nothing from the answer.
+            Some(subscription)
+                if subscription.expires_at.is_some()
+                    && !is_signed(
+                        &subscription.secret,
+                        request.signature.as_deref(),
+                        &request.body,
+                    ) =>
+            {
+                CallbackResponse::status(StatusCode::OK)
+            }
+            Some(subscription) if subscription.expires_at.is_some() => {
+                let _ = pushed.send(PushedContent {
+                    feed_url: subscription.feed_url.clone(),
+                    content: request.body.clone(),
+                });
+                CallbackResponse::status(StatusCode::OK)
+            }
+            // Tells the hub to stop delivering to this callback.
+            _ => CallbackResponse::status(StatusCode::GONE),
+        },
+        _ => CallbackResponse::status(StatusCode::METHOD_NOT_ALLOWED),
+    }
+}
+
+/// Answers the hub's intent verification, echoing the challenge only for a
+/// request we actually made.
+fn verify(
+    subscriptions: &mut BTreeMap<String, Subscription>,
+    id: &str,
+    query: &BTreeMap<Cow<'_, str>, Cow<'_, str>>,
+) -> CallbackResponse {
+    let param = |name: &str| query.get(name).map(|value| value.as_ref());
+    let Some(subscription) = subscriptions.get_mut(id) else {
+        return CallbackResponse::status(StatusCode::NOT_FOUND);
+    };
+    if param("hub.topic") != Some(subscription.hub.topic.as_str()) {
+        return CallbackResponse::status(StatusCode::NOT_FOUND);
+    }
+
+    let pending = subscription.pending.map(|(mode, _)| mode.as_str());
+    match (param("hub.mode"), param("hub.challenge")) {
+        (Some("denied"), _) => {
+            subscriptions.remove(id);
+            CallbackResponse::status(StatusCode::OK)
+        }
+        (Some(mode), Some(challenge)) if Some(mode) == pending => {
+            if mode == Mode::Unsubscribe.as_str() {
+                subscriptions.remove(id);
+            } else {
+                let lease = param("hub.lease_seconds")
+                    .and_then(|seconds| seconds.parse::<u64>().ok())
+                    .map_or(MAX_LEASE, Duration::from_secs)
+                    .min(MAX_LEASE);
+                let now = Utc::now();
+                subscription.pending = None;
+                subscription.expires_at = Some(
+                    TimeDelta::from_std(lease)
+                        .ok()
+                        .and_then(|lease| now.checked_add_signed(lease))
+                        .unwrap_or(now),
+                );
+            }
+            CallbackResponse {
+                status: StatusCode::OK,
+                body: challenge.to_string(),
+            }
+        }
+        (Some(_), None) => CallbackResponse::status(StatusCode::BAD_REQUEST),
+        _ => CallbackResponse::status(StatusCode::NOT_FOUND),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use hmac::{digest::KeyInit, Hmac, Mac};
+    use reqwest::{StatusCode, Url};
+    use sha2::Sha256;
+
+    use crate::config::{NetworkSettings, WebSubSettings};
+    use crate::feed::store::EntryStore;
+    use crate::test_support::{MockResponse, MockServer};
+
+    use std::time::Duration;
+
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpStream,
+        time::timeout,
+    };
+
+    use super::{discover_hub, ingest, Hub, Subscriber, MAX_LINE};
+
+    #[test]
+    fn discovers_hub_and_self_links() {
+        let atom = br#"<feed xmlns="http://www.w3.org/2005/Atom">
+            <link rel="hub" href="https://hub.example.com/" />
+            <link href='https://example.com/atom?a=1&amp;b=2' rel='self'/>
+        </feed>"#;
+        assert_eq!(
+            discover_hub(atom, "https://example.com/feed"),
+            Some(Hub {
+                hub: "https://hub.example.com/".to_string(),
+                topic: "https://example.com/atom?a=1&b=2".to_string(),
+            })
+        );
+
+        let rss = br#"<rss xmlns:atom="http://www.w3.org/2005/Atom"><channel>
+            <atom:link rel="hub" href="https://hub.example.com/"/>
+            <link>https://example.com/</link>
+        </channel></rss>"#;
+        let hub = discover_hub(rss, "https://example.com/rss").unwrap();
+        assert_eq!(hub.topic, "https://example.com/rss");
+
+        assert_eq!(discover_hub(b"<rss><channel/></rss>", "https://x"), None);
+    }
+
+    #[tokio::test]
+    async fn subscribes_answers_challenges_and_ingests_pushes() {
+        let hub = MockServer::start(|_| MockResponse::status(202)).await;
+        let settings = WebSubSettings {
+            enabled: true,
+            ..WebSubSettings::default()
+        };
+        let (subscriber, mut pushed) = Subscriber::start(&settings, &NetworkSettings::default())
+            .await
+            .unwrap();
+        let topic = "https://example.com/feed.xml";
+        let advertised = Hub {
+            hub: hub.url("/"),
+            topic: topic.to_string(),
+        };
+
+        subscriber.subscribe(topic, &advertised).await.unwrap();
+        let request = &hub.requests()[0];
+        assert_eq!(request.method, "POST");
+        let form: Vec<(String, String)> = url_pairs(&request.body);
+        let field = |name: &str| {
+            form.iter()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.clone())
+                .unwrap()
+        };
+        assert_eq!(field("hub.mode"), "subscribe");
+        assert_eq!(field("hub.topic"), topic);
+        let secret = field("hub.secret");
+        assert_eq!(secret.len(), 64);
+        let callback = Url::parse(&field("hub.callback")).unwrap();
+        assert_eq!(callback.port(), Some(subscriber.local_addr().port()));
+
+        let client = reqwest::Client::new();
+        let verify = |topic: &str| {
+            let mut url = callback.clone();
+            url.query_pairs_mut()
+                .append_pair("hub.mode", "subscribe")
+                .append_pair("hub.topic", topic)
+                .append_pair("hub.challenge", "c4l1")
+                // Far beyond any sane lease; it is capped, not overflowed.
+                .append_pair("hub.lease_seconds", &u64::MAX.to_string());
+            client.get(url).send()
+        };
+        let forged = verify("https://evil.example.com/").await.unwrap();
+        assert_eq!(forged.status(), StatusCode::NOT_FOUND);
+        let push = client
+            .post(callback.clone())
+            .body("<rss/>")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(push.status(), StatusCode::GONE);
+
+        let verified = verify(topic).await.unwrap();
+        assert_eq!(verified.status(), StatusCode::OK);
+        assert_eq!(verified.text().await.unwrap(), "c4l1");
+        assert!(!subscriber.needs_subscription(topic, Utc::now()));
+
+        let feed = r#"<rss version="2.0"><channel><title>Pushed</title>
+            <item><guid>1</guid><title>Fresh news</title></item>
+        </channel></rss>"#;
+        let sign = |body: &str| {
+            let mut mac = <Hmac<Sha256> as KeyInit>::new_from_slice(secret.as_bytes()).unwrap();
+            mac.update(body.as_bytes());
+            format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+        };
+        let forged_feed = feed.replace("Fresh news", "Forged news");
+        for signature in [None, Some(sign(feed))] {
+            let mut push = client.post(callback.clone()).body(forged_feed.clone());
+            if let Some(signature) = signature {
+                push = push.header("X-Hub-Signature", signature);
+            }
+            assert_eq!(push.send().await.unwrap().status(), StatusCode::OK);
+        }
+        let push = client
+            .post(callback)
+            .header("X-Hub-Signature", sign(feed))
+            .body(feed)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(push.status(), StatusCode::OK);
+
+        let content = pushed.recv().await.unwrap();
+        assert_eq!(content.feed_url, topic);
+        let mut store = EntryStore::in_memory();
+        let added = ingest(&mut store, &content).unwrap();
+        assert_eq!(added[0].title, "Fresh news");
+        assert_eq!(store.entries(topic).len(), 1);
+    }
+
+    #[tokio::test]
+    async fn rejects_overlong_request_lines() {
+        let settings = WebSubSettings {
+            enabled: true,
+            ..WebSubSettings::default()
+        };
+        let (subscriber, _pushed) = Subscriber::start(&settings, &NetworkSettings::default())
+            .await
+            .unwrap();
+        let mut stream = TcpStream::connect(subscriber.local_addr()).await.unwrap();
+        let mut request = b"GET /".to_vec();
+        request.resize(MAX_LINE + 1, b'a');
+        stream.write_all(&request).await.unwrap();
+
+        let mut response = String::new();
+        timeout(Duration::from_secs(5), stream.read_to_string(&mut response))
+            .await
+            .expect("the callback server kept waiting for the end of the line")
+            .unwrap();
+        assert!(response.starts_with("HTTP/1.1 400"), "{response}");
+    }
+
+    fn url_pairs(body: &[u8]) -> Vec<(String, String)> {
+        let query = String::from_utf8_lossy(body);
+        Url::parse(&format!("http://form.invalid/?{query}"))
+            .unwrap()
+            .query_pairs()
+            .into_owned()
+            .collect()
+    }
+}